@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+/// Command-line flags overriding the configuration file and environment.
+///
+/// Meant to be flattened into a binary's own arguments with
+/// `#[command(flatten)]`. Flags are the highest layer, so a flag always wins
+/// over `IGNITE_*` variables and the TOML file.
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigArgs {
+    /// TOML configuration file; IGNITE_CONFIG or ./ignite.toml by default.
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// REST endpoint of a node; repeat for several nodes.
+    #[arg(long = "rest-url", value_name = "URL")]
+    pub rest_urls: Vec<String>,
+
+    /// Cache used as the SQL entry point of the REST client.
+    #[arg(long, value_name = "NAME")]
+    pub rest_cache: Option<String>,
+
+    /// Rows fetched per REST request.
+    #[arg(long)]
+    pub page_size: Option<u32>,
+
+    /// Thin client address of a node; repeat for several nodes.
+    #[arg(long = "thin-addr", value_name = "HOST:PORT")]
+    pub thin_addrs: Vec<String>,
+
+    /// Cache used by the thin client.
+    #[arg(long, value_name = "NAME")]
+    pub thin_cache: Option<String>,
+
+    /// Login for clusters with authentication; the password is read from
+    /// IGNITE_PASSWORD or the configuration file.
+    #[arg(long)]
+    pub login: Option<String>,
+}
@@ -0,0 +1,558 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+use url::Url;
+
+use crate::args::ConfigArgs;
+use crate::error::{ConfigError, ConfigResult};
+
+/// Environment variable naming the configuration file.
+pub const CONFIG_ENV: &str = "IGNITE_CONFIG";
+/// Environment variable with comma-separated REST endpoints.
+pub const REST_URL_ENV: &str = "IGNITE_REST_URL";
+/// Environment variable with comma-separated thin client addresses.
+pub const THIN_ADDRS_ENV: &str = "IGNITE_THIN_ADDRS";
+/// Environment variable holding the Ignite login.
+pub const LOGIN_ENV: &str = "IGNITE_LOGIN";
+/// Environment variable holding the Ignite password.
+pub const PASSWORD_ENV: &str = "IGNITE_PASSWORD";
+
+/// File read when neither `--config` nor [`CONFIG_ENV`] is given, if it
+/// exists.
+pub const DEFAULT_CONFIG_FILE: &str = "ignite.toml";
+/// REST endpoint exposed by the Ignite Jetty connector of the examples'
+/// `docker-compose.yaml`.
+pub const DEFAULT_REST_URL: &str = "http://localhost:8080/ignite";
+pub const DEFAULT_REST_CACHE: &str = "PersonCache";
+pub const DEFAULT_THIN_ADDR: &str = "127.0.0.1:10800";
+pub const DEFAULT_THIN_CACHE: &str = "my_rust_cache";
+
+/// Validated settings of both clients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgniteConfig {
+    pub rest: RestConfig,
+    pub thin: ThinConfig,
+    /// Shared by both clients; `None` for clusters without authentication.
+    pub credentials: Option<Credentials>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestConfig {
+    /// At least one `http` or `https` endpoint.
+    pub urls: Vec<Url>,
+    pub cache: String,
+    /// The client's default when unset.
+    pub page_size: Option<u32>,
+    pub connect_timeout: Option<Duration>,
+    pub timeout: Option<Duration>,
+    /// Only set with `https` endpoints.
+    pub tls: Option<TlsFiles>,
+}
+
+/// PEM files for `https` endpoints. Every path has been checked to exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsFiles {
+    pub ca_certificate: Option<PathBuf>,
+    /// Client certificate and its private key.
+    pub client: Option<(PathBuf, PathBuf)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThinConfig {
+    /// At least one `HOST:PORT` address, tried in order.
+    pub addrs: Vec<String>,
+    pub cache: String,
+}
+
+/// Login and password for clusters with authentication enabled.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    login: String,
+    password: String,
+}
+
+impl Credentials {
+    pub fn new(login: impl Into<String>, password: impl Into<String>) -> Self {
+        Credentials {
+            login: login.into(),
+            password: password.into(),
+        }
+    }
+
+    /// Reads [`LOGIN_ENV`] and [`PASSWORD_ENV`]; `None` unless both are set.
+    pub fn from_env() -> Option<Self> {
+        let login = env::var(LOGIN_ENV).ok()?;
+        let password = env::var(PASSWORD_ENV).ok()?;
+        Some(Credentials::new(login, password))
+    }
+
+    pub fn login(&self) -> &str {
+        &self.login
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("login", &self.login)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+impl IgniteConfig {
+    /// Loads the configuration of this process: the TOML file, then the
+    /// `IGNITE_*` environment variables, then `args`, each layer overriding
+    /// the one before.
+    pub fn load(args: &ConfigArgs) -> ConfigResult<Self> {
+        ConfigLoader::new().args(args.clone()).load()
+    }
+}
+
+/// Layers a TOML file, environment variables and command-line flags into an
+/// [`IgniteConfig`].
+///
+/// ```toml
+/// [rest]
+/// urls = ["https://node1:8443/ignite", "https://node2:8443/ignite"]
+/// cache = "PersonCache"
+/// page_size = 1000
+/// connect_timeout_secs = 5
+/// timeout_secs = 60
+///
+/// [rest.tls]
+/// ca_certificate = "certs/ca.pem"  # relative to this file
+/// client_certificate = "certs/client.pem"
+/// client_key = "certs/client.key"
+///
+/// [thin]
+/// addrs = ["node1:10800", "node2:10800"]
+/// cache = "my_rust_cache"
+///
+/// [credentials]
+/// login = "ignite"
+/// password = "ignite"
+/// ```
+///
+/// Every key is optional. Unknown keys are rejected so that typos do not go
+/// unnoticed.
+#[derive(Debug, Clone)]
+pub struct ConfigLoader {
+    file: Option<PathBuf>,
+    env: HashMap<String, String>,
+    args: ConfigArgs,
+}
+
+impl Default for ConfigLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigLoader {
+    /// Loader reading the environment of this process.
+    pub fn new() -> Self {
+        let env = env::vars_os()
+            .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+            .collect();
+        ConfigLoader {
+            file: None,
+            env,
+            args: ConfigArgs::default(),
+        }
+    }
+
+    /// Configuration file to read unless `--config` names another one.
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.file = Some(path.into());
+        self
+    }
+
+    /// Replaces the environment variables read by the loader.
+    pub fn env<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.env = vars
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect();
+        self
+    }
+
+    pub fn args(mut self, args: ConfigArgs) -> Self {
+        self.args = args;
+        self
+    }
+
+    /// Reads and validates every layer.
+    pub fn load(self) -> ConfigResult<IgniteConfig> {
+        let file = match (&self.args.config, &self.file, self.env.get(CONFIG_ENV)) {
+            (Some(path), _, _) | (None, Some(path), _) => Some(path.clone()),
+            (None, None, Some(path)) => Some(PathBuf::from(path)),
+            (None, None, None) => {
+                Some(PathBuf::from(DEFAULT_CONFIG_FILE)).filter(|path| path.is_file())
+            }
+        };
+        let file = match file {
+            Some(path) => Layer::from_file(&path)?,
+            None => Layer::default(),
+        };
+        let layer = Layer::from_args(&self.args)?
+            .over(Layer::from_env(&self.env)?)
+            .over(file);
+        layer.finish()
+    }
+}
+
+/// Settings given by one source; `None` leaves a setting to lower layers.
+#[derive(Debug, Default)]
+struct Layer {
+    rest_urls: Option<Vec<Url>>,
+    rest_cache: Option<String>,
+    page_size: Option<u32>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    tls: Option<TlsFiles>,
+    thin_addrs: Option<Vec<String>>,
+    thin_cache: Option<String>,
+    login: Option<String>,
+    password: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct FileConfig {
+    #[serde(default)]
+    rest: FileRest,
+    #[serde(default)]
+    thin: FileThin,
+    #[serde(default)]
+    credentials: FileCredentials,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct FileRest {
+    urls: Option<Vec<String>>,
+    cache: Option<String>,
+    page_size: Option<u32>,
+    connect_timeout_secs: Option<u64>,
+    timeout_secs: Option<u64>,
+    tls: Option<FileTls>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct FileTls {
+    ca_certificate: Option<PathBuf>,
+    client_certificate: Option<PathBuf>,
+    client_key: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct FileThin {
+    addrs: Option<Vec<String>>,
+    cache: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct FileCredentials {
+    login: Option<String>,
+    password: Option<String>,
+}
+
+impl Layer {
+    fn from_file(path: &Path) -> ConfigResult<Layer> {
+        let text = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let file: FileConfig = toml::from_str(&text).map_err(|source| ConfigError::Toml {
+            path: path.to_path_buf(),
+            source: Box::new(source),
+        })?;
+        let origin = |key: &str| format!("{}: {key}", path.display());
+        // Relative certificate paths are relative to the file.
+        let base = path.parent().unwrap_or(Path::new(""));
+        let tls_file = |key: &str, value: Option<PathBuf>| {
+            value
+                .map(|value| existing_file(&origin(key), base.join(value)))
+                .transpose()
+        };
+        let tls = match file.rest.tls {
+            None => None,
+            Some(tls) => {
+                let ca_certificate = tls_file("rest.tls.ca_certificate", tls.ca_certificate)?;
+                let certificate = tls_file("rest.tls.client_certificate", tls.client_certificate)?;
+                let key = tls_file("rest.tls.client_key", tls.client_key)?;
+                let client = match (certificate, key) {
+                    (Some(certificate), Some(key)) => Some((certificate, key)),
+                    (None, None) => None,
+                    _ => {
+                        return Err(ConfigError::invalid(
+                            origin("rest.tls"),
+                            "client_certificate and client_key go together",
+                        ));
+                    }
+                };
+                Some(TlsFiles {
+                    ca_certificate,
+                    client,
+                })
+            }
+        };
+        Ok(Layer {
+            rest_urls: file
+                .rest
+                .urls
+                .map(|urls| rest_urls(&origin("rest.urls"), urls))
+                .transpose()?,
+            rest_cache: file
+                .rest
+                .cache
+                .map(|name| cache_name(&origin("rest.cache"), name))
+                .transpose()?,
+            page_size: file
+                .rest
+                .page_size
+                .map(|size| page_size(&origin("rest.page_size"), size))
+                .transpose()?,
+            connect_timeout: file
+                .rest
+                .connect_timeout_secs
+                .map(|secs| timeout(&origin("rest.connect_timeout_secs"), secs))
+                .transpose()?,
+            timeout: file
+                .rest
+                .timeout_secs
+                .map(|secs| timeout(&origin("rest.timeout_secs"), secs))
+                .transpose()?,
+            tls,
+            thin_addrs: file
+                .thin
+                .addrs
+                .map(|addrs| thin_addrs(&origin("thin.addrs"), addrs))
+                .transpose()?,
+            thin_cache: file
+                .thin
+                .cache
+                .map(|name| cache_name(&origin("thin.cache"), name))
+                .transpose()?,
+            login: file.credentials.login,
+            password: file.credentials.password,
+        })
+    }
+
+    fn from_env(env: &HashMap<String, String>) -> ConfigResult<Layer> {
+        let list = |key: &str| env.get(key).map(|value| split_list(value));
+        Ok(Layer {
+            rest_urls: list(REST_URL_ENV)
+                .map(|urls| rest_urls(REST_URL_ENV, urls))
+                .transpose()?,
+            thin_addrs: list(THIN_ADDRS_ENV)
+                .map(|addrs| thin_addrs(THIN_ADDRS_ENV, addrs))
+                .transpose()?,
+            login: env.get(LOGIN_ENV).cloned(),
+            password: env.get(PASSWORD_ENV).cloned(),
+            ..Layer::default()
+        })
+    }
+
+    fn from_args(args: &ConfigArgs) -> ConfigResult<Layer> {
+        let flags = |values: &[String]| {
+            (!values.is_empty())
+                .then(|| values.iter().flat_map(|value| split_list(value)).collect())
+        };
+        Ok(Layer {
+            rest_urls: flags(&args.rest_urls)
+                .map(|urls| rest_urls("--rest-url", urls))
+                .transpose()?,
+            rest_cache: args
+                .rest_cache
+                .clone()
+                .map(|name| cache_name("--rest-cache", name))
+                .transpose()?,
+            page_size: args
+                .page_size
+                .map(|size| page_size("--page-size", size))
+                .transpose()?,
+            thin_addrs: flags(&args.thin_addrs)
+                .map(|addrs| thin_addrs("--thin-addr", addrs))
+                .transpose()?,
+            thin_cache: args
+                .thin_cache
+                .clone()
+                .map(|name| cache_name("--thin-cache", name))
+                .transpose()?,
+            login: args.login.clone(),
+            ..Layer::default()
+        })
+    }
+
+    /// Settings of `self`, falling back to `lower` where unset.
+    fn over(self, lower: Layer) -> Layer {
+        Layer {
+            rest_urls: self.rest_urls.or(lower.rest_urls),
+            rest_cache: self.rest_cache.or(lower.rest_cache),
+            page_size: self.page_size.or(lower.page_size),
+            connect_timeout: self.connect_timeout.or(lower.connect_timeout),
+            timeout: self.timeout.or(lower.timeout),
+            tls: self.tls.or(lower.tls),
+            thin_addrs: self.thin_addrs.or(lower.thin_addrs),
+            thin_cache: self.thin_cache.or(lower.thin_cache),
+            login: self.login.or(lower.login),
+            password: self.password.or(lower.password),
+        }
+    }
+
+    /// Fills in defaults and checks settings that depend on each other.
+    fn finish(self) -> ConfigResult<IgniteConfig> {
+        let urls = match self.rest_urls {
+            Some(urls) => urls,
+            None => vec![Url::parse(DEFAULT_REST_URL).expect("valid default URL")],
+        };
+        if self.tls.is_some()
+            && let Some(url) = urls.iter().find(|url| url.scheme() != "https")
+        {
+            return Err(ConfigError::invalid(
+                "rest.tls",
+                format!("endpoint `{url}` must use https when TLS files are given"),
+            ));
+        }
+        let credentials = match (self.login, self.password) {
+            (Some(login), Some(password)) => Some(Credentials::new(login, password)),
+            (None, None) => None,
+            (Some(_), None) => {
+                return Err(ConfigError::invalid(
+                    "credentials",
+                    format!("a login was given without a password, set {PASSWORD_ENV}"),
+                ));
+            }
+            (None, Some(_)) => {
+                return Err(ConfigError::invalid(
+                    "credentials",
+                    format!("a password was given without a login, set {LOGIN_ENV}"),
+                ));
+            }
+        };
+        Ok(IgniteConfig {
+            rest: RestConfig {
+                urls,
+                cache: self
+                    .rest_cache
+                    .unwrap_or_else(|| DEFAULT_REST_CACHE.to_string()),
+                page_size: self.page_size,
+                connect_timeout: self.connect_timeout,
+                timeout: self.timeout,
+                tls: self.tls,
+            },
+            thin: ThinConfig {
+                addrs: self
+                    .thin_addrs
+                    .unwrap_or_else(|| vec![DEFAULT_THIN_ADDR.to_string()]),
+                cache: self
+                    .thin_cache
+                    .unwrap_or_else(|| DEFAULT_THIN_CACHE.to_string()),
+            },
+            credentials,
+        })
+    }
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn rest_urls(origin: &str, urls: Vec<String>) -> ConfigResult<Vec<Url>> {
+    if urls.is_empty() {
+        return Err(ConfigError::invalid(origin, "at least one URL is needed"));
+    }
+    urls.iter()
+        .map(|url| {
+            let parsed = Url::parse(url).map_err(|err| {
+                ConfigError::invalid(origin, format!("invalid URL `{url}`: {err}"))
+            })?;
+            if !matches!(parsed.scheme(), "http" | "https") || !parsed.has_host() {
+                return Err(ConfigError::invalid(
+                    origin,
+                    format!("`{url}` is not an http or https URL"),
+                ));
+            }
+            Ok(parsed)
+        })
+        .collect()
+}
+
+fn thin_addrs(origin: &str, addrs: Vec<String>) -> ConfigResult<Vec<String>> {
+    if addrs.is_empty() {
+        return Err(ConfigError::invalid(
+            origin,
+            "at least one address is needed",
+        ));
+    }
+    for addr in &addrs {
+        let valid = addr.rsplit_once(':').is_some_and(|(host, port)| {
+            !host.is_empty() && port.parse::<u16>().is_ok_and(|port| port != 0)
+        });
+        if !valid {
+            return Err(ConfigError::invalid(
+                origin,
+                format!("`{addr}` is not a HOST:PORT address"),
+            ));
+        }
+    }
+    Ok(addrs)
+}
+
+fn cache_name(origin: &str, name: String) -> ConfigResult<String> {
+    if name.trim().is_empty() {
+        return Err(ConfigError::invalid(origin, "cache name is empty"));
+    }
+    Ok(name)
+}
+
+fn page_size(origin: &str, size: u32) -> ConfigResult<u32> {
+    if size == 0 {
+        return Err(ConfigError::invalid(origin, "page size must be at least 1"));
+    }
+    Ok(size)
+}
+
+fn timeout(origin: &str, secs: u64) -> ConfigResult<Duration> {
+    if secs == 0 {
+        return Err(ConfigError::invalid(
+            origin,
+            "timeout must be at least 1 second",
+        ));
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+fn existing_file(origin: &str, path: PathBuf) -> ConfigResult<PathBuf> {
+    if !path.is_file() {
+        return Err(ConfigError::invalid(
+            origin,
+            format!("no such file `{}`", path.display()),
+        ));
+    }
+    Ok(path)
+}
@@ -0,0 +1,13 @@
+mod args;
+mod config;
+mod error;
+#[cfg(feature = "thin")]
+mod thin;
+
+pub use args::ConfigArgs;
+pub use config::{
+    CONFIG_ENV, ConfigLoader, Credentials, DEFAULT_CONFIG_FILE, DEFAULT_REST_CACHE,
+    DEFAULT_REST_URL, DEFAULT_THIN_ADDR, DEFAULT_THIN_CACHE, IgniteConfig, LOGIN_ENV, PASSWORD_ENV,
+    REST_URL_ENV, RestConfig, THIN_ADDRS_ENV, ThinConfig, TlsFiles,
+};
+pub use error::{ConfigError, ConfigResult};
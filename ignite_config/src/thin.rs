@@ -0,0 +1,38 @@
+use ignite_rs::error::IgniteResult;
+use ignite_rs::{Client, ClientConfig};
+
+use crate::config::IgniteConfig;
+
+impl IgniteConfig {
+    /// One thin client configuration per address of `[thin]`, in order,
+    /// carrying the credentials.
+    pub fn thin_client_configs(&self) -> Vec<ClientConfig> {
+        self.thin
+            .addrs
+            .iter()
+            .map(|addr| {
+                let mut config = ClientConfig::new(addr);
+                if let Some(credentials) = &self.credentials {
+                    config.username = Some(credentials.login().to_string());
+                    config.password = Some(credentials.password().to_string());
+                }
+                config
+            })
+            .collect()
+    }
+
+    /// Connects the thin client to the first address that accepts it, or
+    /// returns the error of the last one.
+    pub fn connect_thin(&self) -> IgniteResult<Client> {
+        let mut configs = self.thin_client_configs().into_iter();
+        let first = configs.next().expect("validated configs have an address");
+        let mut result = ignite_rs::new_client(first);
+        for config in configs {
+            if result.is_ok() {
+                break;
+            }
+            result = ignite_rs::new_client(config);
+        }
+        result
+    }
+}
@@ -0,0 +1,215 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use ignite_config::{
+    ConfigArgs, ConfigError, ConfigLoader, Credentials, DEFAULT_REST_CACHE, DEFAULT_REST_URL,
+    DEFAULT_THIN_ADDR, DEFAULT_THIN_CACHE, IgniteConfig, TlsFiles,
+};
+
+/// Directory in the system temp directory, unique to this test process and
+/// `name`, holding `files`.
+fn scratch(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ignite-config-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, contents) in files {
+        std::fs::write(dir.join(file), contents).unwrap();
+    }
+    dir
+}
+
+fn loader(env: &[(&str, &str)]) -> ConfigLoader {
+    ConfigLoader::new().env(env.iter().copied())
+}
+
+fn invalid_origin(err: ConfigError) -> String {
+    match err {
+        ConfigError::Invalid { origin, .. } => origin,
+        err => panic!("{err:?}"),
+    }
+}
+
+#[test]
+fn defaults_match_the_examples() {
+    let config = loader(&[]).load().unwrap();
+
+    assert_eq!(config.rest.urls[0].as_str(), DEFAULT_REST_URL);
+    assert_eq!(config.rest.cache, DEFAULT_REST_CACHE);
+    assert_eq!(config.rest.page_size, None);
+    assert_eq!(config.rest.tls, None);
+    assert_eq!(config.thin.addrs, [DEFAULT_THIN_ADDR]);
+    assert_eq!(config.thin.cache, DEFAULT_THIN_CACHE);
+    assert_eq!(config.credentials, None);
+}
+
+#[test]
+fn flags_override_environment_overrides_file() {
+    let dir = scratch(
+        "layers",
+        &[(
+            "ignite.toml",
+            r#"
+            [rest]
+            urls = ["http://file:8080/ignite"]
+            cache = "FileCache"
+            page_size = 500
+            timeout_secs = 30
+
+            [thin]
+            addrs = ["file:10800"]
+            cache = "file_cache"
+
+            [credentials]
+            login = "file-user"
+            password = "file-secret"
+            "#,
+        )],
+    );
+    let file = dir.join("ignite.toml");
+    let env = [
+        ("IGNITE_CONFIG", file.to_str().unwrap()),
+        (
+            "IGNITE_REST_URL",
+            "http://env1:8080/ignite, http://env2:8080/ignite",
+        ),
+        ("IGNITE_THIN_ADDRS", "env1:10800,env2:10800"),
+        ("IGNITE_PASSWORD", "env-secret"),
+    ];
+
+    let config = loader(&env).load().unwrap();
+    let urls: Vec<_> = config.rest.urls.iter().map(|url| url.as_str()).collect();
+    assert_eq!(urls, ["http://env1:8080/ignite", "http://env2:8080/ignite"]);
+    assert_eq!(config.rest.cache, "FileCache");
+    assert_eq!(config.rest.page_size, Some(500));
+    assert_eq!(config.rest.timeout, Some(Duration::from_secs(30)));
+    assert_eq!(config.thin.addrs, ["env1:10800", "env2:10800"]);
+    assert_eq!(config.thin.cache, "file_cache");
+    assert_eq!(
+        config.credentials,
+        Some(Credentials::new("file-user", "env-secret"))
+    );
+
+    let args = ConfigArgs {
+        rest_urls: vec!["https://flag:8443/ignite".into()],
+        rest_cache: Some("FlagCache".into()),
+        thin_addrs: vec!["flag:10800".into()],
+        login: Some("flag-user".into()),
+        ..ConfigArgs::default()
+    };
+    let config = loader(&env).args(args).load().unwrap();
+    assert_eq!(config.rest.urls[0].as_str(), "https://flag:8443/ignite");
+    assert_eq!(config.rest.cache, "FlagCache");
+    assert_eq!(config.thin.addrs, ["flag:10800"]);
+    assert_eq!(
+        config.credentials,
+        Some(Credentials::new("flag-user", "env-secret"))
+    );
+    assert!(!format!("{config:?}").contains("env-secret"));
+}
+
+#[test]
+fn tls_paths_are_relative_to_the_file() {
+    let dir = scratch(
+        "tls",
+        &[
+            (
+                "ignite.toml",
+                r#"
+                [rest]
+                urls = ["https://node:8443/ignite"]
+                [rest.tls]
+                ca_certificate = "ca.pem"
+                client_certificate = "client.pem"
+                client_key = "client.key"
+                "#,
+            ),
+            ("ca.pem", ""),
+            ("client.pem", ""),
+            ("client.key", ""),
+        ],
+    );
+
+    let config = loader(&[]).file(dir.join("ignite.toml")).load().unwrap();
+
+    assert_eq!(
+        config.rest.tls,
+        Some(TlsFiles {
+            ca_certificate: Some(dir.join("ca.pem")),
+            client: Some((dir.join("client.pem"), dir.join("client.key"))),
+        })
+    );
+    let err = loader(&[("IGNITE_REST_URL", "http://node:8080/ignite")])
+        .file(dir.join("ignite.toml"))
+        .load()
+        .unwrap_err();
+    assert_eq!(invalid_origin(err), "rest.tls");
+}
+
+#[test]
+fn invalid_settings_name_their_origin() {
+    let err = loader(&[("IGNITE_THIN_ADDRS", "127.0.0.1:10800,localhost")])
+        .load()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "IGNITE_THIN_ADDRS: `localhost` is not a HOST:PORT address"
+    );
+
+    let err = loader(&[("IGNITE_REST_URL", "ftp://node/ignite")])
+        .load()
+        .unwrap_err();
+    assert_eq!(invalid_origin(err), "IGNITE_REST_URL");
+
+    let args = ConfigArgs {
+        page_size: Some(0),
+        ..ConfigArgs::default()
+    };
+    let err = loader(&[]).args(args).load().unwrap_err();
+    assert_eq!(invalid_origin(err), "--page-size");
+
+    let err = loader(&[("IGNITE_LOGIN", "ignite")]).load().unwrap_err();
+    assert_eq!(invalid_origin(err), "credentials");
+
+    let dir = scratch(
+        "invalid",
+        &[
+            ("typo.toml", "[rest]\nurl = \"http://node:8080/ignite\"\n"),
+            ("empty.toml", "[thin]\ncache = \" \"\n"),
+            ("missing.toml", "[rest.tls]\nca_certificate = \"ca.pem\"\n"),
+        ],
+    );
+    let err = loader(&[]).file(dir.join("typo.toml")).load().unwrap_err();
+    assert!(matches!(err, ConfigError::Toml { .. }), "{err:?}");
+    let err = loader(&[]).file(dir.join("empty.toml")).load().unwrap_err();
+    assert!(invalid_origin(err).ends_with("empty.toml: thin.cache"));
+    let err = loader(&[])
+        .file(dir.join("missing.toml"))
+        .load()
+        .unwrap_err();
+    assert!(invalid_origin(err).ends_with("missing.toml: rest.tls.ca_certificate"));
+
+    let args = ConfigArgs {
+        config: Some(dir.join("absent.toml")),
+        ..ConfigArgs::default()
+    };
+    let err = loader(&[]).args(args).load().unwrap_err();
+    assert!(matches!(err, ConfigError::Io { .. }), "{err:?}");
+}
+
+#[test]
+fn thin_client_configs_carry_addresses_and_credentials() {
+    let config: IgniteConfig = loader(&[
+        ("IGNITE_THIN_ADDRS", "node1:10800,node2:10800"),
+        ("IGNITE_LOGIN", "ignite"),
+        ("IGNITE_PASSWORD", "secret"),
+    ])
+    .load()
+    .unwrap();
+
+    let configs = config.thin_client_configs();
+    let addrs: Vec<_> = configs.iter().map(|config| config.addr.as_str()).collect();
+    assert_eq!(addrs, ["node1:10800", "node2:10800"]);
+    assert!(configs.iter().all(|config| {
+        config.username.as_deref() == Some("ignite") && config.password.as_deref() == Some("secret")
+    }));
+}
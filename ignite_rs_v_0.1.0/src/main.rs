@@ -1,4 +1,6 @@
-use ignite_rs::{ClientConfig, Ignite}; // Import Ignite TRAIT
+use clap::Parser; // Command-line flags
+use ignite_config::{ConfigArgs, IgniteConfig}; // Shared configuration of both examples
+use ignite_rs::Ignite; // Import Ignite TRAIT
 use ignite_rs::cache::Cache; // Import Cache struct
 use ignite_rs_derive::IgniteObj; // Import the derive macro
 use serde::{Serialize, Deserialize}; // Still needed for general struct serialization
@@ -12,27 +14,42 @@ struct MyValue {
     name: String,
 }
 
+/// Puts and gets a few values through the Apache Ignite thin client.
+#[derive(Parser)]
+struct Args {
+    #[command(flatten)]
+    config: ConfigArgs,
+}
+
 fn main() {
-    // 1. Create a client configuration
-    let client_config = ClientConfig::new("127.0.0.1:10800"); // Connect to localhost:10800
+    // 1. Load the configuration: ignite.toml, then IGNITE_THIN_ADDRS and
+    // IGNITE_LOGIN / IGNITE_PASSWORD, then the flags. Invalid settings stop
+    // the example before it connects.
+    let args = Args::parse();
+    let config = IgniteConfig::load(&args.config).unwrap_or_else(|err| {
+        eprintln!("Invalid configuration: {}", err);
+        std::process::exit(2);
+    });
 
     // 2. Create the actual client connection.
-    // In 0.1.0, this is done via the synchronous function `ignite_rs::new_client`.
-    // The `new_client` function returns a `Client` struct, which implements the `Ignite` trait.
+    // `connect_thin` builds one `ClientConfig` per configured address and calls
+    // the synchronous `ignite_rs::new_client` until a node accepts it.
+    // The returned `Client` struct implements the `Ignite` trait.
     // By having `use ignite_rs::Ignite;` at the top, the methods from this trait
     // (like get_or_create_cache and destroy_cache) become available on the `client` instance.
-    println!("Connecting to Apache Ignite...");
-    let mut client = ignite_rs::new_client(client_config)
+    println!("Connecting to Apache Ignite at {}...", config.thin.addrs.join(", "));
+    let mut client = config
+        .connect_thin()
         .expect("Failed to connect to Apache Ignite. Is the cluster running?");
 
     println!("Successfully connected to Apache Ignite!");
 
-    let cache_name = "my_rust_cache";
+    let cache_name = config.thin.cache.as_str();
 
     // 3. Get or create a typed cache.
     // The `Cache` type needs to be explicitly typed with your Key and Value types.
     println!("Getting or creating cache '{}'...", cache_name);
-    let mut cache: Cache<i32, MyValue> = client // Use 'client' here (returned by new_client)
+    let cache: Cache<i32, MyValue> = client // Use 'client' here (returned by new_client)
         .get_or_create_cache(cache_name) // This method is provided by the Ignite trait
         .expect("Failed to get or create cache");
 
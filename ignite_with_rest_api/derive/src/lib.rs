@@ -0,0 +1,405 @@
+use std::collections::BTreeMap;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::{
+    Data, DeriveInput, Error, Fields, GenericArgument, Ident, LitInt, LitStr, PathArguments,
+    Result, Type, parse_macro_input,
+};
+
+/// Implements `IgniteTable` for a struct with named fields, mapping each
+/// field to a column of a SQL table.
+///
+/// Struct attributes, all optional:
+///
+/// - `#[ignite(table = "SCHEMA.NAME")]`: table name, the struct name by
+///   default.
+/// - `#[ignite(template = "..", backups = N, atomicity = "..",
+///   write_synchronization_mode = "..", cache_group = "..", cache_name = "..",
+///   data_region = "..", key_type = "..", value_type = "..")]`: parameters of
+///   the `WITH` clause of `CREATE TABLE`.
+///
+/// Field attributes:
+///
+/// - `#[ignite(primary_key)]`: part of the primary key; at least one field
+///   needs it. Several make a composite key.
+/// - `#[ignite(column = "NAME")]`: column name, the field name by default.
+///   Names that are SQL keywords, such as `key`, `value` or `order`, are
+///   quoted in upper case in the generated statements.
+/// - `#[ignite(length = N)]`: `VARCHAR(N)` instead of `VARCHAR`.
+/// - `#[ignite(sql_type = "..")]`: column type, required for field types
+///   the macro does not know.
+/// - `#[ignite(index)]` or `#[ignite(index = "NAME")]`: indexes the column;
+///   columns sharing an index name form a composite index.
+/// - `#[ignite(affinity_key)]`: collocates rows by this primary key column.
+///
+/// Column types follow the field types: integers, `f32`/`f64`, `bool`,
+/// `String`, `Decimal`, `NaiveDate`, `NaiveTime`, `NaiveDateTime`, `Uuid` and
+/// `Vec<u8>`. `Option` fields are nullable, all others `NOT NULL`.
+#[proc_macro_derive(IgniteTable, attributes(ignite))]
+pub fn derive_ignite_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// `WITH` parameters set by struct attributes of the same name.
+const TABLE_OPTIONS: &[&str] = &[
+    "template",
+    "backups",
+    "atomicity",
+    "write_synchronization_mode",
+    "cache_group",
+    "cache_name",
+    "data_region",
+    "key_type",
+    "value_type",
+];
+
+struct Column {
+    field: Ident,
+    ty: Type,
+    name: String,
+    sql_type: String,
+    primary_key: bool,
+    nullable: bool,
+    affinity_key: bool,
+    index: Option<String>,
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let ident = &input.ident;
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            ident,
+            "IgniteTable can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            ident,
+            "IgniteTable needs a struct with named fields",
+        ));
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "IgniteTable cannot be generic",
+        ));
+    }
+
+    let mut table = ident.to_string();
+    let mut options = Vec::new();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("ignite"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("table") {
+                let name = meta.value()?.parse::<LitStr>()?;
+                if !is_table_name(&name.value()) {
+                    return Err(Error::new_spanned(name, "expected TABLE or SCHEMA.TABLE"));
+                }
+                table = name.value();
+                return Ok(());
+            }
+            let Some(key) = TABLE_OPTIONS.iter().find(|key| meta.path.is_ident(key)) else {
+                return Err(meta.error("unknown table attribute"));
+            };
+            let value = if *key == "backups" {
+                meta.value()?
+                    .parse::<LitInt>()?
+                    .base10_parse::<u32>()?
+                    .to_string()
+            } else {
+                option_value(&meta)?
+            };
+            options.push((key.to_uppercase(), value));
+            Ok(())
+        })?;
+    }
+
+    let mut columns = Vec::new();
+    for field in &fields.named {
+        let field_ident = field.ident.clone().expect("named field");
+        let mut column = None;
+        let mut length = None;
+        let mut sql_type = None;
+        let mut primary_key = false;
+        let mut affinity_key = false;
+        let mut index = None;
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("ignite"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("primary_key") {
+                    primary_key = true;
+                } else if meta.path.is_ident("affinity_key") {
+                    affinity_key = true;
+                } else if meta.path.is_ident("column") {
+                    let name = meta.value()?.parse::<LitStr>()?;
+                    if !is_identifier(&name.value()) {
+                        return Err(Error::new_spanned(name, "expected an unquoted identifier"));
+                    }
+                    column = Some(name.value());
+                } else if meta.path.is_ident("length") {
+                    length = Some(meta.value()?.parse::<LitInt>()?.base10_parse::<u32>()?);
+                } else if meta.path.is_ident("sql_type") {
+                    sql_type = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("index") {
+                    index = Some(match meta.value() {
+                        Ok(value) => {
+                            let name = value.parse::<LitStr>()?;
+                            if !is_identifier(&name.value()) {
+                                return Err(Error::new_spanned(name, "expected an identifier"));
+                            }
+                            Some(name.value())
+                        }
+                        Err(_) => None,
+                    });
+                } else {
+                    return Err(meta.error("unknown field attribute"));
+                }
+                Ok(())
+            })?;
+        }
+
+        let (inner, nullable) = match option_inner(&field.ty) {
+            Some(inner) => (inner, true),
+            None => (&field.ty, false),
+        };
+        let sql_type = match (sql_type, length) {
+            (Some(_), Some(_)) => {
+                return Err(Error::new_spanned(field, "use either sql_type or length"));
+            }
+            (Some(sql_type), None) => sql_type,
+            (None, length) => {
+                let Some(sql_type) = sql_type_of(inner) else {
+                    return Err(Error::new_spanned(
+                        &field.ty,
+                        "unknown SQL type, set it with #[ignite(sql_type = \"...\")]",
+                    ));
+                };
+                match length {
+                    Some(length) if sql_type == "VARCHAR" => format!("VARCHAR({length})"),
+                    Some(_) => {
+                        return Err(Error::new_spanned(field, "length only applies to strings"));
+                    }
+                    None => sql_type.to_string(),
+                }
+            }
+        };
+        if primary_key && nullable {
+            return Err(Error::new_spanned(
+                &field.ty,
+                "primary key fields cannot be Option",
+            ));
+        }
+        if affinity_key && !primary_key {
+            return Err(Error::new_spanned(
+                field,
+                "the affinity key must be a primary key field",
+            ));
+        }
+        columns.push(Column {
+            name: column.unwrap_or_else(|| field_ident.to_string()),
+            field: field_ident,
+            ty: field.ty.clone(),
+            sql_type,
+            primary_key,
+            nullable,
+            affinity_key,
+            index: index.map(|name| name.unwrap_or_default()),
+        });
+    }
+
+    let key: Vec<_> = columns.iter().filter(|column| column.primary_key).collect();
+    if key.is_empty() {
+        return Err(Error::new_spanned(
+            ident,
+            "mark a field with #[ignite(primary_key)]",
+        ));
+    }
+    if key.len() == columns.len() {
+        return Err(Error::new_spanned(
+            ident,
+            "the table needs a column outside the primary key",
+        ));
+    }
+    let affinity: Vec<_> = columns
+        .iter()
+        .filter(|column| column.affinity_key)
+        .collect();
+    match affinity.as_slice() {
+        [] => {}
+        [column] => options.push(("AFFINITY_KEY".to_string(), column.name.clone())),
+        [_, second, ..] => {
+            return Err(Error::new_spanned(
+                &second.field,
+                "only one field can be the affinity key",
+            ));
+        }
+    }
+
+    // Unnamed indexes cover one column; named ones collect every column
+    // sharing the name.
+    let table_short = table.rsplit('.').next().unwrap_or(&table).to_string();
+    let mut indexes: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for column in &columns {
+        match column.index.as_deref() {
+            None => {}
+            Some("") => indexes
+                .entry(format!("{table_short}_{}_idx", column.name))
+                .or_default()
+                .push(&column.name),
+            Some(name) => indexes
+                .entry(name.to_string())
+                .or_default()
+                .push(&column.name),
+        }
+    }
+
+    let column_defs = columns.iter().map(|column| {
+        let Column {
+            name,
+            sql_type,
+            primary_key,
+            nullable,
+            ..
+        } = column;
+        let field = column.field.to_string();
+        quote! {
+            ::ignite_with_rest_api::ColumnDef {
+                name: #name,
+                field: #field,
+                sql_type: #sql_type,
+                primary_key: #primary_key,
+                nullable: #nullable,
+            }
+        }
+    });
+    let index_defs = indexes.iter().map(|(name, columns)| {
+        quote! { ::ignite_with_rest_api::IndexDef { name: #name, columns: &[#(#columns),*] } }
+    });
+    let option_defs = options.iter().map(|(key, value)| quote! { (#key, #value) });
+
+    let fields = columns.iter().map(|column| &column.field);
+    let key_type = key.iter().map(|column| &column.ty);
+    let (key_type, key_values) = if key.len() == 1 {
+        (
+            quote! { #(#key_type)* },
+            quote! { key as &dyn ::ignite_with_rest_api::ToSqlArg },
+        )
+    } else {
+        let indexes = (0..key.len()).map(syn::Index::from);
+        (
+            quote! { (#(#key_type),*) },
+            quote! { #(&key.#indexes as &dyn ::ignite_with_rest_api::ToSqlArg),* },
+        )
+    };
+
+    Ok(quote! {
+        impl ::ignite_with_rest_api::IgniteTable for #ident {
+            type Key = #key_type;
+
+            const TABLE: ::ignite_with_rest_api::TableDef = ::ignite_with_rest_api::TableDef {
+                name: #table,
+                columns: &[#(#column_defs),*],
+                indexes: &[#(#index_defs),*],
+                options: &[#(#option_defs),*],
+            };
+
+            fn values(&self) -> ::std::vec::Vec<&dyn ::ignite_with_rest_api::ToSqlArg> {
+                ::std::vec![#(&self.#fields as &dyn ::ignite_with_rest_api::ToSqlArg),*]
+            }
+
+            fn key_values(
+                key: &Self::Key,
+            ) -> ::std::vec::Vec<&dyn ::ignite_with_rest_api::ToSqlArg> {
+                ::std::vec![#key_values]
+            }
+        }
+    })
+}
+
+/// Value of a string option; `,` and `"` would break the `WITH` clause.
+fn option_value(meta: &ParseNestedMeta) -> Result<String> {
+    let value = meta.value()?.parse::<LitStr>()?;
+    if value.value().is_empty() || value.value().contains([',', '"', '=']) {
+        return Err(Error::new_spanned(
+            value,
+            "option values cannot contain `,`, `\"` or `=`",
+        ));
+    }
+    Ok(value.value())
+}
+
+/// `T` of an `Option<T>` field.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let segment = last_segment(ty)?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    single_argument(&segment.arguments)
+}
+
+fn sql_type_of(ty: &Type) -> Option<&'static str> {
+    let segment = last_segment(ty)?;
+    let sql_type = match segment.ident.to_string().as_str() {
+        "bool" => "BOOLEAN",
+        "i8" => "TINYINT",
+        "i16" => "SMALLINT",
+        "i32" => "INT",
+        "i64" => "BIGINT",
+        "f32" => "REAL",
+        "f64" => "DOUBLE",
+        "String" => "VARCHAR",
+        "Decimal" => "DECIMAL",
+        "NaiveDate" => "DATE",
+        "NaiveTime" => "TIME",
+        "NaiveDateTime" => "TIMESTAMP",
+        "Uuid" => "UUID",
+        "Vec" => match single_argument(&segment.arguments).and_then(last_segment) {
+            Some(inner) if inner.ident == "u8" => "VARBINARY",
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(sql_type)
+}
+
+fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+        _ => None,
+    }
+}
+
+fn single_argument(arguments: &PathArguments) -> Option<&Type> {
+    let PathArguments::AngleBracketed(arguments) = arguments else {
+        return None;
+    };
+    match arguments.args.first() {
+        Some(GenericArgument::Type(ty)) if arguments.args.len() == 1 => Some(ty),
+        _ => None,
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+fn is_table_name(name: &str) -> bool {
+    name.split('.').count() <= 2 && name.split('.').all(is_identifier)
+}
@@ -0,0 +1 @@
+CREATE TABLE Person (id INT PRIMARY KEY, name VARCHAR(50), age INT);
@@ -0,0 +1,250 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::error::{IgniteRestError, Result};
+use crate::sql::placeholders;
+
+/// A value bound to a `?` placeholder of a SQL query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlArg {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Decimal(Decimal),
+    String(String),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Timestamp(NaiveDateTime),
+    Uuid(Uuid),
+    Bytes(Vec<u8>),
+}
+
+/// Types that can be bound to a query placeholder.
+///
+/// `Sync` is required so that queries holding their arguments can run on
+/// any task.
+pub trait ToSqlArg: Sync {
+    fn to_sql_arg(&self) -> SqlArg;
+}
+
+impl ToSqlArg for SqlArg {
+    fn to_sql_arg(&self) -> SqlArg {
+        self.clone()
+    }
+}
+
+impl<T: ToSqlArg + ?Sized> ToSqlArg for &T {
+    fn to_sql_arg(&self) -> SqlArg {
+        (**self).to_sql_arg()
+    }
+}
+
+impl<T: ToSqlArg> ToSqlArg for Option<T> {
+    fn to_sql_arg(&self) -> SqlArg {
+        self.as_ref().map_or(SqlArg::Null, ToSqlArg::to_sql_arg)
+    }
+}
+
+macro_rules! int_args {
+    ($($ty:ty),*) => {
+        $(impl ToSqlArg for $ty {
+            fn to_sql_arg(&self) -> SqlArg {
+                SqlArg::Int(i64::from(*self))
+            }
+        })*
+    };
+}
+
+int_args!(i8, i16, i32, i64, u8, u16, u32);
+
+impl ToSqlArg for f32 {
+    fn to_sql_arg(&self) -> SqlArg {
+        SqlArg::Float(f64::from(*self))
+    }
+}
+
+impl ToSqlArg for f64 {
+    fn to_sql_arg(&self) -> SqlArg {
+        SqlArg::Float(*self)
+    }
+}
+
+impl ToSqlArg for Decimal {
+    fn to_sql_arg(&self) -> SqlArg {
+        SqlArg::Decimal(*self)
+    }
+}
+
+impl ToSqlArg for bool {
+    fn to_sql_arg(&self) -> SqlArg {
+        SqlArg::Bool(*self)
+    }
+}
+
+impl ToSqlArg for str {
+    fn to_sql_arg(&self) -> SqlArg {
+        SqlArg::String(self.to_string())
+    }
+}
+
+impl ToSqlArg for String {
+    fn to_sql_arg(&self) -> SqlArg {
+        SqlArg::String(self.clone())
+    }
+}
+
+impl ToSqlArg for NaiveDate {
+    fn to_sql_arg(&self) -> SqlArg {
+        SqlArg::Date(*self)
+    }
+}
+
+impl ToSqlArg for NaiveTime {
+    fn to_sql_arg(&self) -> SqlArg {
+        SqlArg::Time(*self)
+    }
+}
+
+impl ToSqlArg for NaiveDateTime {
+    fn to_sql_arg(&self) -> SqlArg {
+        SqlArg::Timestamp(*self)
+    }
+}
+
+/// Bound as a UTC timestamp; `java.sql.Timestamp` carries no zone.
+impl<Tz: TimeZone> ToSqlArg for DateTime<Tz>
+where
+    DateTime<Tz>: Sync,
+{
+    fn to_sql_arg(&self) -> SqlArg {
+        SqlArg::Timestamp(self.naive_utc())
+    }
+}
+
+impl ToSqlArg for Uuid {
+    fn to_sql_arg(&self) -> SqlArg {
+        SqlArg::Uuid(*self)
+    }
+}
+
+impl ToSqlArg for [u8] {
+    fn to_sql_arg(&self) -> SqlArg {
+        SqlArg::Bytes(self.to_vec())
+    }
+}
+
+impl ToSqlArg for Vec<u8> {
+    fn to_sql_arg(&self) -> SqlArg {
+        SqlArg::Bytes(self.clone())
+    }
+}
+
+impl SqlArg {
+    /// SQL type the placeholder is cast to, so that the server does not
+    /// treat the argument as the string it travels as.
+    fn cast_type(&self) -> Option<&'static str> {
+        match self {
+            SqlArg::Null | SqlArg::String(_) => None,
+            SqlArg::Bool(_) => Some("BOOLEAN"),
+            SqlArg::Int(_) => Some("BIGINT"),
+            SqlArg::Float(_) => Some("DOUBLE"),
+            SqlArg::Decimal(_) => Some("DECIMAL"),
+            SqlArg::Date(_) => Some("DATE"),
+            SqlArg::Time(_) => Some("TIME"),
+            SqlArg::Timestamp(_) => Some("TIMESTAMP"),
+            SqlArg::Uuid(_) => Some("UUID"),
+            // H2 reads a string cast to BINARY as hex digits.
+            SqlArg::Bytes(_) => Some("BINARY"),
+        }
+    }
+
+    /// Text sent as the `argN` parameter; `None` for NULL.
+    fn encode(&self) -> Option<String> {
+        match self {
+            SqlArg::Null => None,
+            SqlArg::Bool(value) => Some(value.to_string()),
+            SqlArg::Int(value) => Some(value.to_string()),
+            SqlArg::Float(value) if value.is_nan() => Some("NaN".to_string()),
+            SqlArg::Float(value) if value.is_infinite() => {
+                Some(if *value > 0.0 { "Infinity" } else { "-Infinity" }.to_string())
+            }
+            SqlArg::Float(value) => Some(value.to_string()),
+            SqlArg::Decimal(value) => Some(value.to_string()),
+            SqlArg::String(value) => Some(value.clone()),
+            SqlArg::Date(value) => Some(value.format("%Y-%m-%d").to_string()),
+            SqlArg::Time(value) => Some(value.format("%H:%M:%S%.f").to_string()),
+            SqlArg::Timestamp(value) => Some(value.format("%Y-%m-%d %H:%M:%S%.f").to_string()),
+            SqlArg::Uuid(value) => Some(value.hyphenated().to_string()),
+            SqlArg::Bytes(value) => Some(value.iter().map(|byte| format!("{byte:02X}")).collect()),
+        }
+    }
+}
+
+/// Query text and positional `argN` values ready for `qryfldexe`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BoundQuery {
+    pub(crate) sql: String,
+    pub(crate) args: Vec<String>,
+}
+
+impl BoundQuery {
+    /// `arg1..argN` parameter names for [`BoundQuery::arg_params`].
+    pub(crate) fn arg_names(&self) -> Vec<String> {
+        (1..=self.args.len()).map(|n| format!("arg{n}")).collect()
+    }
+
+    /// Pairs the argument values with `names`.
+    pub(crate) fn arg_params<'a>(
+        &'a self,
+        names: &'a [String],
+    ) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+        names.iter().map(String::as_str).zip(self.args.iter().map(String::as_str))
+    }
+}
+
+/// Binds `args` to the `?` placeholders of `sql`.
+///
+/// The REST API passes every argument as a string, so typed arguments get
+/// their placeholder wrapped in a `CAST`, and NULL (which has no string
+/// form) is written into the query as a literal.
+pub(crate) fn bind(sql: &str, args: &[&dyn ToSqlArg]) -> Result<BoundQuery> {
+    let positions = placeholders(sql);
+    if positions.len() != args.len() {
+        return Err(IgniteRestError::Encode(format!(
+            "query has {} placeholder(s) but {} argument(s) were given",
+            positions.len(),
+            args.len()
+        )));
+    }
+
+    let mut bound = BoundQuery {
+        sql: String::with_capacity(sql.len()),
+        args: Vec::with_capacity(args.len()),
+    };
+    let mut copied = 0;
+    for (position, arg) in positions.into_iter().zip(args) {
+        bound.sql.push_str(&sql[copied..position]);
+        copied = position + 1;
+
+        let arg = arg.to_sql_arg();
+        match (arg.encode(), arg.cast_type()) {
+            (None, _) => bound.sql.push_str("NULL"),
+            (Some(value), cast) => {
+                match cast {
+                    Some(cast) => {
+                        bound.sql.push_str("CAST(? AS ");
+                        bound.sql.push_str(cast);
+                        bound.sql.push(')');
+                    }
+                    None => bound.sql.push('?'),
+                }
+                bound.args.push(value);
+            }
+        }
+    }
+    bound.sql.push_str(&sql[copied..]);
+
+    Ok(bound)
+}
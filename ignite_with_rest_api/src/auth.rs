@@ -0,0 +1,28 @@
+use std::sync::RwLock;
+
+use tokio::sync::Mutex;
+
+pub use ignite_config::{Credentials, LOGIN_ENV, PASSWORD_ENV};
+
+/// Session token shared by all clones of a client.
+#[derive(Debug, Default)]
+pub(crate) struct Session {
+    token: RwLock<Option<String>>,
+    /// Serializes logins so concurrent tasks that all see an expired token
+    /// authenticate once instead of each on their own.
+    login: Mutex<()>,
+}
+
+impl Session {
+    pub(crate) fn token(&self) -> Option<String> {
+        self.token.read().unwrap_or_else(|err| err.into_inner()).clone()
+    }
+
+    pub(crate) fn set_token(&self, token: Option<String>) {
+        *self.token.write().unwrap_or_else(|err| err.into_inner()) = token;
+    }
+
+    pub(crate) fn login_lock(&self) -> &Mutex<()> {
+        &self.login
+    }
+}
@@ -0,0 +1,289 @@
+//! Key-value and cache management commands, stored in two SQLite tables.
+
+use rusqlite::{Connection, OptionalExtension, params};
+use serde_json::{Map, Value, json};
+
+use crate::{Emulator, Request};
+
+pub const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS __caches (
+        name TEXT PRIMARY KEY,
+        mode TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS __entries (
+        cache_name TEXT NOT NULL,
+        key TEXT NOT NULL,
+        value TEXT NOT NULL,
+        value_type TEXT,
+        PRIMARY KEY (cache_name, key)
+    );
+";
+
+impl Emulator {
+    pub fn getorcreate(&self, request: &Request) -> Result<Value, String> {
+        let name = request.require("cacheName")?;
+        let mode = match request.get("templateName") {
+            Some(template) if template.eq_ignore_ascii_case("replicated") => "REPLICATED",
+            _ => "PARTITIONED",
+        };
+        self.db()
+            .execute(
+                "INSERT OR IGNORE INTO __caches (name, mode) VALUES (?1, ?2)",
+                params![name, mode],
+            )
+            .map_err(|err| err.to_string())?;
+        Ok(Value::Null)
+    }
+
+    pub fn destroycache(&self, request: &Request) -> Result<Value, String> {
+        let name = request.require("cacheName")?;
+        let db = self.db();
+        db.execute("DELETE FROM __entries WHERE cache_name = ?1", [name])
+            .and_then(|_| db.execute("DELETE FROM __caches WHERE name = ?1", [name]))
+            .map_err(|err| err.to_string())?;
+        Ok(Value::Null)
+    }
+
+    /// Caches as listed by `top`.
+    pub fn caches(&self) -> Vec<Value> {
+        let db = self.db();
+        let Ok(mut statement) = db.prepare("SELECT name, mode FROM __caches ORDER BY name") else {
+            return Vec::new();
+        };
+        statement
+            .query_map([], |row| {
+                Ok(json!({
+                    "name": row.get::<_, String>(0)?,
+                    "mode": row.get::<_, String>(1)?,
+                    "sqlSchema": null,
+                }))
+            })
+            .and_then(Iterator::collect)
+            .unwrap_or_default()
+    }
+
+    pub fn kv(&self, command: &str, request: &Request) -> Result<Value, String> {
+        let cache = Cache::open(self, request)?;
+        match command {
+            "get" => cache.get(request.require("key")?),
+            "put" => {
+                cache.put(request.require("key")?, request.require("val")?)?;
+                Ok(Value::Bool(true))
+            }
+            "putifabsent" => Ok(Value::Bool(
+                cache.put_if_absent(request.require("key")?, request.require("val")?)?,
+            )),
+            "getandput" => cache.get_and_put(request.require("key")?, request.require("val")?),
+            "replace" => Ok(Value::Bool(cache.replace(
+                request.require("key")?,
+                request.require("val")?,
+                None,
+            )?)),
+            "cas" => Ok(Value::Bool(cache.replace(
+                request.require("key")?,
+                request.require("val")?,
+                Some(request.require("val2")?),
+            )?)),
+            "getall" => {
+                let mut entries = Map::new();
+                for key in request.numbered("k") {
+                    let value = cache.get(key)?;
+                    if !value.is_null() {
+                        entries.insert(key.to_string(), value);
+                    }
+                }
+                Ok(Value::Object(entries))
+            }
+            "putall" => {
+                let keys = request.numbered("k");
+                let values = request.numbered("v");
+                if keys.len() != values.len() {
+                    return Err("Number of keys and values must match".to_string());
+                }
+                for (key, value) in keys.into_iter().zip(values) {
+                    cache.put(key, value)?;
+                }
+                Ok(Value::Bool(true))
+            }
+            "rmv" => Ok(Value::Bool(
+                cache.remove(Some(request.require("key")?))? > 0,
+            )),
+            "rmvall" => {
+                let keys = request.numbered("k");
+                if keys.is_empty() {
+                    cache.remove(None)?;
+                }
+                for key in keys {
+                    cache.remove(Some(key))?;
+                }
+                Ok(Value::Bool(true))
+            }
+            "containskey" => Ok(Value::Bool(cache.raw(request.require("key")?)?.is_some())),
+            "containskeys" => {
+                for key in request.numbered("k") {
+                    if cache.raw(key)?.is_none() {
+                        return Ok(Value::Bool(false));
+                    }
+                }
+                Ok(Value::Bool(true))
+            }
+            "size" => cache.size().map(Value::from),
+            "metadata" => Ok(Value::Null),
+            "cache" => Ok(json!({
+                "reads": 0, "writes": 0, "hits": 0, "misses": 0, "commits": 0, "rollbacks": 0,
+            })),
+            _ => Err(format!(
+                "Failed to find registered handler for command: {command}"
+            )),
+        }
+    }
+}
+
+/// One cache, with the `valueType` of the request that addressed it.
+struct Cache<'a> {
+    emulator: &'a Emulator,
+    name: &'a str,
+    value_type: Option<&'a str>,
+}
+
+impl<'a> Cache<'a> {
+    fn open(emulator: &'a Emulator, request: &'a Request) -> Result<Self, String> {
+        let name = request.require("cacheName")?;
+        let exists = emulator
+            .db()
+            .query_row("SELECT 1 FROM __caches WHERE name = ?1", [name], |_| Ok(()))
+            .optional()
+            .map_err(|err| err.to_string())?
+            .is_some();
+        if !exists {
+            return Err(format!("Failed to find cache for given cache name: {name}"));
+        }
+        Ok(Cache {
+            emulator,
+            name,
+            value_type: request.get("valueType"),
+        })
+    }
+
+    fn raw(&self, key: &str) -> Result<Option<String>, String> {
+        self.emulator
+            .db()
+            .query_row(
+                "SELECT value FROM __entries WHERE cache_name = ?1 AND key = ?2",
+                params![self.name, key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|err| err.to_string())
+    }
+
+    /// The stored value, typed as it was written: values put with a numeric
+    /// or boolean `valueType` come back as JSON numbers or booleans, all
+    /// others as strings.
+    fn get(&self, key: &str) -> Result<Value, String> {
+        self.get_in(&self.emulator.db(), key)
+    }
+
+    fn get_in(&self, db: &Connection, key: &str) -> Result<Value, String> {
+        let entry: Option<(String, Option<String>)> = db
+            .query_row(
+                "SELECT value, value_type FROM __entries WHERE cache_name = ?1 AND key = ?2",
+                params![self.name, key],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(|err| err.to_string())?;
+        let Some((value, value_type)) = entry else {
+            return Ok(Value::Null);
+        };
+        let typed = match value_type
+            .as_deref()
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("int" | "integer" | "long" | "short" | "byte" | "float" | "double") => {
+                serde_json::from_str::<serde_json::Number>(&value)
+                    .ok()
+                    .map(Value::Number)
+            }
+            Some("boolean" | "bool") => value.parse().ok().map(Value::Bool),
+            _ => None,
+        };
+        Ok(typed.unwrap_or(Value::String(value)))
+    }
+
+    fn put(&self, key: &str, value: &str) -> Result<(), String> {
+        self.put_in(&self.emulator.db(), key, value)
+    }
+
+    fn put_in(&self, db: &Connection, key: &str, value: &str) -> Result<(), String> {
+        db.execute(
+            "INSERT OR REPLACE INTO __entries (cache_name, key, value, value_type) \
+             VALUES (?1, ?2, ?3, ?4)",
+            params![self.name, key, value, self.value_type],
+        )
+        .map_err(|err| err.to_string())?;
+        Ok(())
+    }
+
+    /// Stores `value` unless `key` has one; returns whether it was stored.
+    fn put_if_absent(&self, key: &str, value: &str) -> Result<bool, String> {
+        let inserted = self
+            .emulator
+            .db()
+            .execute(
+                "INSERT OR IGNORE INTO __entries (cache_name, key, value, value_type) \
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![self.name, key, value, self.value_type],
+            )
+            .map_err(|err| err.to_string())?;
+        Ok(inserted > 0)
+    }
+
+    /// Stores `value` and returns the previous one, under a single lock.
+    fn get_and_put(&self, key: &str, value: &str) -> Result<Value, String> {
+        let db = self.emulator.db();
+        let previous = self.get_in(&db, key)?;
+        self.put_in(&db, key, value)?;
+        Ok(previous)
+    }
+
+    /// Overwrites the value of an existing `key`, only if it is currently
+    /// `expected` when given; returns whether it was written.
+    fn replace(&self, key: &str, value: &str, expected: Option<&str>) -> Result<bool, String> {
+        let updated = self
+            .emulator
+            .db()
+            .execute(
+                "UPDATE __entries SET value = ?3, value_type = ?4 \
+                 WHERE cache_name = ?1 AND key = ?2 AND (?5 IS NULL OR value = ?5)",
+                params![self.name, key, value, self.value_type, expected],
+            )
+            .map_err(|err| err.to_string())?;
+        Ok(updated > 0)
+    }
+
+    /// Removes `key`, or every entry when `None`; returns the number removed.
+    fn remove(&self, key: Option<&str>) -> Result<usize, String> {
+        let db = self.emulator.db();
+        let removed = match key {
+            Some(key) => db.execute(
+                "DELETE FROM __entries WHERE cache_name = ?1 AND key = ?2",
+                params![self.name, key],
+            ),
+            None => db.execute("DELETE FROM __entries WHERE cache_name = ?1", [self.name]),
+        };
+        removed.map_err(|err| err.to_string())
+    }
+
+    fn size(&self) -> Result<i64, String> {
+        self.emulator
+            .db()
+            .query_row(
+                "SELECT COUNT(*) FROM __entries WHERE cache_name = ?1",
+                [self.name],
+                |row| row.get(0),
+            )
+            .map_err(|err| err.to_string())
+    }
+}
@@ -0,0 +1,232 @@
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{RawQuery, State};
+use axum::http::{Method, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::any;
+use clap::Parser;
+use ignite_with_rest_api::{STATUS_FAILED, STATUS_SUCCESS};
+use rusqlite::Connection;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+mod kv;
+mod sql;
+
+/// Local stand-in for the Ignite REST connector, backed by SQLite.
+///
+/// Serves `/ignite?cmd=...` with the same JSON envelopes as Ignite for the
+/// SQL fields query commands (`qryfldexe`, `qryfetch`, `qrycls`), the
+/// key-value commands and the cluster commands the client uses, so examples
+/// and applications run without a JVM.
+#[derive(Parser, Debug)]
+#[command(name = "ignite-emulator")]
+struct Args {
+    /// Address to listen on; port 0 picks a free one.
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+
+    /// SQLite database file; data is kept in memory when omitted.
+    #[arg(long)]
+    db: Option<PathBuf>,
+
+    /// Cache to create at startup, as if by `getorcreate`. Repeatable.
+    #[arg(long = "cache")]
+    caches: Vec<String>,
+}
+
+/// Version reported by `version`.
+const VERSION: &str = "2.16.0";
+
+pub struct Emulator {
+    db: Mutex<Connection>,
+    open_queries: Mutex<sql::OpenQueries>,
+    state: Mutex<&'static str>,
+    node_id: Uuid,
+    port: u16,
+}
+
+impl Emulator {
+    fn db(&self) -> MutexGuard<'_, Connection> {
+        lock(&self.db)
+    }
+
+    fn open_queries(&self) -> MutexGuard<'_, sql::OpenQueries> {
+        lock(&self.open_queries)
+    }
+
+    fn handle(&self, command: &str, request: &Request) -> Result<Value, String> {
+        match command {
+            "version" => Ok(json!(VERSION)),
+            "probe" => Ok(json!("grid has started")),
+            "authenticate" => Ok(Value::Bool(true)),
+            "top" => Ok(json!([self.node(request)])),
+            "node" => Ok(self.node(request)),
+            "state" => Ok(json!(*lock(&self.state))),
+            "setstate" => self.set_state(request),
+            "qryfldexe" => self.qryfldexe(request),
+            "qryfetch" => self.qryfetch(request),
+            "qrycls" => self.qrycls(request),
+            "getorcreate" => self.getorcreate(request),
+            "destroycache" => self.destroycache(request),
+            command => self.kv(command, request),
+        }
+    }
+
+    fn set_state(&self, request: &Request) -> Result<Value, String> {
+        let state = match request.require("state")?.to_ascii_uppercase().as_str() {
+            "ACTIVE" => "ACTIVE",
+            "ACTIVE_READ_ONLY" => "ACTIVE_READ_ONLY",
+            "INACTIVE" if request.get("force") == Some("true") => "INACTIVE",
+            "INACTIVE" => {
+                return Err("Deactivation stopped. Deactivation clears in-memory caches \
+                            (without persistence) including the system caches. \
+                            To deactivate the cluster pass '--force' flag."
+                    .to_string());
+            }
+            other => return Err(format!("Unknown cluster state: {other}")),
+        };
+        *lock(&self.state) = state;
+        Ok(Value::Null)
+    }
+
+    /// The single node of this "cluster".
+    fn node(&self, request: &Request) -> Value {
+        let attributes = (request.get("attr") == Some("true"))
+            .then(|| json!({ "org.apache.ignite.build.ver": VERSION, "emulator": true }));
+        let metrics = (request.get("mtr") == Some("true")).then(|| {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_millis() as i64);
+            json!({ "lastUpdateTime": now, "totalCpus": 1 })
+        });
+        json!({
+            "nodeId": self.node_id.to_string(),
+            "consistentId": "ignite-emulator",
+            "order": 1,
+            "tcpHostNames": ["localhost"],
+            "tcpAddresses": ["127.0.0.1"],
+            "tcpPort": self.port,
+            "caches": self.caches(),
+            "defaultCacheMode": "PARTITIONED",
+            "attributes": attributes,
+            "metrics": metrics,
+        })
+    }
+}
+
+/// Parameters of one request, from the query string and a form body.
+pub struct Request {
+    params: Vec<(String, String)>,
+}
+
+impl Request {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn require(&self, name: &str) -> Result<&str, String> {
+        self.get(name)
+            .ok_or_else(|| format!("Failed to find mandatory parameter in request: {name}"))
+    }
+
+    /// Values of `{prefix}1`, `{prefix}2`, ... up to the first gap.
+    fn numbered(&self, prefix: &str) -> Vec<&str> {
+        (1..)
+            .map_while(|index| self.get(&format!("{prefix}{index}")))
+            .collect()
+    }
+}
+
+async fn handle(
+    State(emulator): State<Arc<Emulator>>,
+    method: Method,
+    RawQuery(query): RawQuery,
+    body: Bytes,
+) -> Response {
+    let mut params: Vec<(String, String)> =
+        url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+            .into_owned()
+            .collect();
+    if method == Method::POST {
+        params.extend(url::form_urlencoded::parse(&body).into_owned());
+    }
+    let request = Request { params };
+
+    // SQLite calls block, so they run off the async workers.
+    let reply = tokio::task::spawn_blocking(move || match request.get("cmd") {
+        Some(command) => emulator.handle(command, &request),
+        None => Err("Failed to find mandatory parameter in request: cmd".to_string()),
+    })
+    .await
+    .unwrap_or_else(|err| Err(format!("Emulator failure: {err}")));
+
+    let body = match reply {
+        Ok(response) => json!({
+            "successStatus": STATUS_SUCCESS,
+            "response": response,
+            "error": null,
+            "sessionToken": null,
+        }),
+        Err(error) => json!({
+            "successStatus": STATUS_FAILED,
+            "response": null,
+            "error": error,
+            "sessionToken": null,
+        }),
+    };
+    (
+        [(header::CONTENT_TYPE, "application/json")],
+        body.to_string(),
+    )
+        .into_response()
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let db = match &args.db {
+        Some(path) => Connection::open(path)?,
+        None => Connection::open_in_memory()?,
+    };
+    db.execute_batch(kv::SCHEMA)?;
+
+    let listener = tokio::net::TcpListener::bind(args.listen).await?;
+    let addr = listener.local_addr()?;
+    let node_id = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+    let emulator = Arc::new(Emulator {
+        db: Mutex::new(db),
+        open_queries: Mutex::default(),
+        state: Mutex::new("ACTIVE"),
+        node_id: Uuid::from_u128(node_id),
+        port: addr.port(),
+    });
+    for cache in &args.caches {
+        let request = Request {
+            params: vec![("cacheName".to_string(), cache.clone())],
+        };
+        emulator.getorcreate(&request).map_err(anyhow::Error::msg)?;
+    }
+
+    let app = Router::new()
+        .route("/ignite", any(handle))
+        .with_state(emulator);
+    // Tests and scripts read this line to find the port.
+    println!("Ignite REST emulator listening on http://{addr}/ignite");
+    std::io::stdout().flush()?;
+    axum::serve(listener, app).await?;
+    Ok(())
+}
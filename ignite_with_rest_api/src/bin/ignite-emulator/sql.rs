@@ -0,0 +1,467 @@
+//! `qryfldexe`, `qryfetch` and `qrycls` on top of SQLite.
+
+use std::collections::{HashMap, VecDeque};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{Connection, params_from_iter};
+use serde_json::{Value, json};
+
+use crate::{Emulator, Request};
+
+/// Rows of a query that did not fit in its first page.
+#[derive(Default)]
+pub struct OpenQueries {
+    next_id: i64,
+    queries: HashMap<i64, VecDeque<Vec<Value>>>,
+}
+
+impl Emulator {
+    pub fn qryfldexe(&self, request: &Request) -> Result<Value, String> {
+        let query = request.require("qry")?;
+        let page_size = request.page_size()?;
+        let (sql, casts) = translate(query);
+        let args = casts
+            .iter()
+            .enumerate()
+            .map(|(index, cast)| {
+                bind_arg(request.get(&format!("arg{}", index + 1)), cast.as_deref())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (fields, rows) = {
+            let db = self.db();
+            run(&db, &sql, args).map_err(|err| sql_error(&err))?
+        };
+
+        let mut rows: VecDeque<_> = rows.into();
+        let first: Vec<_> = rows.drain(..page_size.min(rows.len())).collect();
+        let mut open = self.open_queries();
+        open.next_id += 1;
+        let query_id = open.next_id;
+        let last = rows.is_empty();
+        if !last {
+            open.queries.insert(query_id, rows);
+        }
+
+        Ok(json!({
+            "fieldsMetadata": fields,
+            "items": first,
+            "queryId": query_id,
+            "last": last,
+        }))
+    }
+
+    pub fn qryfetch(&self, request: &Request) -> Result<Value, String> {
+        let query_id = request.query_id()?;
+        let page_size = request.page_size()?;
+        let mut open = self.open_queries();
+        let rows = open
+            .queries
+            .get_mut(&query_id)
+            .ok_or_else(|| format!("Failed to find query with ID: {query_id}"))?;
+
+        let page: Vec<_> = rows.drain(..page_size.min(rows.len())).collect();
+        let last = rows.is_empty();
+        if last {
+            open.queries.remove(&query_id);
+        }
+
+        Ok(json!({
+            "fieldsMetadata": null,
+            "items": page,
+            "queryId": query_id,
+            "last": last,
+        }))
+    }
+
+    pub fn qrycls(&self, request: &Request) -> Result<Value, String> {
+        let query_id = request.query_id()?;
+        self.open_queries().queries.remove(&query_id);
+        Ok(Value::Bool(true))
+    }
+}
+
+impl Request {
+    fn page_size(&self) -> Result<usize, String> {
+        match self.get("pageSize") {
+            None => Ok(1024),
+            Some(size) => size
+                .parse::<usize>()
+                .ok()
+                .filter(|size| *size > 0)
+                .ok_or_else(|| format!("Invalid pageSize: {size}")),
+        }
+    }
+
+    fn query_id(&self) -> Result<i64, String> {
+        let id = self.require("qryId")?;
+        id.parse().map_err(|_| format!("Invalid qryId: {id}"))
+    }
+}
+
+/// Runs one statement. Queries return their rows; other statements return
+/// the single `UPDATED` count Ignite answers DML and DDL with.
+fn run(
+    db: &Connection,
+    sql: &str,
+    args: Vec<SqlValue>,
+) -> rusqlite::Result<(Vec<Value>, Vec<Vec<Value>>)> {
+    let mut statement = db.prepare(sql)?;
+    if statement.column_count() == 0 {
+        let updated = statement.execute(params_from_iter(args))?;
+        let is_dml = matches!(first_keyword(sql).as_str(), "INSERT" | "UPDATE" | "DELETE");
+        let updated = if is_dml { updated } else { 0 };
+        return Ok((
+            vec![field("UPDATED", "java.lang.Long")],
+            vec![vec![json!(updated)]],
+        ));
+    }
+
+    let names: Vec<String> = statement
+        .column_names()
+        .into_iter()
+        .map(str::to_uppercase)
+        .collect();
+    let declared: Vec<Option<String>> = statement
+        .columns()
+        .iter()
+        .map(|column| column.decl_type().map(java_type_of_declared))
+        .collect();
+
+    let mut cells: Vec<Vec<SqlValue>> = Vec::new();
+    let mut rows = statement.query(params_from_iter(args))?;
+    while let Some(row) = rows.next()? {
+        let row = (0..names.len())
+            .map(|index| row.get_ref(index).map(SqlValue::from))
+            .collect::<rusqlite::Result<_>>()?;
+        cells.push(row);
+    }
+
+    // Expressions have no declared type; like Ignite, report the type of
+    // the values they produce.
+    let types: Vec<String> = declared
+        .into_iter()
+        .enumerate()
+        .map(|(index, declared)| {
+            declared.unwrap_or_else(|| {
+                let value = cells
+                    .iter()
+                    .map(|row| &row[index])
+                    .find(|value| **value != SqlValue::Null);
+                java_type_of_value(value).to_string()
+            })
+        })
+        .collect();
+
+    let fields = names
+        .iter()
+        .zip(&types)
+        .map(|(name, java_type)| field(name, java_type))
+        .collect();
+    let rows = cells
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .zip(&types)
+                .map(|(value, java_type)| to_json(value, java_type))
+                .collect()
+        })
+        .collect();
+    Ok((fields, rows))
+}
+
+fn field(name: &str, java_type: &str) -> Value {
+    json!({ "fieldName": name, "fieldTypeName": java_type })
+}
+
+fn first_keyword(sql: &str) -> String {
+    sql.split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase()
+}
+
+/// Java class Ignite reports for a column declared as `declared`, e.g.
+/// `VARCHAR(50)`.
+fn java_type_of_declared(declared: &str) -> String {
+    let base = declared
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_uppercase();
+    let java_type = match base.as_str() {
+        "INT" | "INTEGER" | "MEDIUMINT" | "INT4" | "SIGNED" => "java.lang.Integer",
+        "BIGINT" | "INT8" | "LONG" => "java.lang.Long",
+        "SMALLINT" | "INT2" | "YEAR" => "java.lang.Short",
+        "TINYINT" => "java.lang.Byte",
+        "BOOLEAN" | "BOOL" | "BIT" => "java.lang.Boolean",
+        "REAL" | "FLOAT4" => "java.lang.Float",
+        "DOUBLE" | "DOUBLE PRECISION" | "FLOAT" | "FLOAT8" => "java.lang.Double",
+        "DECIMAL" | "NUMERIC" | "NUMBER" | "DEC" => "java.math.BigDecimal",
+        "DATE" => "java.sql.Date",
+        "TIME" => "java.sql.Time",
+        "TIMESTAMP" | "DATETIME" | "SMALLDATETIME" => "java.sql.Timestamp",
+        "UUID" => "java.util.UUID",
+        "BINARY" | "VARBINARY" | "BLOB" | "BYTEA" | "LONGVARBINARY" | "RAW" => "[B",
+        "OTHER" => "java.lang.Object",
+        _ => "java.lang.String",
+    };
+    java_type.to_string()
+}
+
+fn java_type_of_value(value: Option<&SqlValue>) -> &'static str {
+    match value {
+        Some(SqlValue::Integer(_)) => "java.lang.Long",
+        Some(SqlValue::Real(_)) => "java.lang.Double",
+        Some(SqlValue::Text(_)) => "java.lang.String",
+        Some(SqlValue::Blob(_)) => "[B",
+        Some(SqlValue::Null) | None => "java.lang.Object",
+    }
+}
+
+/// Renders a cell the way Ignite's REST object mapper writes `java_type`.
+fn to_json(value: SqlValue, java_type: &str) -> Value {
+    let text = match value {
+        SqlValue::Null => return Value::Null,
+        SqlValue::Blob(bytes) => return Value::String(BASE64.encode(bytes)),
+        SqlValue::Integer(number) => match java_type {
+            "java.lang.Boolean" => return Value::Bool(number != 0),
+            "java.lang.String" | "java.util.UUID" => return Value::String(number.to_string()),
+            _ => return json!(number),
+        },
+        SqlValue::Real(number) => match java_type {
+            "java.lang.String" => return Value::String(number.to_string()),
+            _ => return json!(number),
+        },
+        SqlValue::Text(text) => text,
+    };
+
+    match java_type {
+        "java.lang.Integer"
+        | "java.lang.Long"
+        | "java.lang.Short"
+        | "java.lang.Byte"
+        | "java.lang.Float"
+        | "java.lang.Double"
+        | "java.math.BigDecimal" => {
+            // Text in a numeric column, e.g. a DECIMAL that SQLite kept
+            // exact; the `exact-decimal` feature keeps every digit.
+            serde_json::from_str::<serde_json::Number>(text.trim())
+                .map(Value::Number)
+                .unwrap_or(Value::String(text))
+        }
+        "java.lang.Boolean" => match text.to_ascii_lowercase().as_str() {
+            "true" | "1" => Value::Bool(true),
+            "false" | "0" => Value::Bool(false),
+            _ => Value::String(text),
+        },
+        // java.sql.Date and java.sql.Time are written with toString();
+        // Timestamp goes through the mapper's US medium date format.
+        "java.sql.Date" => Value::String(
+            parse_timestamp(&text)
+                .map(|time| time.date().to_string())
+                .unwrap_or(text),
+        ),
+        "java.sql.Time" => Value::String(
+            NaiveTime::parse_from_str(&text, "%H:%M:%S%.f")
+                .map(|time| time.format("%H:%M:%S").to_string())
+                .unwrap_or(text),
+        ),
+        "java.sql.Timestamp" => Value::String(
+            parse_timestamp(&text)
+                .map(|time| time.format("%b %-d, %Y %-I:%M:%S %p").to_string())
+                .unwrap_or(text),
+        ),
+        _ => Value::String(text),
+    }
+}
+
+fn parse_timestamp(text: &str) -> Option<NaiveDateTime> {
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+}
+
+/// Converts an `argN` value according to the `CAST(? AS type)` the client
+/// wrapped its placeholder in.
+fn bind_arg(arg: Option<&str>, cast: Option<&str>) -> Result<SqlValue, String> {
+    let Some(arg) = arg else {
+        return Ok(SqlValue::Null);
+    };
+    let invalid = || {
+        format!(
+            "Failed to convert argument `{arg}` to {}",
+            cast.unwrap_or("VARCHAR")
+        )
+    };
+    let value = match cast {
+        Some("BIGINT" | "INT" | "INTEGER" | "SMALLINT" | "TINYINT") => {
+            SqlValue::Integer(arg.parse().map_err(|_| invalid())?)
+        }
+        Some("DOUBLE" | "REAL" | "FLOAT") => SqlValue::Real(match arg {
+            "NaN" => f64::NAN,
+            "Infinity" => f64::INFINITY,
+            "-Infinity" => f64::NEG_INFINITY,
+            _ => arg.parse().map_err(|_| invalid())?,
+        }),
+        Some("BOOLEAN") => SqlValue::Integer(match arg.to_ascii_lowercase().as_str() {
+            "true" | "1" => 1,
+            "false" | "0" => 0,
+            _ => return Err(invalid()),
+        }),
+        Some("BINARY" | "VARBINARY") => SqlValue::Blob(decode_hex(arg).ok_or_else(invalid)?),
+        _ => SqlValue::Text(arg.to_string()),
+    };
+    Ok(value)
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+/// Rewrites Ignite SQL into SQLite SQL.
+///
+/// Returns the statement and, per `?` placeholder, the type of the
+/// `CAST(? AS type)` around it, which is replaced by a bare `?` so SQLite's
+/// own type affinity applies to the bound value.
+fn translate(sql: &str) -> (String, Vec<Option<String>>) {
+    let mut out = String::with_capacity(sql.len());
+    let mut casts = Vec::new();
+    let bytes = sql.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        let rest = &sql[index..];
+        let skip = match bytes[index] {
+            quote @ (b'\'' | b'"') => rest[1..]
+                .find(quote as char)
+                .map_or(rest.len(), |end| end + 2),
+            b'-' if rest.starts_with("--") => rest.find('\n').unwrap_or(rest.len()),
+            b'/' if rest.starts_with("/*") => rest.find("*/").map_or(rest.len(), |end| end + 2),
+            b'?' => {
+                casts.push(None);
+                1
+            }
+            b'C' | b'c' => match parse_cast(rest) {
+                Some((len, cast)) => {
+                    casts.push(Some(cast));
+                    out.push('?');
+                    index += len;
+                    continue;
+                }
+                None => 1,
+            },
+            _ => rest.chars().next().map_or(1, char::len_utf8),
+        };
+        out.push_str(&rest[..skip]);
+        index += skip;
+    }
+
+    let out = strip_table_options(&out);
+    let out = match first_keyword(&out).as_str() {
+        // SQLite's upsert spelling of Ignite's MERGE INTO ... VALUES.
+        "MERGE" => format!("INSERT OR REPLACE{}", &out.trim_start()["MERGE".len()..]),
+        _ => out,
+    };
+    (out, casts)
+}
+
+/// Matches `CAST(? AS type)` at the start of `sql`, returning its length and
+/// the upper-cased type.
+fn parse_cast(sql: &str) -> Option<(usize, String)> {
+    let head = sql.get(..4)?;
+    if !head.eq_ignore_ascii_case("CAST") {
+        return None;
+    }
+    let rest = sql[4..]
+        .trim_start()
+        .strip_prefix('(')?
+        .trim_start()
+        .strip_prefix('?')?;
+    let rest = rest.trim_start();
+    if !rest.get(..2)?.eq_ignore_ascii_case("AS") {
+        return None;
+    }
+    let rest = rest[2..].trim_start();
+    let end = rest.find(')')?;
+    let cast = rest[..end].trim().to_ascii_uppercase();
+    let cast = cast
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let len = sql.len() - rest.len() + end + 1;
+    Some((len, cast))
+}
+
+/// Drops the `WITH "template=..."` clause that follows a CREATE TABLE column
+/// list; SQLite has no equivalent.
+fn strip_table_options(sql: &str) -> String {
+    if !sql
+        .trim_start()
+        .to_ascii_uppercase()
+        .starts_with("CREATE TABLE")
+    {
+        return sql.to_string();
+    }
+    match sql.rfind(')') {
+        Some(end)
+            if sql[end + 1..]
+                .trim_start()
+                .to_ascii_uppercase()
+                .starts_with("WITH") =>
+        {
+            sql[..=end].to_string()
+        }
+        _ => sql.to_string(),
+    }
+}
+
+/// Rephrases a SQLite error in the words of Ignite's, so clients classify
+/// it the same way.
+fn sql_error(err: &rusqlite::Error) -> String {
+    let message = err.to_string();
+    let quoted = |name: &str| name.trim().trim_matches('"').to_uppercase();
+    let ignite = if let Some(rest) = message.strip_prefix("table ")
+        && let Some(table) = rest.strip_suffix(" already exists")
+    {
+        format!("Table already exists: {}", quoted(table))
+    } else if let Some(table) = message.strip_prefix("no such table: ") {
+        format!("Table \"{}\" not found", quoted(table))
+    } else if let Some(rest) = message.strip_prefix("index ")
+        && let Some(index) = rest.strip_suffix(" already exists")
+    {
+        format!("Index already exists: {}", quoted(index))
+    } else if let Some(index) = message.strip_prefix("no such index: ") {
+        format!("Index doesn't exist: {}", quoted(index))
+    } else if let Some(column) = message.strip_prefix("no such column: ") {
+        format!("Column \"{}\" not found", quoted(column))
+    } else if message.starts_with("UNIQUE constraint failed") {
+        "Duplicate key during INSERT".to_string()
+    } else if let Some(column) = message.strip_prefix("NOT NULL constraint failed: ") {
+        format!(
+            "Null value is not allowed for column \"{}\"",
+            quoted(column)
+        )
+    } else if message.contains("syntax error") || message.contains("incomplete input") {
+        "Syntax error in SQL statement".to_string()
+    } else {
+        return format!("Failed to execute query: {message}");
+    };
+    format!("Failed to execute query: {ignite} [sqlite: {message}]")
+}
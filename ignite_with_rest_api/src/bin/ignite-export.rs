@@ -0,0 +1,80 @@
+use std::io;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::{ArgGroup, Parser};
+use ignite_config::{ConfigArgs, IgniteConfig};
+use ignite_with_rest_api::{
+    DEFAULT_ROW_GROUP_SIZE, ExportFormat, Exporter, IgniteRestClientBuilder,
+};
+
+/// Writes a table, query or cache of an Apache Ignite cluster to a CSV,
+/// JSON Lines or Parquet file over the REST API.
+///
+/// All pages of the result are streamed to the file; the file only appears
+/// once the export is complete.
+#[derive(Parser, Debug)]
+#[command(name = "ignite-export")]
+#[command(group(ArgGroup::new("source").required(true).args(["table", "query", "kv_cache"])))]
+struct Args {
+    /// File to write, or `-` for standard output.
+    output: PathBuf,
+
+    /// SQL table to export, optionally `SCHEMA.TABLE`.
+    #[arg(long)]
+    table: Option<String>,
+
+    /// SELECT statement to export.
+    #[arg(long)]
+    query: Option<String>,
+
+    /// Key-value cache to export with a scan.
+    #[arg(long)]
+    kv_cache: Option<String>,
+
+    /// csv, jsonl or parquet; taken from the file extension by default.
+    #[arg(long)]
+    format: Option<ExportFormat>,
+
+    /// Rows per Parquet row group.
+    #[arg(long, default_value_t = DEFAULT_ROW_GROUP_SIZE)]
+    row_group_size: usize,
+
+    #[command(flatten)]
+    config: ConfigArgs,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let stdout = args.output.as_os_str() == "-";
+    let format = match args.format {
+        Some(format) => format,
+        None if stdout => ExportFormat::Csv,
+        None => ExportFormat::from_path(&args.output).with_context(|| {
+            format!(
+                "cannot tell the format of `{}`, pass --format",
+                args.output.display()
+            )
+        })?,
+    };
+    let config = IgniteConfig::load(&args.config)?;
+    let client = IgniteRestClientBuilder::from_config(&config).build()?;
+
+    let exporter = match (&args.table, &args.query, &args.kv_cache) {
+        (Some(table), _, _) => Exporter::table(client, table)?,
+        (None, Some(query), _) => Exporter::query(client, query, &[]),
+        (None, None, Some(name)) => Exporter::cache(client.cache(name)),
+        (None, None, None) => unreachable!("clap requires a source"),
+    }
+    .format(format)
+    .row_group_size(args.row_group_size);
+
+    let written = if stdout {
+        exporter.export_writer(io::stdout()).await?
+    } else {
+        exporter.export_file(&args.output).await?
+    };
+    eprintln!("Exported {written} rows.");
+    Ok(())
+}
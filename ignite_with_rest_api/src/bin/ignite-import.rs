@@ -0,0 +1,151 @@
+use std::path::PathBuf;
+
+use anyhow::bail;
+use clap::{ArgGroup, Parser};
+use ignite_config::{ConfigArgs, IgniteConfig};
+use ignite_with_rest_api::{
+    CsvImporter, DEFAULT_IMPORT_BATCH_SIZE, IgniteRestClientBuilder, ImportError, ImportMode,
+};
+
+/// Loads a CSV file into an Apache Ignite table or cache over the REST API.
+///
+/// The header names the column of each field. Records that cannot be
+/// converted or are refused by the cluster are written to the rejects file;
+/// an interrupted import prints the line to pass to `--resume-from`.
+#[derive(Parser, Debug)]
+#[command(name = "ignite-import")]
+#[command(group(ArgGroup::new("target").required(true).args(["table", "kv_cache"])))]
+struct Args {
+    /// CSV file to import.
+    file: PathBuf,
+
+    /// SQL table to write to, optionally `SCHEMA.TABLE`.
+    #[arg(long)]
+    table: Option<String>,
+
+    /// Key-value cache to write to with `putall`.
+    #[arg(long, requires_all = ["key_column", "value_column"])]
+    kv_cache: Option<String>,
+
+    /// CSV column holding the cache keys.
+    #[arg(long)]
+    key_column: Option<String>,
+
+    /// CSV column holding the cache values.
+    #[arg(long)]
+    value_column: Option<String>,
+
+    /// Java type of the cache keys, e.g. `int`.
+    #[arg(long)]
+    key_type: Option<String>,
+
+    /// Java type of the cache values.
+    #[arg(long)]
+    value_type: Option<String>,
+
+    /// Overwrite existing rows with MERGE instead of rejecting them.
+    #[arg(long)]
+    merge: bool,
+
+    /// Rows per request.
+    #[arg(long, default_value_t = DEFAULT_IMPORT_BATCH_SIZE)]
+    batch_size: usize,
+
+    /// Field separator.
+    #[arg(long, default_value_t = ',')]
+    delimiter: char,
+
+    /// Field text that stands for NULL.
+    #[arg(long, default_value = "")]
+    null: String,
+
+    /// Fill a column from a differently named field, as `HEADER=COLUMN`.
+    #[arg(long = "map", value_name = "HEADER=COLUMN")]
+    map: Vec<String>,
+
+    /// Ignore fields whose header matches no column.
+    #[arg(long)]
+    skip_unmapped: bool,
+
+    /// File receiving rejected records with their line and reason.
+    #[arg(long)]
+    rejects: Option<PathBuf>,
+
+    /// Skip records before this line, as printed by an interrupted import.
+    #[arg(long, default_value_t = 0)]
+    resume_from: u64,
+
+    #[command(flatten)]
+    config: ConfigArgs,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    if !args.delimiter.is_ascii() {
+        bail!("the delimiter must be a single ASCII character");
+    }
+    let config = IgniteConfig::load(&args.config)?;
+    let client = IgniteRestClientBuilder::from_config(&config).build()?;
+
+    let mut importer = match (&args.table, &args.kv_cache) {
+        (Some(table), _) => CsvImporter::into_table(client, table)?.mode(if args.merge {
+            ImportMode::Merge
+        } else {
+            ImportMode::Insert
+        }),
+        (None, Some(name)) => {
+            let mut cache = client.cache(name);
+            if let Some(key_type) = &args.key_type {
+                cache = cache.key_type(key_type);
+            }
+            if let Some(value_type) = &args.value_type {
+                cache = cache.value_type(value_type);
+            }
+            CsvImporter::into_cache(
+                cache,
+                args.key_column.clone().unwrap_or_default(),
+                args.value_column.clone().unwrap_or_default(),
+            )
+        }
+        (None, None) => unreachable!("clap requires a target"),
+    };
+    for mapping in &args.map {
+        let Some((header, column)) = mapping.split_once('=') else {
+            bail!("invalid --map `{mapping}`, expected HEADER=COLUMN");
+        };
+        importer = importer.map_column(header, column);
+    }
+    if let Some(rejects) = &args.rejects {
+        importer = importer.rejects_file(rejects);
+    }
+    let importer = importer
+        .batch_size(args.batch_size)
+        .delimiter(args.delimiter as u8)
+        .null_value(&args.null)
+        .skip_unmapped(args.skip_unmapped)
+        .resume_from_line(args.resume_from)
+        .on_progress(|progress| {
+            eprint!(
+                "\r{} rows written, {} rejected",
+                progress.rows_written, progress.rows_rejected
+            );
+        });
+
+    let outcome = importer.import_file(&args.file).await;
+    eprintln!();
+    match outcome {
+        Ok(progress) => {
+            println!(
+                "Imported {} rows, rejected {}.",
+                progress.rows_written, progress.rows_rejected
+            );
+            Ok(())
+        }
+        Err(err @ ImportError::Interrupted { resume_line, .. }) => {
+            eprintln!("Continue with --resume-from {resume_line}");
+            Err(err.into())
+        }
+        Err(err) => Err(err.into()),
+    }
+}
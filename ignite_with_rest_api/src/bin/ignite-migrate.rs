@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use ignite_config::{ConfigArgs, IgniteConfig};
+use ignite_with_rest_api::{
+    DEFAULT_HISTORY_TABLE, IgniteRestClientBuilder, MigrationState, Migrator,
+};
+
+/// Versioned schema migrations for Apache Ignite over the REST API.
+///
+/// Migrations are `V<version>__<description>.sql` files; applied versions
+/// and their checksums are recorded in a history table on the cluster.
+#[derive(Parser, Debug)]
+#[command(name = "ignite-migrate")]
+struct Args {
+    /// Directory holding the migration scripts.
+    #[arg(long, default_value = "migrations")]
+    dir: PathBuf,
+
+    /// Table recording applied migrations.
+    #[arg(long, default_value = DEFAULT_HISTORY_TABLE)]
+    history_table: String,
+
+    #[command(subcommand)]
+    command: Command,
+
+    #[command(flatten)]
+    config: ConfigArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List every migration and whether it has been applied.
+    Status,
+    /// Apply all pending migrations in version order.
+    Migrate,
+    /// Mark an existing schema as being at `version` without running anything.
+    Baseline {
+        #[arg(long)]
+        version: u32,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let config = IgniteConfig::load(&args.config)?;
+    let client = IgniteRestClientBuilder::from_config(&config).build()?;
+    let migrator = Migrator::from_dir(client, &args.dir)?.history_table(&args.history_table)?;
+
+    match args.command {
+        Command::Status => {
+            for status in migrator.status().await? {
+                let state = match &status.state {
+                    MigrationState::Applied { installed_on } => format!("applied {installed_on}"),
+                    MigrationState::Pending => "pending".to_string(),
+                    MigrationState::Modified { installed_on } => {
+                        format!("MODIFIED since applied {installed_on}")
+                    }
+                    MigrationState::BelowBaseline => "below baseline".to_string(),
+                    MigrationState::Baseline { installed_on } => format!("baseline {installed_on}"),
+                    MigrationState::Missing { installed_on } => {
+                        format!("applied {installed_on}, file missing")
+                    }
+                };
+                println!(
+                    "V{:03}  {:<40} {}",
+                    status.version, status.description, state
+                );
+            }
+        }
+        Command::Migrate => {
+            let applied = migrator.migrate().await?;
+            for version in &applied {
+                println!("Applied V{version:03}");
+            }
+            if applied.is_empty() {
+                println!("Schema is up to date.");
+            }
+        }
+        Command::Baseline { version } => {
+            migrator.baseline(version).await?;
+            println!("Baselined at V{version:03}.");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use std::path::PathBuf;
+use std::time::Instant;
+
+use clap::Parser;
+use futures::TryStreamExt;
+use ignite_config::{ConfigArgs, IgniteConfig};
+use ignite_with_rest_api::{
+    IgniteRestClient, IgniteRestClientBuilder, OutputFormat, ToSqlArg, is_terminated,
+    split_statements,
+};
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+
+/// Interactive SQL shell for Apache Ignite over the REST API.
+///
+/// Statements end with `;` and may span several lines. Lines starting with
+/// `\` are meta-commands; type `\help` to list them.
+#[derive(Parser, Debug)]
+#[command(name = "ignite-sql")]
+struct Args {
+    /// Result format: table, csv, jsonl or markdown.
+    #[arg(long, default_value_t = OutputFormat::Table)]
+    format: OutputFormat,
+
+    /// History file; defaults to ~/.ignite_sql_history.
+    #[arg(long)]
+    history: Option<PathBuf>,
+
+    #[command(flatten)]
+    config: ConfigArgs,
+}
+
+const HELP: &str = "\
+Statements are sent when a line ends with `;`.
+
+  \\tables             list SQL tables
+  \\describe <table>   list the columns of a table
+  \\cache [name]       show or switch the cache queries run against
+  \\format [name]      show or switch the result format (table, csv, jsonl, markdown)
+  \\help               show this text
+  \\quit               exit (Ctrl-D works too)";
+
+struct Shell {
+    client: IgniteRestClient,
+    cache: String,
+    format: OutputFormat,
+}
+
+impl Shell {
+    /// Runs one statement and prints its rows, with the row count and timing
+    /// on stderr so that machine-readable formats stay clean.
+    async fn run(&self, sql: &str, args: &[&dyn ToSqlArg]) {
+        let started = Instant::now();
+        let result = async {
+            let cursor = self.client.query_in(Some(&self.cache), sql, args).await?;
+            let fields = cursor.fields().to_vec();
+            let rows: Vec<_> = cursor.try_collect().await?;
+            Ok::<_, ignite_with_rest_api::IgniteRestError>((fields, rows))
+        }
+        .await;
+        let elapsed = started.elapsed();
+
+        match result {
+            Ok((fields, rows)) => {
+                print!("{}", self.format.render(&fields, &rows));
+                let noun = if rows.len() == 1 { "row" } else { "rows" };
+                eprintln!("({} {}, {:.1} ms)\n", rows.len(), noun, elapsed.as_secs_f64() * 1000.0);
+            }
+            Err(err) => eprintln!("ERROR: {err}\n({:.1} ms)\n", elapsed.as_secs_f64() * 1000.0),
+        }
+    }
+
+    /// Handles a `\` line; returns `false` when the shell should exit.
+    async fn meta(&mut self, line: &str) -> bool {
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let argument = words.next();
+
+        match (command, argument) {
+            ("\\q" | "\\quit", _) => return false,
+            ("\\?" | "\\help", _) => println!("{HELP}\n"),
+            ("\\tables", _) => {
+                self.run(
+                    "SELECT SCHEMA_NAME, TABLE_NAME, CACHE_NAME FROM SYS.TABLES \
+                     ORDER BY SCHEMA_NAME, TABLE_NAME",
+                    &[],
+                )
+                .await
+            }
+            ("\\describe" | "\\d", Some(table)) => {
+                let table = sql_identifier(table);
+                self.run(
+                    "SELECT COLUMN_NAME, TYPE, NULLABLE, PK, DEFAULT_VALUE FROM SYS.TABLE_COLUMNS \
+                     WHERE TABLE_NAME = ? AND COLUMN_NAME NOT IN ('_KEY', '_VAL') \
+                     ORDER BY SCHEMA_NAME, COLUMN_NAME",
+                    &[&table],
+                )
+                .await
+            }
+            ("\\cache", Some(cache)) => {
+                self.cache = cache.to_string();
+                println!("Queries now run against cache {}.\n", self.cache);
+            }
+            ("\\cache", None) => println!("Current cache: {}\n", self.cache),
+            ("\\format", Some(format)) => match format.parse() {
+                Ok(format) => {
+                    self.format = format;
+                    println!("Results are now printed as {}.\n", self.format);
+                }
+                Err(err) => println!("{err}\n"),
+            },
+            ("\\format", None) => println!("Current format: {}\n", self.format),
+            _ => println!("Unknown command {line:?}; type \\help for the list.\n"),
+        }
+        true
+    }
+}
+
+/// Table name as Ignite stores it: unquoted names are upper-cased.
+fn sql_identifier(name: &str) -> String {
+    match name.strip_prefix('"').and_then(|name| name.strip_suffix('"')) {
+        Some(quoted) => quoted.to_string(),
+        None => name.to_uppercase(),
+    }
+}
+
+fn history_path(args: &Args) -> Option<PathBuf> {
+    args.history.clone().or_else(|| {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".ignite_sql_history"))
+    })
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let config = IgniteConfig::load(&args.config)?;
+    let client = IgniteRestClientBuilder::from_config(&config).build()?;
+    let endpoints: Vec<_> = client.endpoints().map(|url| url.to_string()).collect();
+    let mut shell = Shell {
+        client,
+        cache: config.rest.cache.clone(),
+        format: args.format,
+    };
+
+    let mut editor = DefaultEditor::new()?;
+    let history = history_path(&args);
+    if let Some(history) = &history {
+        // A missing history file just means this is the first session.
+        let _ = editor.load_history(history);
+    }
+
+    println!(
+        "Connected to {}. Type \\help for help.\n",
+        endpoints.join(", ")
+    );
+    let mut buffer = String::new();
+    loop {
+        let prompt = if buffer.is_empty() { "ignite> " } else { "   ...> " };
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                buffer.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(err.into()),
+        };
+
+        if buffer.is_empty() && line.trim_start().starts_with('\\') {
+            editor.add_history_entry(line.as_str())?;
+            if !shell.meta(line.trim()).await {
+                break;
+            }
+            continue;
+        }
+
+        if !buffer.is_empty() {
+            buffer.push('\n');
+        }
+        buffer.push_str(&line);
+        if !is_terminated(&buffer) {
+            continue;
+        }
+
+        editor.add_history_entry(buffer.as_str())?;
+        for statement in split_statements(&buffer) {
+            shell.run(statement, &[]).await;
+        }
+        buffer.clear();
+    }
+
+    if let Some(history) = &history {
+        editor.save_history(history)?;
+    }
+    Ok(())
+}
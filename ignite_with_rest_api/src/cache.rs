@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::client::IgniteRestClient;
+use crate::error::{IgniteRestError, Result};
+
+/// Key-value access to one cache through the REST API.
+///
+/// Keys and values are serialized with serde: strings, numbers and booleans
+/// are sent as-is, anything else as JSON. Ignite stores untyped REST values
+/// as strings, so set [`RestCache::key_type`] and [`RestCache::value_type`]
+/// to have them stored (and read back) as the intended Java types.
+#[derive(Clone, Debug)]
+pub struct RestCache {
+    client: IgniteRestClient,
+    name: String,
+    key_type: Option<String>,
+    value_type: Option<String>,
+}
+
+impl IgniteRestClient {
+    /// Handle for the key-value commands of cache `name`.
+    pub fn cache(&self, name: impl Into<String>) -> RestCache {
+        RestCache {
+            client: self.clone(),
+            name: name.into(),
+            key_type: None,
+            value_type: None,
+        }
+    }
+}
+
+impl RestCache {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn client(&self) -> &IgniteRestClient {
+        &self.client
+    }
+
+    /// Java type of the keys, sent as `keyType`: a REST alias such as `int`,
+    /// `long`, `string`, `uuid`, `date`, or a fully qualified class name.
+    pub fn key_type(mut self, key_type: impl Into<String>) -> Self {
+        self.key_type = Some(key_type.into());
+        self
+    }
+
+    /// Java type of the values, sent as `valueType`; see [`RestCache::key_type`].
+    pub fn value_type(mut self, value_type: impl Into<String>) -> Self {
+        self.value_type = Some(value_type.into());
+        self
+    }
+
+    /// `get`: value stored under `key`.
+    pub async fn get<K: Serialize, V: DeserializeOwned>(&self, key: &K) -> Result<Option<V>> {
+        let params = self.params().key(key)?;
+        self.optional_value("get", params).await
+    }
+
+    /// `put`: stores `value` under `key`.
+    pub async fn put<K: Serialize, V: Serialize>(&self, key: &K, value: &V) -> Result<bool> {
+        let params = self.params().key(key)?.value("val", value)?;
+        self.flag("put", params).await
+    }
+
+    /// `putifabsent`: stores `value` only if `key` has no value; `true` if it did.
+    pub async fn put_if_absent<K: Serialize, V: Serialize>(&self, key: &K, value: &V) -> Result<bool> {
+        let params = self.params().key(key)?.value("val", value)?;
+        self.flag("putifabsent", params).await
+    }
+
+    /// `getandput`: stores `value` and returns the previous one.
+    pub async fn get_and_put<K: Serialize, V: Serialize + DeserializeOwned>(
+        &self,
+        key: &K,
+        value: &V,
+    ) -> Result<Option<V>> {
+        let params = self.params().key(key)?.value("val", value)?;
+        self.optional_value("getandput", params).await
+    }
+
+    /// `replace`: stores `value` only if `key` already has one; `true` if it did.
+    pub async fn replace<K: Serialize, V: Serialize>(&self, key: &K, value: &V) -> Result<bool> {
+        let params = self.params().key(key)?.value("val", value)?;
+        self.flag("replace", params).await
+    }
+
+    /// `cas`: stores `new` only if the current value equals `expected`.
+    pub async fn cas<K: Serialize, V: Serialize>(&self, key: &K, new: &V, expected: &V) -> Result<bool> {
+        let params = self
+            .params()
+            .key(key)?
+            .value("val", new)?
+            .value("val2", expected)?;
+        self.flag("cas", params).await
+    }
+
+    /// `getall`: values of every key that is present.
+    pub async fn get_all<K, V>(&self, keys: &[K]) -> Result<HashMap<K, V>>
+    where
+        K: Serialize + DeserializeOwned + Eq + Hash,
+        V: DeserializeOwned,
+    {
+        let cmd = "getall";
+        let params = self.params().keys(keys)?;
+        let entries: Option<HashMap<String, Value>> = self.send(cmd, params).await?;
+
+        entries
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(key, value)| Ok((decode_key(cmd, key)?, decode_value(cmd, value)?)))
+            .collect()
+    }
+
+    /// `putall`: stores every entry in one request.
+    pub async fn put_all<K: Serialize, V: Serialize>(&self, entries: &[(K, V)]) -> Result<bool> {
+        let mut params = self.params();
+        for (index, (key, value)) in entries.iter().enumerate() {
+            params = params
+                .value(&format!("k{}", index + 1), key)?
+                .value(&format!("v{}", index + 1), value)?;
+        }
+        self.flag("putall", params).await
+    }
+
+    /// `rmv`: removes `key`; `true` if it was present.
+    pub async fn remove<K: Serialize>(&self, key: &K) -> Result<bool> {
+        let params = self.params().key(key)?;
+        self.flag("rmv", params).await
+    }
+
+    /// `rmvall`: removes the given keys.
+    ///
+    /// An empty slice is a no-op; `rmvall` without keys would empty the whole
+    /// cache, which is what [`RestCache::clear`] is for.
+    pub async fn remove_all<K: Serialize>(&self, keys: &[K]) -> Result<bool> {
+        if keys.is_empty() {
+            return Ok(true);
+        }
+        let params = self.params().keys(keys)?;
+        self.flag("rmvall", params).await
+    }
+
+    /// `rmvall` without keys: removes every entry of the cache.
+    pub async fn clear(&self) -> Result<bool> {
+        self.flag("rmvall", self.params()).await
+    }
+
+    /// `containskey`: whether `key` has a value.
+    pub async fn contains_key<K: Serialize>(&self, key: &K) -> Result<bool> {
+        let params = self.params().key(key)?;
+        self.flag("containskey", params).await
+    }
+
+    /// `containskeys`: whether every one of `keys` has a value.
+    pub async fn contains_keys<K: Serialize>(&self, keys: &[K]) -> Result<bool> {
+        let params = self.params().keys(keys)?;
+        self.flag("containskeys", params).await
+    }
+
+    fn params(&self) -> Params {
+        let mut params = Params(vec![("cacheName".to_string(), self.name.clone())]);
+        if let Some(key_type) = &self.key_type {
+            params.0.push(("keyType".to_string(), key_type.clone()));
+        }
+        if let Some(value_type) = &self.value_type {
+            params.0.push(("valueType".to_string(), value_type.clone()));
+        }
+        params
+    }
+
+    async fn send<T: DeserializeOwned>(&self, cmd: &str, params: Params) -> Result<Option<T>> {
+        let params: Vec<(&str, &str)> = params
+            .0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        self.client.command(cmd, &params).await
+    }
+
+    async fn flag(&self, cmd: &str, params: Params) -> Result<bool> {
+        Ok(self.send::<bool>(cmd, params).await?.unwrap_or(false))
+    }
+
+    async fn optional_value<V: DeserializeOwned>(&self, cmd: &str, params: Params) -> Result<Option<V>> {
+        match self.send::<Value>(cmd, params).await? {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => decode_value(cmd, value).map(Some),
+        }
+    }
+}
+
+/// Query parameters of one key-value command.
+struct Params(Vec<(String, String)>);
+
+impl Params {
+    fn key<K: Serialize>(self, key: &K) -> Result<Self> {
+        self.value("key", key)
+    }
+
+    /// Numbered `k1..kN` parameters.
+    fn keys<K: Serialize>(mut self, keys: &[K]) -> Result<Self> {
+        for (index, key) in keys.iter().enumerate() {
+            self = self.value(&format!("k{}", index + 1), key)?;
+        }
+        Ok(self)
+    }
+
+    fn value<T: Serialize>(mut self, name: &str, value: &T) -> Result<Self> {
+        self.0.push((name.to_string(), encode_param(value)?));
+        Ok(self)
+    }
+}
+
+/// Renders a key or value as a REST parameter.
+pub(crate) fn encode_param<T: Serialize>(value: &T) -> Result<String> {
+    let value = serde_json::to_value(value).map_err(|err| IgniteRestError::Encode(err.to_string()))?;
+    match value {
+        Value::Null => Err(IgniteRestError::Encode(
+            "null cannot be sent as a key or value".into(),
+        )),
+        Value::String(text) => Ok(text),
+        other => Ok(other.to_string()),
+    }
+}
+
+/// Decodes a value returned by the server.
+///
+/// Values written without a `valueType` come back as the JSON text they were
+/// stored as, so a string that does not decode directly is parsed once more.
+pub(crate) fn decode_value<V: DeserializeOwned>(cmd: &str, value: Value) -> Result<V> {
+    let text = match &value {
+        Value::String(text) => Some(text.clone()),
+        _ => None,
+    };
+    serde_json::from_value(value).or_else(|err| match text {
+        Some(text) => serde_json::from_str(&text).map_err(|_| decode_error(cmd, err)),
+        None => Err(decode_error(cmd, err)),
+    })
+}
+
+/// Decodes a key of a `getall` response, where keys are always JSON strings.
+fn decode_key<K: DeserializeOwned>(cmd: &str, key: String) -> Result<K> {
+    serde_json::from_str(&key)
+        .or_else(|_| serde_json::from_value(Value::String(key)))
+        .map_err(|err| decode_error(cmd, err))
+}
+
+fn decode_error(cmd: &str, source: serde_json::Error) -> IgniteRestError {
+    IgniteRestError::Decode {
+        command: cmd.to_string(),
+        source,
+    }
+}
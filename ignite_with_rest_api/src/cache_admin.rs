@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::cache::RestCache;
+use crate::client::IgniteRestClient;
+use crate::error::{IgniteRestError, Result};
+
+/// Cache template passed to `getorcreate` as `templateName`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheTemplate {
+    Partitioned,
+    Replicated,
+    /// A template registered in the node configuration.
+    Custom(String),
+}
+
+impl CacheTemplate {
+    fn as_str(&self) -> &str {
+        match self {
+            CacheTemplate::Partitioned => "PARTITIONED",
+            CacheTemplate::Replicated => "REPLICATED",
+            CacheTemplate::Custom(name) => name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteSynchronizationMode {
+    FullSync,
+    FullAsync,
+    PrimarySync,
+}
+
+impl WriteSynchronizationMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            WriteSynchronizationMode::FullSync => "FULL_SYNC",
+            WriteSynchronizationMode::FullAsync => "FULL_ASYNC",
+            WriteSynchronizationMode::PrimarySync => "PRIMARY_SYNC",
+        }
+    }
+}
+
+/// Settings for a cache created with `getorcreate`; unset options fall back
+/// to the template's values.
+#[derive(Debug, Clone, Default)]
+pub struct CacheOptions {
+    template: Option<CacheTemplate>,
+    backups: Option<u32>,
+    data_region: Option<String>,
+    cache_group: Option<String>,
+    write_synchronization_mode: Option<WriteSynchronizationMode>,
+}
+
+impl CacheOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn template(mut self, template: CacheTemplate) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    pub fn backups(mut self, backups: u32) -> Self {
+        self.backups = Some(backups);
+        self
+    }
+
+    pub fn data_region(mut self, data_region: impl Into<String>) -> Self {
+        self.data_region = Some(data_region.into());
+        self
+    }
+
+    pub fn cache_group(mut self, cache_group: impl Into<String>) -> Self {
+        self.cache_group = Some(cache_group.into());
+        self
+    }
+
+    pub fn write_synchronization_mode(mut self, mode: WriteSynchronizationMode) -> Self {
+        self.write_synchronization_mode = Some(mode);
+        self
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(template) = &self.template {
+            params.push(("templateName", template.as_str().to_string()));
+        }
+        if let Some(backups) = self.backups {
+            params.push(("backups", backups.to_string()));
+        }
+        if let Some(data_region) = &self.data_region {
+            params.push(("dataRegion", data_region.clone()));
+        }
+        if let Some(cache_group) = &self.cache_group {
+            params.push(("cacheGroup", cache_group.clone()));
+        }
+        if let Some(mode) = self.write_synchronization_mode {
+            params.push(("writeSynchronizationMode", mode.as_str().to_string()));
+        }
+        params
+    }
+}
+
+/// Which copies of the entries `size` counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePeekMode {
+    All,
+    Near,
+    Primary,
+    Backup,
+    Onheap,
+    Offheap,
+}
+
+impl CachePeekMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            CachePeekMode::All => "ALL",
+            CachePeekMode::Near => "NEAR",
+            CachePeekMode::Primary => "PRIMARY",
+            CachePeekMode::Backup => "BACKUP",
+            CachePeekMode::Onheap => "ONHEAP",
+            CachePeekMode::Offheap => "OFFHEAP",
+        }
+    }
+}
+
+/// SQL metadata of a cache, as returned by `metadata`.
+///
+/// `fields` and `indexes` are keyed by SQL type name, then by field name.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CacheMetadata {
+    pub cache_name: Option<String>,
+    pub types: Vec<String>,
+    pub key_classes: BTreeMap<String, String>,
+    pub val_classes: BTreeMap<String, String>,
+    pub fields: BTreeMap<String, BTreeMap<String, String>>,
+    pub indexes: BTreeMap<String, Vec<CacheIndex>>,
+}
+
+impl CacheMetadata {
+    /// Java class of `field` in SQL type `type_name`, compared ignoring ASCII case.
+    pub fn field_type(&self, type_name: &str, field: &str) -> Option<&str> {
+        let (_, fields) = self
+            .fields
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(type_name))?;
+        fields
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(field))
+            .map(|(_, class)| class.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CacheIndex {
+    pub name: String,
+    pub fields: Vec<String>,
+    /// Fields of `fields` sorted in descending order.
+    pub descendings: Vec<String>,
+    pub unique: bool,
+}
+
+/// Cache metrics returned by the `cache` command. Times are in milliseconds.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CacheMetrics {
+    pub create_time: i64,
+    pub read_time: i64,
+    pub write_time: i64,
+    pub reads: i64,
+    pub writes: i64,
+    pub hits: i64,
+    pub misses: i64,
+    pub commits: i64,
+    pub rollbacks: i64,
+}
+
+impl IgniteRestClient {
+    /// `getorcreate`: creates cache `name` unless it exists, and returns a
+    /// handle to it.
+    pub async fn get_or_create_cache(&self, name: &str, options: &CacheOptions) -> Result<RestCache> {
+        let options = options.params();
+        let mut params = vec![("cacheName", name)];
+        params.extend(options.iter().map(|(param, value)| (*param, value.as_str())));
+        self.command::<Value>("getorcreate", &params).await?;
+
+        Ok(self.cache(name))
+    }
+
+    /// `destroycache`: drops cache `name` and its data.
+    pub async fn destroy_cache(&self, name: &str) -> Result<()> {
+        self.command::<Value>("destroycache", &[("cacheName", name)])
+            .await?;
+        Ok(())
+    }
+}
+
+impl RestCache {
+    /// `size`: number of entries. With no peek modes the server counts
+    /// primary copies only.
+    pub async fn size(&self, peek_modes: &[CachePeekMode]) -> Result<u64> {
+        let modes = peek_modes
+            .iter()
+            .map(|mode| mode.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut params = vec![("cacheName", self.name())];
+        if !modes.is_empty() {
+            params.push(("peekModes", modes.as_str()));
+        }
+
+        Ok(self.client().command("size", &params).await?.unwrap_or(0))
+    }
+
+    /// `metadata`: SQL types, fields and indexes of this cache.
+    pub async fn metadata(&self) -> Result<CacheMetadata> {
+        let cmd = "metadata";
+        let response: Option<Value> = self
+            .client()
+            .command(cmd, &[("cacheName", self.name())])
+            .await?;
+
+        // Depending on the version the server answers with the metadata of
+        // this cache or with a list covering every cache.
+        let metadata = match response {
+            Some(Value::Array(all)) => all
+                .into_iter()
+                .find(|meta| meta.get("cacheName").and_then(Value::as_str) == Some(self.name()))
+                .unwrap_or(Value::Null),
+            Some(meta) => meta,
+            None => Value::Null,
+        };
+        if metadata.is_null() {
+            return Ok(CacheMetadata {
+                cache_name: Some(self.name().to_string()),
+                ..CacheMetadata::default()
+            });
+        }
+
+        serde_json::from_value(metadata).map_err(|source| IgniteRestError::Decode {
+            command: cmd.to_string(),
+            source,
+        })
+    }
+
+    /// `cache`: read/write/hit metrics of this cache.
+    pub async fn metrics(&self) -> Result<CacheMetrics> {
+        Ok(self
+            .client()
+            .command("cache", &[("cacheName", self.name())])
+            .await?
+            .unwrap_or_default())
+    }
+}
@@ -0,0 +1,575 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::TryStreamExt;
+use reqwest::Client;
+use reqwest::header::CONTENT_TYPE;
+use serde::de::DeserializeOwned;
+use url::{Url, form_urlencoded};
+
+use crate::args::{ToSqlArg, bind};
+use crate::auth::Credentials;
+use crate::cursor::SqlCursor;
+use crate::endpoint::{
+    DEFAULT_EJECT_AFTER, DEFAULT_EJECTION_PERIOD, Endpoint, EndpointSelection, Endpoints,
+};
+use crate::error::{IgniteRestError, Result};
+use crate::response::{QueryPage, RestResponse, SqlResult};
+use crate::retry::{RetryPolicy, is_idempotent, is_transient, is_undelivered};
+use crate::tls::TlsConfig;
+
+pub use ignite_config::DEFAULT_REST_URL as DEFAULT_BASE_URL;
+
+/// Page size used for SQL queries unless the builder overrides it.
+pub const DEFAULT_PAGE_SIZE: u32 = 10;
+
+/// Size in bytes of the encoded parameters above which a command is sent as
+/// a form-encoded `POST` body rather than in the URL. Jetty rejects request
+/// lines longer than its 8 KiB header buffer, so this leaves ample room.
+pub const DEFAULT_POST_THRESHOLD: usize = 2048;
+
+/// Time allowed to open a connection to an endpoint, unless overridden.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time allowed for one request, from sending it to reading the whole
+/// response, unless overridden.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Long-lived client for the Ignite REST API.
+///
+/// Cloning is cheap: clones share the same connection pool and settings, so a
+/// service can create one client at startup and hand clones to its tasks.
+#[derive(Clone, Debug)]
+pub struct IgniteRestClient {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    http: Client,
+    endpoints: Endpoints,
+    cache_name: Option<String>,
+    page_size: u32,
+    credentials: Option<Credentials>,
+    post_threshold: usize,
+    retry: RetryPolicy,
+    /// Per-request timeout, which also overrides that of a supplied client.
+    timeout: Option<Duration>,
+}
+
+impl IgniteRestClient {
+    /// Client for `base_url` with default settings.
+    pub fn new(base_url: &str) -> Result<Self> {
+        Self::builder().base_url(base_url).build()
+    }
+
+    pub fn builder() -> IgniteRestClientBuilder {
+        IgniteRestClientBuilder::default()
+    }
+
+    /// First configured endpoint.
+    pub fn base_url(&self) -> &Url {
+        &self.inner.endpoints.get(0).url
+    }
+
+    /// All configured endpoints, in order.
+    pub fn endpoints(&self) -> impl Iterator<Item = &Url> {
+        self.inner.endpoints.urls()
+    }
+
+    /// Cache used by commands that are not given an explicit cache name.
+    pub fn cache_name(&self) -> Option<&str> {
+        self.inner.cache_name.as_deref()
+    }
+
+    pub fn page_size(&self) -> u32 {
+        self.inner.page_size
+    }
+
+    /// Sends `cmd` with the given parameters and returns its `response` field.
+    ///
+    /// Parameter values are URL-encoded here, so callers pass them verbatim.
+    /// A non-zero `successStatus` is returned as the matching
+    /// [`IgniteRestError`] variant. With credentials configured, the cached
+    /// session token is attached, and an expired token triggers one
+    /// transparent login and resend.
+    ///
+    /// When an endpoint cannot be reached or answers with a 5xx status, the
+    /// command moves on to the next endpoint and is retried according to the
+    /// [`RetryPolicy`], as far as that is safe for the command.
+    pub async fn command<T: DeserializeOwned>(
+        &self,
+        cmd: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Option<T>> {
+        Ok(self.command_on(None, cmd, params).await?.0)
+    }
+
+    /// [`command`](Self::command) that also returns the index of the endpoint
+    /// that answered. With `pinned` set, every attempt goes to that endpoint:
+    /// follow-up commands of a query must reach the node that holds it.
+    pub(crate) async fn command_on<T: DeserializeOwned>(
+        &self,
+        pinned: Option<usize>,
+        cmd: &str,
+        params: &[(&str, &str)],
+    ) -> Result<(Option<T>, usize)> {
+        let endpoints = &self.inner.endpoints;
+        let policy = &self.inner.retry;
+        let idempotent = is_idempotent(cmd, params);
+        let mut tried = Vec::new();
+        let mut retries = 0;
+        let mut index = pinned.unwrap_or_else(|| endpoints.pick(&tried));
+        loop {
+            let (result, sent) = self.attempt::<T>(endpoints.get(index), cmd, params).await;
+            let err = match result {
+                Ok(response) => {
+                    endpoints.record_success(index);
+                    return Ok((response, index));
+                }
+                Err(err) if !is_transient(cmd, &err) => {
+                    endpoints.record_success(index);
+                    return Err(err);
+                }
+                Err(err) => err,
+            };
+            endpoints.record_failure(index);
+            // The first attempt may have been applied; doing it again is
+            // only harmless for idempotent commands.
+            if sent && !is_undelivered(&err) && !idempotent {
+                return Err(err);
+            }
+
+            // Failing over to an endpoint not tried yet is free; going back
+            // to one counts as a retry and waits for the backoff first.
+            tried.push(index);
+            let next = pinned.unwrap_or_else(|| endpoints.pick(&tried));
+            if tried.contains(&next) {
+                if retries >= policy.max_retries {
+                    return Err(err);
+                }
+                retries += 1;
+                tokio::time::sleep(policy.backoff(retries)).await;
+            }
+            index = next;
+        }
+    }
+
+    /// Sends `cmd` to `endpoint` once, logging in first if needed. The flag
+    /// is false when the command itself was never sent because the login
+    /// failed.
+    async fn attempt<T: DeserializeOwned>(
+        &self,
+        endpoint: &Endpoint,
+        cmd: &str,
+        params: &[(&str, &str)],
+    ) -> (Result<Option<T>>, bool) {
+        if self.inner.credentials.is_none() {
+            let result = self.send::<T>(endpoint, cmd, params, None).await;
+            return (result.and_then(|response| response.into_result(cmd)), true);
+        }
+
+        let token = match endpoint.session.token() {
+            Some(token) => token,
+            None => match self.login(endpoint, None).await {
+                Ok(token) => token,
+                Err(err) => return (Err(err), false),
+            },
+        };
+        let result = match self.send::<T>(endpoint, cmd, params, Some(&token)).await {
+            Ok(response) => response.into_result(cmd),
+            Err(err) => Err(err),
+        };
+        let result = match result {
+            // The server rejected the command before running it, so sending
+            // it again with a fresh token is safe for any command.
+            Err(IgniteRestError::AuthenticationFailed(_)) => {
+                match self.login(endpoint, Some(&token)).await {
+                    Ok(token) => self
+                        .send::<T>(endpoint, cmd, params, Some(&token))
+                        .await
+                        .and_then(|response| response.into_result(cmd)),
+                    Err(err) => Err(err),
+                }
+            }
+            result => result,
+        };
+        (result, true)
+    }
+
+    /// Authenticates with `cmd=authenticate` and caches the session token of
+    /// `endpoint`.
+    ///
+    /// `stale` is the token that was just rejected; if another task has
+    /// already replaced it, that newer token is returned without logging in.
+    async fn login(&self, endpoint: &Endpoint, stale: Option<&str>) -> Result<String> {
+        let Some(credentials) = &self.inner.credentials else {
+            return Err(IgniteRestError::Config("no credentials configured".into()));
+        };
+        let session = &endpoint.session;
+        let _guard = session.login_lock().lock().await;
+        if let Some(current) = session.token()
+            && stale != Some(current.as_str())
+        {
+            return Ok(current);
+        }
+
+        let cmd = "authenticate";
+        let params = [
+            ("ignite.login", credentials.login()),
+            ("ignite.password", credentials.password()),
+        ];
+        let response = self
+            .send::<serde_json::Value>(endpoint, cmd, &params, None)
+            .await?;
+        let token = response.session_token.clone();
+        response.into_result(cmd)?;
+
+        let token = token.ok_or_else(|| {
+            IgniteRestError::AuthenticationFailed("no session token in response".into())
+        })?;
+        session.set_token(Some(token.clone()));
+        Ok(token)
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        endpoint: &Endpoint,
+        cmd: &str,
+        params: &[(&str, &str)],
+        session_token: Option<&str>,
+    ) -> Result<RestResponse<T>> {
+        let mut url = endpoint.url.clone();
+        url.query_pairs_mut().append_pair("cmd", cmd);
+        let form = {
+            let mut form = form_urlencoded::Serializer::new(String::new());
+            form.extend_pairs(params);
+            if let Some(token) = session_token {
+                form.append_pair("sessionToken", token);
+            }
+            form.finish()
+        };
+
+        // Ignite reads parameters from the query string and a form body
+        // alike; only `cmd` stays in the URL so access logs still show it.
+        // The password of `authenticate` must never reach those logs.
+        let mut request = if cmd == "authenticate" || form.len() > self.inner.post_threshold {
+            self.inner
+                .http
+                .post(url)
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(form)
+        } else {
+            if !form.is_empty() {
+                let query = format!("{}&{form}", url.query().unwrap_or_default());
+                url.set_query(Some(&query));
+            }
+            self.inner.http.get(url)
+        };
+        if let Some(timeout) = self.inner.timeout {
+            request = request.timeout(timeout);
+        }
+        let res = request.send().await?.error_for_status()?;
+        let body = res.text().await?;
+
+        serde_json::from_str(&body).map_err(|source| IgniteRestError::Decode {
+            command: cmd.to_string(),
+            source,
+        })
+    }
+
+    /// Runs `query` through `qryfldexe` against the default cache and returns
+    /// the first page of the result.
+    ///
+    /// `args` are bound to the `?` placeholders of `query` in order, so values
+    /// never have to be formatted into the SQL text.
+    pub async fn execute_sql(&self, query: &str, args: &[&dyn ToSqlArg]) -> Result<SqlResult> {
+        self.execute_sql_in(self.cache_name(), query, args).await
+    }
+
+    /// Runs `query` through `qryfldexe` against `cache` and returns the first
+    /// page of the result.
+    pub async fn execute_sql_in(
+        &self,
+        cache: Option<&str>,
+        query: &str,
+        args: &[&dyn ToSqlArg],
+    ) -> Result<SqlResult> {
+        Ok(self.open_sql(cache, query, args).await?.0)
+    }
+
+    /// Runs `qryfldexe` and returns the first page with the endpoint that
+    /// holds the query.
+    async fn open_sql(
+        &self,
+        cache: Option<&str>,
+        query: &str,
+        args: &[&dyn ToSqlArg],
+    ) -> Result<(SqlResult, usize)> {
+        let bound = bind(query, args)?;
+        let page_size = self.inner.page_size.to_string();
+        let mut params = vec![("qry", bound.sql.as_str()), ("pageSize", page_size.as_str())];
+        if let Some(cache) = cache {
+            params.push(("cacheName", cache));
+        }
+        let arg_names = bound.arg_names();
+        params.extend(bound.arg_params(&arg_names));
+
+        let (page, endpoint) = self.command_on(None, "qryfldexe", &params).await?;
+        Ok((page.unwrap_or_default(), endpoint))
+    }
+
+    /// Runs `query` against the default cache and returns a cursor over all of
+    /// its rows, however many pages they span.
+    pub async fn query(&self, query: &str, args: &[&dyn ToSqlArg]) -> Result<SqlCursor> {
+        self.query_in(self.cache_name(), query, args).await
+    }
+
+    /// Runs `query` against `cache` and returns a cursor over all of its rows.
+    pub async fn query_in(
+        &self,
+        cache: Option<&str>,
+        query: &str,
+        args: &[&dyn ToSqlArg],
+    ) -> Result<SqlCursor> {
+        let (first_page, endpoint) = self.open_sql(cache, query, args).await?;
+
+        Ok(SqlCursor::new(self.clone(), "qryfldexe", endpoint, first_page))
+    }
+
+    /// Fetches the next page of a query opened on `endpoint` with `qryfetch`.
+    pub(crate) async fn fetch_page<I: DeserializeOwned>(
+        &self,
+        endpoint: usize,
+        query_id: i64,
+    ) -> Result<QueryPage<I>> {
+        let query_id = query_id.to_string();
+        let page_size = self.inner.page_size.to_string();
+        let params = [("qryId", query_id.as_str()), ("pageSize", page_size.as_str())];
+        let (page, _) = self.command_on(Some(endpoint), "qryfetch", &params).await?;
+
+        Ok(page.unwrap_or_default())
+    }
+
+    /// Releases a query opened on `endpoint` with `qrycls`.
+    pub(crate) async fn close_query(&self, endpoint: usize, query_id: i64) -> Result<()> {
+        let query_id = query_id.to_string();
+        self.command_on::<serde_json::Value>(Some(endpoint), "qrycls", &[("qryId", &query_id)])
+            .await?;
+        Ok(())
+    }
+
+    /// Runs `query` against the default cache and decodes each row into `T`.
+    ///
+    /// Every page is fetched. Struct fields are matched to columns by name, see
+    /// [`SqlResult::rows_as`].
+    pub async fn query_as<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        args: &[&dyn ToSqlArg],
+    ) -> Result<Vec<T>> {
+        self.query(query, args).await?.into_typed().try_collect().await
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IgniteRestClientBuilder {
+    endpoints: Vec<String>,
+    cache_name: Option<String>,
+    page_size: u32,
+    credentials: Option<Credentials>,
+    post_threshold: usize,
+    http: Option<Client>,
+    tls: Option<TlsConfig>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    retry: RetryPolicy,
+    selection: EndpointSelection,
+    eject_after: u32,
+    ejection_period: Duration,
+}
+
+impl Default for IgniteRestClientBuilder {
+    fn default() -> Self {
+        Self {
+            endpoints: vec![DEFAULT_BASE_URL.to_string()],
+            cache_name: None,
+            page_size: DEFAULT_PAGE_SIZE,
+            credentials: None,
+            post_threshold: DEFAULT_POST_THRESHOLD,
+            http: None,
+            tls: None,
+            connect_timeout: None,
+            timeout: None,
+            retry: RetryPolicy::default(),
+            selection: EndpointSelection::default(),
+            eject_after: DEFAULT_EJECT_AFTER,
+            ejection_period: DEFAULT_EJECTION_PERIOD,
+        }
+    }
+}
+
+impl IgniteRestClientBuilder {
+    /// Full URL of the REST endpoint, e.g. `http://localhost:8080/ignite`.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.endpoints = vec![base_url.into()];
+        self
+    }
+
+    /// REST endpoints of several nodes of the same cluster, used in place of
+    /// a single base URL; see [`endpoint_selection`](Self::endpoint_selection).
+    pub fn endpoints<I, S>(mut self, endpoints: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.endpoints = endpoints.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Whether commands stick to the first healthy endpoint or rotate.
+    pub fn endpoint_selection(mut self, selection: EndpointSelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Skips an endpoint for `period` once `failures` commands in a row could
+    /// not reach it or got a 5xx status; zero failures disables this.
+    pub fn eject_after(mut self, failures: u32, period: Duration) -> Self {
+        self.eject_after = failures;
+        self.ejection_period = period;
+        self
+    }
+
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Time allowed to open a connection, [`DEFAULT_CONNECT_TIMEOUT`] if
+    /// unset. Not available together with [`http_client`](Self::http_client).
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Time allowed for one request until its response is fully read,
+    /// [`DEFAULT_TIMEOUT`] if unset. Overrides the timeout of a client given
+    /// to [`http_client`](Self::http_client).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn cache_name(mut self, cache_name: impl Into<String>) -> Self {
+        self.cache_name = Some(cache_name.into());
+        self
+    }
+
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Logs in with `credentials` for clusters that have authentication enabled.
+    pub fn credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Uses [`Credentials::from_env`] if both variables are set.
+    pub fn credentials_from_env(mut self) -> Self {
+        if let Some(credentials) = Credentials::from_env() {
+            self.credentials = Some(credentials);
+        }
+        self
+    }
+
+    /// Certificates for `https` endpoints whose server certificate is not
+    /// signed by a system root, or that require a client certificate. Not
+    /// available together with [`http_client`](Self::http_client).
+    pub fn tls(mut self, tls: TlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Sends commands whose URL-encoded parameters exceed `bytes` as an
+    /// `application/x-www-form-urlencoded` `POST` body instead of a query
+    /// string. Zero posts every command; `usize::MAX` posts only
+    /// `authenticate`, which is always sent as a form so that the password
+    /// stays out of the URL.
+    pub fn post_threshold(mut self, bytes: usize) -> Self {
+        self.post_threshold = bytes;
+        self
+    }
+
+    /// Reuses an existing `reqwest::Client` instead of creating a new pool;
+    /// its own timeouts apply unless [`timeout`](Self::timeout) is set.
+    pub fn http_client(mut self, http: Client) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    pub fn build(self) -> Result<IgniteRestClient> {
+        if self.endpoints.is_empty() {
+            return Err(IgniteRestError::Config("no REST endpoint configured".into()));
+        }
+        let urls = self
+            .endpoints
+            .iter()
+            .map(|url| {
+                Url::parse(url).map_err(|err| {
+                    IgniteRestError::Config(format!("invalid base URL `{url}`: {err}"))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if self.tls.is_some()
+            && let Some(url) = urls.iter().find(|url| url.scheme() != "https")
+        {
+            return Err(IgniteRestError::Config(format!(
+                "endpoint `{url}` must use https when TLS settings are given"
+            )));
+        }
+        if self.page_size == 0 {
+            return Err(IgniteRestError::Config("page size must be greater than zero".into()));
+        }
+
+        let http = match self.http {
+            Some(_) if self.connect_timeout.is_some() => {
+                return Err(IgniteRestError::Config(
+                    "a connect timeout cannot be applied to a supplied HTTP client".into(),
+                ));
+            }
+            Some(_) if self.tls.is_some() => {
+                return Err(IgniteRestError::Config(
+                    "TLS settings cannot be applied to a supplied HTTP client".into(),
+                ));
+            }
+            Some(http) => http,
+            None => {
+                let mut http = Client::builder()
+                    .connect_timeout(self.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT))
+                    .timeout(self.timeout.unwrap_or(DEFAULT_TIMEOUT));
+                if let Some(tls) = &self.tls {
+                    http = tls.apply(http)?;
+                }
+                http.build().map_err(|err| {
+                    IgniteRestError::Config(format!("failed to create HTTP client: {err}"))
+                })?
+            }
+        };
+
+        Ok(IgniteRestClient {
+            inner: Arc::new(Inner {
+                http,
+                endpoints: Endpoints::new(urls, self.selection, self.eject_after, self.ejection_period),
+                cache_name: self.cache_name,
+                page_size: self.page_size,
+                credentials: self.credentials,
+                post_threshold: self.post_threshold,
+                retry: self.retry,
+                timeout: self.timeout,
+            }),
+        })
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::client::IgniteRestClient;
+use crate::error::{IgniteRestError, Result};
+use crate::retry::is_unavailable;
+
+/// A server or client node, as described by `top` and `node`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ClusterNode {
+    pub node_id: String,
+    pub consistent_id: Option<String>,
+    /// Join order of the node in the topology.
+    pub order: i64,
+    pub tcp_host_names: Vec<String>,
+    pub tcp_addresses: Vec<String>,
+    pub tcp_port: u16,
+    pub caches: Vec<NodeCache>,
+    pub default_cache_mode: Option<String>,
+    /// Only filled when attributes were requested.
+    pub attributes: Option<BTreeMap<String, Value>>,
+    /// Only filled when metrics were requested.
+    pub metrics: Option<NodeMetrics>,
+}
+
+/// A cache started on a node.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NodeCache {
+    pub name: String,
+    /// `PARTITIONED`, `REPLICATED` or `LOCAL`.
+    pub mode: String,
+    pub sql_schema: Option<String>,
+}
+
+/// A subset of the node metrics reported by `top`/`node`; memory is in
+/// bytes and times in milliseconds.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NodeMetrics {
+    pub last_update_time: i64,
+    pub up_time: i64,
+    pub total_cpus: i32,
+    pub current_cpu_load: f64,
+    pub average_cpu_load: f64,
+    pub current_gc_cpu_load: f64,
+    pub heap_memory_used: i64,
+    pub heap_memory_committed: i64,
+    pub heap_memory_maximum: i64,
+    pub non_heap_memory_used: i64,
+    pub current_thread_count: i32,
+    pub current_active_jobs: i32,
+    pub current_waiting_jobs: i32,
+    pub total_executed_jobs: i32,
+    pub sent_messages_count: i32,
+    pub received_messages_count: i32,
+}
+
+/// Identifies the node to describe with `node`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeSelector {
+    Id(String),
+    Ip(String),
+}
+
+/// Cluster activation state, as read by `state` and written by `setstate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ClusterState {
+    Active,
+    ActiveReadOnly,
+    Inactive,
+}
+
+impl ClusterState {
+    fn as_str(self) -> &'static str {
+        match self {
+            ClusterState::Active => "ACTIVE",
+            ClusterState::ActiveReadOnly => "ACTIVE_READ_ONLY",
+            ClusterState::Inactive => "INACTIVE",
+        }
+    }
+}
+
+impl IgniteRestClient {
+    /// `version`: Ignite version of the node serving the request.
+    pub async fn version(&self) -> Result<String> {
+        Ok(self.command("version", &[]).await?.unwrap_or_default())
+    }
+
+    /// `probe`: whether the node has finished starting up. A starting node
+    /// answers with HTTP 503, which is neither retried nor held against the
+    /// endpoint.
+    pub async fn probe(&self) -> Result<bool> {
+        match self.command::<Value>("probe", &[]).await {
+            Ok(_) => Ok(true),
+            Err(IgniteRestError::CommandFailed { .. }) => Ok(false),
+            Err(err) if is_unavailable(&err) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// `top`: every node in the topology.
+    pub async fn topology(&self, attributes: bool, metrics: bool) -> Result<Vec<ClusterNode>> {
+        let params = [("attr", bool_param(attributes)), ("mtr", bool_param(metrics))];
+        Ok(self.command("top", &params).await?.unwrap_or_default())
+    }
+
+    /// Number of nodes in the topology.
+    pub async fn topology_size(&self) -> Result<usize> {
+        Ok(self.topology(false, false).await?.len())
+    }
+
+    /// `node`: a single node, or `None` if no node matches.
+    pub async fn node(
+        &self,
+        selector: &NodeSelector,
+        attributes: bool,
+        metrics: bool,
+    ) -> Result<Option<ClusterNode>> {
+        let selector = match selector {
+            NodeSelector::Id(id) => ("id", id.as_str()),
+            NodeSelector::Ip(ip) => ("ip", ip.as_str()),
+        };
+        let params = [
+            selector,
+            ("attr", bool_param(attributes)),
+            ("mtr", bool_param(metrics)),
+        ];
+        self.command("node", &params).await
+    }
+
+    /// `state`: current activation state of the cluster.
+    pub async fn cluster_state(&self) -> Result<ClusterState> {
+        let cmd = "state";
+        let state: Value = self.command(cmd, &[]).await?.unwrap_or_default();
+        serde_json::from_value(state).map_err(|source| IgniteRestError::Decode {
+            command: cmd.to_string(),
+            source,
+        })
+    }
+
+    /// `setstate`: switches the cluster to `state`.
+    ///
+    /// Deactivation discards in-memory data, so it is only sent with
+    /// `force=true` when `force` is set; otherwise the server refuses it.
+    pub async fn set_cluster_state(&self, state: ClusterState, force: bool) -> Result<()> {
+        let params = [("state", state.as_str()), ("force", bool_param(force))];
+        self.command::<Value>("setstate", &params).await?;
+        Ok(())
+    }
+
+    /// Activates the cluster unless it already is.
+    pub async fn activate(&self) -> Result<()> {
+        if self.cluster_state().await? == ClusterState::Active {
+            return Ok(());
+        }
+        self.set_cluster_state(ClusterState::Active, false).await
+    }
+}
+
+fn bool_param(value: bool) -> &'static str {
+    if value { "true" } else { "false" }
+}
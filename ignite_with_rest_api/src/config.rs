@@ -0,0 +1,38 @@
+use ignite_config::IgniteConfig;
+
+use crate::client::{IgniteRestClient, IgniteRestClientBuilder};
+use crate::tls::TlsConfig;
+
+impl IgniteRestClientBuilder {
+    /// Builder set up from the `[rest]` settings and credentials of a loaded
+    /// configuration, which have already been validated.
+    pub fn from_config(config: &IgniteConfig) -> Self {
+        let rest = &config.rest;
+        let mut builder = IgniteRestClient::builder()
+            .endpoints(rest.urls.iter().map(|url| url.as_str()))
+            .cache_name(&rest.cache);
+        if let Some(page_size) = rest.page_size {
+            builder = builder.page_size(page_size);
+        }
+        if let Some(timeout) = rest.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = rest.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(files) = &rest.tls {
+            let mut tls = TlsConfig::new();
+            if let Some(ca) = &files.ca_certificate {
+                tls = tls.ca_certificate(ca);
+            }
+            if let Some((certificate, key)) = &files.client {
+                tls = tls.client_pem(certificate, key);
+            }
+            builder = builder.tls(tls);
+        }
+        if let Some(credentials) = &config.credentials {
+            builder = builder.credentials(credentials.clone());
+        }
+        builder
+    }
+}
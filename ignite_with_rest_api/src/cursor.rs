@@ -0,0 +1,188 @@
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use futures::future::BoxFuture;
+use futures::stream::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::client::IgniteRestClient;
+use crate::error::Result;
+use crate::cache::decode_value;
+use crate::response::{FieldMetadata, KeyValue, QueryPage};
+use crate::row::decode_row;
+
+/// Streams every item of a query result, fetching further pages with
+/// `qryfetch` until the server reports the last one.
+///
+/// Dropping the cursor before it is exhausted sends `qrycls` in the
+/// background so the server can release the query; call
+/// [`QueryCursor::close`] to wait for that instead.
+pub struct QueryCursor<I> {
+    client: IgniteRestClient,
+    /// Command that opened the query, used to label decoding errors.
+    command: &'static str,
+    /// Endpoint that opened the query; only its node knows the query id.
+    endpoint: usize,
+    fields: Vec<FieldMetadata>,
+    query_id: Option<i64>,
+    buffer: VecDeque<I>,
+    last: bool,
+    failed: bool,
+    pending: Option<BoxFuture<'static, Result<QueryPage<I>>>>,
+}
+
+// Items are only ever moved in and out of the buffer, never pinned.
+impl<I> Unpin for QueryCursor<I> {}
+
+impl<I> QueryCursor<I> {
+    fn open_query_id(&self) -> Option<i64> {
+        self.query_id.filter(|_| !self.last)
+    }
+}
+
+/// Cursor over the rows of a SQL fields query (`qryfldexe`).
+pub type SqlCursor = QueryCursor<Vec<Value>>;
+
+/// Cursor over the entries of a scan (`qryscanexe`) or type-based SQL
+/// query (`qryexe`).
+pub type EntryCursor = QueryCursor<KeyValue>;
+
+impl<I> QueryCursor<I>
+where
+    I: DeserializeOwned + Send + 'static,
+{
+    pub(crate) fn new(
+        client: IgniteRestClient,
+        command: &'static str,
+        endpoint: usize,
+        first_page: QueryPage<I>,
+    ) -> Self {
+        let mut cursor = QueryCursor {
+            client,
+            command,
+            endpoint,
+            fields: Vec::new(),
+            query_id: None,
+            buffer: VecDeque::new(),
+            last: true,
+            failed: false,
+            pending: None,
+        };
+        cursor.absorb(first_page);
+        cursor
+    }
+
+    /// Column metadata of the first page; empty for entry queries.
+    pub fn fields(&self) -> &[FieldMetadata] {
+        &self.fields
+    }
+
+    /// Releases the server-side cursor if the result was not fully read.
+    pub async fn close(mut self) -> Result<()> {
+        self.pending = None;
+        match self.open_query_id() {
+            Some(query_id) => {
+                self.last = true;
+                self.client.close_query(self.endpoint, query_id).await
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn absorb(&mut self, page: QueryPage<I>) {
+        if let Some(fields) = page.fields_metadata {
+            self.fields = fields;
+        }
+        self.buffer.extend(page.items.unwrap_or_default());
+        self.query_id = page.query_id.or(self.query_id);
+        self.last = page.last || self.query_id.is_none();
+    }
+}
+
+impl SqlCursor {
+    /// Decodes each row into `T` as it arrives, see
+    /// [`SqlResult::rows_as`](crate::SqlResult::rows_as).
+    pub fn into_typed<T: DeserializeOwned>(self) -> impl Stream<Item = Result<T>> + Send {
+        let fields = self.fields.clone();
+        self.enumerate().map(move |(index, row)| {
+            let row = row?;
+            Ok(decode_row(&fields, &row, index)?)
+        })
+    }
+}
+
+impl EntryCursor {
+    /// Decodes each entry's key and value as it arrives, the same way as
+    /// [`RestCache::get`](crate::RestCache::get).
+    pub fn into_entries<K, V>(self) -> impl Stream<Item = Result<KeyValue<K, V>>> + Send
+    where
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        let command = self.command;
+        self.map(move |entry| {
+            let entry = entry?;
+            Ok(KeyValue {
+                key: decode_value(command, entry.key)?,
+                value: decode_value(command, entry.value)?,
+            })
+        })
+    }
+}
+
+impl<I> Stream for QueryCursor<I>
+where
+    I: DeserializeOwned + Send + 'static,
+{
+    type Item = Result<I>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(row) = this.buffer.pop_front() {
+                return Poll::Ready(Some(Ok(row)));
+            }
+            if this.failed {
+                return Poll::Ready(None);
+            }
+            let Some(query_id) = this.open_query_id() else {
+                return Poll::Ready(None);
+            };
+
+            let fetch = this.pending.get_or_insert_with(|| {
+                let client = this.client.clone();
+                let endpoint = this.endpoint;
+                Box::pin(async move { client.fetch_page::<I>(endpoint, query_id).await })
+            });
+            let page = ready!(fetch.as_mut().poll(cx));
+            this.pending = None;
+
+            match page {
+                Ok(page) => this.absorb(page),
+                Err(err) => {
+                    this.failed = true;
+                    return Poll::Ready(Some(Err(err)));
+                }
+            }
+        }
+    }
+}
+
+impl<I> Drop for QueryCursor<I> {
+    fn drop(&mut self) {
+        let Some(query_id) = self.open_query_id() else {
+            return;
+        };
+        // Without a runtime there is nothing to send the request on; the
+        // server will eventually expire the cursor on its own.
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let client = self.client.clone();
+            let endpoint = self.endpoint;
+            runtime.spawn(async move {
+                let _ = client.close_query(endpoint, query_id).await;
+            });
+        }
+    }
+}
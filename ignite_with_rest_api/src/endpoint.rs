@@ -0,0 +1,136 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use url::Url;
+
+use crate::auth::Session;
+
+/// Consecutive transient failures after which an endpoint is ejected.
+pub const DEFAULT_EJECT_AFTER: u32 = 1;
+
+/// How long an ejected endpoint is skipped before it is tried again.
+pub const DEFAULT_EJECTION_PERIOD: Duration = Duration::from_secs(30);
+
+/// How commands are spread over the endpoints of a multi-node client.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EndpointSelection {
+    /// Every command goes to the first healthy endpoint in the configured
+    /// order; the others only take over while it is ejected.
+    #[default]
+    Failover,
+    /// Commands rotate over the healthy endpoints.
+    RoundRobin,
+}
+
+/// One REST endpoint of the cluster.
+#[derive(Debug)]
+pub(crate) struct Endpoint {
+    pub(crate) url: Url,
+    /// Session tokens are only known to the node that issued them, so each
+    /// endpoint logs in on its own.
+    pub(crate) session: Session,
+    health: Mutex<Health>,
+}
+
+#[derive(Debug, Default)]
+struct Health {
+    failures: u32,
+    ejected_until: Option<Instant>,
+}
+
+impl Endpoint {
+    /// End of the current ejection, `None` while the endpoint is healthy.
+    fn ejected_until(&self) -> Option<Instant> {
+        let health = self.health.lock().unwrap_or_else(|err| err.into_inner());
+        health.ejected_until.filter(|until| *until > Instant::now())
+    }
+}
+
+/// The endpoints of a client with their health, shared by all its clones.
+#[derive(Debug)]
+pub(crate) struct Endpoints {
+    list: Vec<Endpoint>,
+    selection: EndpointSelection,
+    next: AtomicUsize,
+    eject_after: u32,
+    ejection_period: Duration,
+}
+
+impl Endpoints {
+    /// `urls` must not be empty.
+    pub(crate) fn new(
+        urls: Vec<Url>,
+        selection: EndpointSelection,
+        eject_after: u32,
+        ejection_period: Duration,
+    ) -> Self {
+        let list = urls
+            .into_iter()
+            .map(|url| Endpoint {
+                url,
+                session: Session::default(),
+                health: Mutex::default(),
+            })
+            .collect();
+        Endpoints {
+            list,
+            selection,
+            next: AtomicUsize::new(0),
+            eject_after,
+            ejection_period,
+        }
+    }
+
+    pub(crate) fn get(&self, index: usize) -> &Endpoint {
+        &self.list[index]
+    }
+
+    pub(crate) fn urls(&self) -> impl Iterator<Item = &Url> {
+        self.list.iter().map(|endpoint| &endpoint.url)
+    }
+
+    /// Endpoint for the next attempt of a command that already went to
+    /// `tried`: a healthy endpoint not tried yet, else the untried one whose
+    /// ejection ends first. Once every endpoint was tried, the same order is
+    /// applied to all of them.
+    pub(crate) fn pick(&self, tried: &[usize]) -> usize {
+        let count = self.list.len();
+        let start = match self.selection {
+            EndpointSelection::Failover => 0,
+            EndpointSelection::RoundRobin if tried.is_empty() => {
+                self.next.fetch_add(1, Ordering::Relaxed) % count
+            }
+            EndpointSelection::RoundRobin => tried[0],
+        };
+        let order = (0..count).map(|offset| (start + offset) % count);
+        // `None` sorts first, and ties go to the earliest in `order`.
+        order
+            .clone()
+            .filter(|index| !tried.contains(index))
+            .min_by_key(|&index| self.list[index].ejected_until())
+            .or_else(|| order.min_by_key(|&index| self.list[index].ejected_until()))
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn record_success(&self, index: usize) {
+        let mut health = self.list[index]
+            .health
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        *health = Health::default();
+    }
+
+    /// Counts a transient failure and ejects the endpoint once there were
+    /// `eject_after` of them in a row; zero disables ejection.
+    pub(crate) fn record_failure(&self, index: usize) {
+        let mut health = self.list[index]
+            .health
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        health.failures = health.failures.saturating_add(1);
+        if self.eject_after > 0 && health.failures >= self.eject_after {
+            health.ejected_until = Some(Instant::now() + self.ejection_period);
+        }
+    }
+}
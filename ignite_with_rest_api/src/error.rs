@@ -0,0 +1,161 @@
+use std::fmt;
+
+use crate::row::RowDecodeError;
+
+pub type Result<T, E = IgniteRestError> = std::result::Result<T, E>;
+
+/// `successStatus` values defined by the Ignite REST protocol.
+pub const STATUS_SUCCESS: u32 = 0;
+pub const STATUS_FAILED: u32 = 1;
+pub const STATUS_AUTH_FAILED: u32 = 2;
+pub const STATUS_SECURITY_CHECK_FAILED: u32 = 3;
+
+#[derive(Debug, thiserror::Error)]
+pub enum IgniteRestError {
+    /// The request never produced an HTTP response, or the response was not 2xx.
+    #[error("transport error: {0}")]
+    Transport(#[from] reqwest::Error),
+
+    /// The body was not the JSON the command is expected to return.
+    #[error("failed to decode `{command}` response: {source}")]
+    Decode {
+        command: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// A key, value or argument could not be turned into a request parameter.
+    #[error("failed to encode parameter: {0}")]
+    Encode(String),
+
+    /// `successStatus` 1 for a non-SQL command.
+    #[error("`{command}` failed: {message}")]
+    CommandFailed { command: String, message: String },
+
+    /// `successStatus` 2.
+    #[error("authentication failed: {0}")]
+    AuthenticationFailed(String),
+
+    /// `successStatus` 3.
+    #[error("security check failed: {0}")]
+    SecurityCheckFailed(String),
+
+    /// `successStatus` 1 for a SQL command, classified from Ignite's message.
+    #[error("SQL error: {0}")]
+    Sql(SqlError),
+
+    /// A result row did not match the requested Rust type.
+    #[error("failed to decode {0}")]
+    RowDecode(#[from] RowDecodeError),
+
+    /// Decoded rows could not be assembled into an Arrow record batch.
+    #[cfg(feature = "arrow")]
+    #[error("failed to build record batch: {0}")]
+    Arrow(#[from] arrow::error::ArrowError),
+
+    /// The client was configured with invalid settings.
+    #[error("invalid configuration: {0}")]
+    Config(String),
+}
+
+impl IgniteRestError {
+    /// Classifies a failed response by its `successStatus`.
+    pub(crate) fn from_status(command: &str, status: u32, message: Option<String>) -> Self {
+        let message = message.unwrap_or_else(|| format!("status {status} without error message"));
+        match status {
+            STATUS_AUTH_FAILED => IgniteRestError::AuthenticationFailed(message),
+            STATUS_SECURITY_CHECK_FAILED => IgniteRestError::SecurityCheckFailed(message),
+            _ if is_sql_command(command) => IgniteRestError::Sql(SqlError::parse(message)),
+            _ => IgniteRestError::CommandFailed {
+                command: command.to_string(),
+                message,
+            },
+        }
+    }
+
+    /// Kind of the SQL error, if this is one.
+    pub fn sql_kind(&self) -> Option<SqlErrorKind> {
+        match self {
+            IgniteRestError::Sql(err) => Some(err.kind),
+            _ => None,
+        }
+    }
+}
+
+fn is_sql_command(command: &str) -> bool {
+    matches!(command, "qryfldexe" | "qryfetch" | "qryexe")
+}
+
+/// SQL failure reported by Ignite, with the original message preserved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlError {
+    pub kind: SqlErrorKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SqlErrorKind {
+    TableAlreadyExists,
+    TableNotFound,
+    IndexAlreadyExists,
+    IndexNotFound,
+    ColumnNotFound,
+    DuplicateKey,
+    NullNotAllowed,
+    ConversionFailed,
+    SyntaxError,
+    Other,
+}
+
+impl SqlError {
+    /// Classifies an Ignite SQL error message.
+    ///
+    /// Ignite's REST connector only returns the exception text, so this
+    /// matches the phrases used by its DDL and H2 parser errors.
+    pub fn parse(message: impl Into<String>) -> Self {
+        let message = message.into();
+        let lower = message.to_ascii_lowercase();
+
+        let kind = if lower.contains("table already exists") {
+            SqlErrorKind::TableAlreadyExists
+        } else if lower.contains("index already exists") {
+            SqlErrorKind::IndexAlreadyExists
+        } else if lower.contains("table doesn't exist")
+            || lower.contains("table does not exist")
+            || (lower.contains("table \"") && lower.contains("\" not found"))
+        {
+            SqlErrorKind::TableNotFound
+        } else if lower.contains("index doesn't exist")
+            || (lower.contains("index \"") && lower.contains("\" not found"))
+        {
+            SqlErrorKind::IndexNotFound
+        } else if lower.contains("column \"") && lower.contains("\" not found") {
+            SqlErrorKind::ColumnNotFound
+        } else if lower.contains("duplicate key") {
+            SqlErrorKind::DuplicateKey
+        } else if lower.contains("null value is not allowed") {
+            SqlErrorKind::NullNotAllowed
+        } else if lower.contains("conversion failed")
+            || lower.contains("data conversion error")
+            || lower.contains("cannot parse")
+            || lower.contains("value too long")
+            || lower.contains("out of range")
+        {
+            SqlErrorKind::ConversionFailed
+        } else if lower.contains("syntax error") {
+            SqlErrorKind::SyntaxError
+        } else {
+            SqlErrorKind::Other
+        };
+
+        SqlError { kind, message }
+    }
+}
+
+impl fmt::Display for SqlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.kind, self.message)
+    }
+}
+
+impl std::error::Error for SqlError {}
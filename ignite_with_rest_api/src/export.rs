@@ -0,0 +1,480 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use futures::stream::{BoxStream, StreamExt};
+use parquet::basic::{Compression, LogicalType, Repetition, TimeUnit, Type as PhysicalType};
+use parquet::column::writer::ColumnWriterImpl;
+use parquet::data_type::{
+    BoolType, ByteArray, ByteArrayType, DataType, DoubleType, FixedLenByteArray,
+    FixedLenByteArrayType, FloatType, Int32Type, Int64Type,
+};
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::{SerializedColumnWriter, SerializedFileWriter};
+use parquet::schema::types::Type;
+use serde_json::Value;
+
+use crate::args::{SqlArg, ToSqlArg};
+use crate::cache::RestCache;
+use crate::client::IgniteRestClient;
+use crate::error::IgniteRestError;
+use crate::format::{CsvWriter, JsonLinesWriter, RowWriter};
+use crate::response::FieldMetadata;
+use crate::sql::is_table_name;
+use crate::value::{IgniteValue, JavaType, decode_values, epoch_days, micros_since_midnight};
+
+/// Rows per Parquet row group unless [`Exporter::row_group_size`] says
+/// otherwise; the exporter holds one row group in memory at a time.
+pub const DEFAULT_ROW_GROUP_SIZE: usize = 10_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExportError {
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("failed to write export: {0}")]
+    Write(#[source] io::Error),
+
+    #[error("invalid table name `{0}`")]
+    InvalidTable(String),
+
+    #[error(transparent)]
+    Rest(#[from] IgniteRestError),
+}
+
+pub type ExportResult<T> = std::result::Result<T, ExportError>;
+
+/// File format of an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// RFC 4180 CSV with a header row, as [`OutputFormat::Csv`](crate::OutputFormat::Csv).
+    #[default]
+    Csv,
+    /// One JSON object per row, as [`OutputFormat::JsonLines`](crate::OutputFormat::JsonLines).
+    JsonLines,
+    /// Snappy-compressed Parquet with one optional column per field, typed
+    /// from its `fieldTypeName`. `BigDecimal` columns are written as text,
+    /// since Ignite reports neither their precision nor their scale.
+    Parquet,
+}
+
+impl ExportFormat {
+    /// Format named by the extension of `path`: `.csv`, `.jsonl`/`.ndjson` or
+    /// `.parquet`.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<ExportFormat> {
+        let extension = path.as_ref().extension()?.to_str()?;
+        extension.parse().ok()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::JsonLines => "jsonl",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "jsonl" | "json-lines" | "ndjson" => Ok(ExportFormat::JsonLines),
+            "parquet" => Ok(ExportFormat::Parquet),
+            _ => Err(format!(
+                "unknown export format `{name}`, expected one of: csv, jsonl, parquet"
+            )),
+        }
+    }
+}
+
+/// Writes every row of a table, query or cache to a file.
+///
+/// Rows are streamed: pages are fetched with `qryfetch` as the previous one
+/// is written, so memory use depends on the client's page size (and, for
+/// Parquet, the row group size) rather than on the size of the result.
+pub struct Exporter {
+    source: Source,
+    format: ExportFormat,
+    row_group_size: usize,
+}
+
+enum Source {
+    Query {
+        client: IgniteRestClient,
+        sql: String,
+        args: Vec<SqlArg>,
+    },
+    Cache(RestCache),
+}
+
+impl Exporter {
+    /// Exports `SELECT * FROM table`; `table` may be `SCHEMA.TABLE`.
+    pub fn table(client: IgniteRestClient, table: impl Into<String>) -> ExportResult<Self> {
+        let table = table.into();
+        if !is_table_name(&table) {
+            return Err(ExportError::InvalidTable(table));
+        }
+        Ok(Self::query(client, format!("SELECT * FROM {table}"), &[]))
+    }
+
+    /// Exports the rows of `sql`, run against the client's default cache.
+    pub fn query(client: IgniteRestClient, sql: impl Into<String>, args: &[&dyn ToSqlArg]) -> Self {
+        Self::new(Source::Query {
+            client,
+            sql: sql.into(),
+            args: args.iter().map(|arg| arg.to_sql_arg()).collect(),
+        })
+    }
+
+    /// Exports every entry of `cache` with `qryscanexe`, as `KEY` and `VALUE`
+    /// columns holding the JSON the connector returns.
+    pub fn cache(cache: RestCache) -> Self {
+        Self::new(Source::Cache(cache))
+    }
+
+    fn new(source: Source) -> Self {
+        Exporter {
+            source,
+            format: ExportFormat::default(),
+            row_group_size: DEFAULT_ROW_GROUP_SIZE,
+        }
+    }
+
+    pub fn format(mut self, format: ExportFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Rows per Parquet row group; at least one.
+    pub fn row_group_size(mut self, row_group_size: usize) -> Self {
+        self.row_group_size = row_group_size.max(1);
+        self
+    }
+
+    /// Writes the export to `path`, returning the number of rows.
+    ///
+    /// Data goes to `<path>.partial` first, which is renamed to `path` once
+    /// complete and removed if the export fails, so `path` never holds a
+    /// truncated export.
+    pub async fn export_file(self, path: impl AsRef<Path>) -> ExportResult<u64> {
+        let path = path.as_ref();
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+        let partial = PathBuf::from(partial);
+        let io_error = |path: &Path| {
+            let path = path.to_path_buf();
+            move |source| ExportError::Io { path, source }
+        };
+
+        let file = File::create(&partial).map_err(io_error(&partial))?;
+        let written = match self.export_writer(BufWriter::new(file)).await {
+            Ok(written) => written,
+            Err(err) => {
+                let _ = fs::remove_file(&partial);
+                return Err(match err {
+                    ExportError::Write(source) => io_error(&partial)(source),
+                    other => other,
+                });
+            }
+        };
+        fs::rename(&partial, path).map_err(io_error(path))?;
+        Ok(written)
+    }
+
+    /// Writes the export to `out`, returning the number of rows.
+    pub async fn export_writer<W: Write + Send>(self, out: W) -> ExportResult<u64> {
+        let (fields, mut rows) = self.open().await?;
+        let mut writer: Box<dyn RowWriter + Send + '_> = match self.format {
+            ExportFormat::Csv => Box::new(CsvWriter::new(out)),
+            ExportFormat::JsonLines => Box::new(JsonLinesWriter::new(out)),
+            ExportFormat::Parquet => Box::new(ParquetWriter::new(out, self.row_group_size)),
+        };
+
+        writer.begin(&fields).map_err(ExportError::Write)?;
+        let mut written = 0;
+        while let Some(row) = rows.next().await {
+            writer.row(&row?).map_err(ExportError::Write)?;
+            written += 1;
+        }
+        writer.finish().map_err(ExportError::Write)?;
+        Ok(written)
+    }
+
+    /// Runs the query or scan, returning the columns and a stream of rows.
+    async fn open(
+        &self,
+    ) -> ExportResult<(
+        Vec<FieldMetadata>,
+        BoxStream<'static, Result<Vec<Value>, IgniteRestError>>,
+    )> {
+        match &self.source {
+            Source::Query { client, sql, args } => {
+                let args: Vec<&dyn ToSqlArg> =
+                    args.iter().map(|arg| arg as &dyn ToSqlArg).collect();
+                let cursor = client.query(sql, &args).await?;
+                Ok((cursor.fields().to_vec(), cursor.boxed()))
+            }
+            Source::Cache(cache) => {
+                let fields = ["KEY", "VALUE"]
+                    .map(|name| FieldMetadata {
+                        field_name: name.to_string(),
+                        field_type_name: "java.lang.Object".to_string(),
+                    })
+                    .to_vec();
+                let entries = cache
+                    .scan()
+                    .await?
+                    .map(|entry| entry.map(|entry| vec![entry.key, entry.value]));
+                Ok((fields, entries.boxed()))
+            }
+        }
+    }
+}
+
+/// Buffers a row group of converted cells and writes it column by column.
+struct ParquetWriter<W: Write + Send> {
+    out: Option<W>,
+    file: Option<SerializedFileWriter<W>>,
+    row_group_size: usize,
+    fields: Vec<FieldMetadata>,
+    types: Vec<JavaType>,
+    rows: Vec<Vec<IgniteValue>>,
+    /// Rows already written, to number decoding errors.
+    offset: usize,
+}
+
+impl<W: Write + Send> ParquetWriter<W> {
+    fn new(out: W, row_group_size: usize) -> Self {
+        ParquetWriter {
+            out: Some(out),
+            file: None,
+            row_group_size,
+            fields: Vec::new(),
+            types: Vec::new(),
+            rows: Vec::new(),
+            offset: 0,
+        }
+    }
+
+    fn write_row_group(&mut self) -> io::Result<()> {
+        let Some(file) = &mut self.file else {
+            return Ok(());
+        };
+        let mut group = file.next_row_group().map_err(io::Error::other)?;
+        let mut index = 0;
+        while let Some(mut column) = group.next_column().map_err(io::Error::other)? {
+            let cells = self.rows.iter().map(|row| &row[index]);
+            write_column(&mut column, &self.types[index], cells).map_err(io::Error::other)?;
+            column.close().map_err(io::Error::other)?;
+            index += 1;
+        }
+        group.close().map_err(io::Error::other)?;
+        self.offset += self.rows.len();
+        self.rows.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write + Send> RowWriter for ParquetWriter<W> {
+    fn begin(&mut self, fields: &[FieldMetadata]) -> io::Result<()> {
+        let Some(out) = self.out.take() else {
+            return Ok(());
+        };
+        let columns = fields
+            .iter()
+            .map(|field| parquet_column(field).map(Arc::new))
+            .collect::<Result<_, _>>()
+            .map_err(io::Error::other)?;
+        let schema = Type::group_type_builder("schema")
+            .with_fields(columns)
+            .build()
+            .map_err(io::Error::other)?;
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let file = SerializedFileWriter::new(out, Arc::new(schema), Arc::new(properties))
+            .map_err(io::Error::other)?;
+
+        self.file = Some(file);
+        self.fields = fields.to_vec();
+        self.types = fields.iter().map(FieldMetadata::java_type).collect();
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[Value]) -> io::Result<()> {
+        let index = self.offset + self.rows.len();
+        let values = decode_values(&self.fields, &self.types, row, index)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        self.rows.push(values);
+        if self.rows.len() >= self.row_group_size {
+            self.write_row_group()?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        if !self.rows.is_empty() {
+            self.write_row_group()?;
+        }
+        match self.file.take() {
+            Some(file) => file.into_inner().map_err(io::Error::other)?.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Optional Parquet column for a field, with the logical type matching its
+/// Java class. Dates, times and timestamps carry no time zone in Ignite and
+/// are written as local, non-UTC-adjusted values; times and timestamps in
+/// microseconds.
+fn parquet_column(field: &FieldMetadata) -> Result<Type, ParquetError> {
+    let (physical, logical, length) = match field.java_type() {
+        JavaType::Boolean => (PhysicalType::BOOLEAN, None, None),
+        JavaType::Byte => (
+            PhysicalType::INT32,
+            Some(LogicalType::integer(8, true)),
+            None,
+        ),
+        JavaType::Short => (
+            PhysicalType::INT32,
+            Some(LogicalType::integer(16, true)),
+            None,
+        ),
+        JavaType::Integer => (PhysicalType::INT32, None, None),
+        JavaType::Long => (PhysicalType::INT64, None, None),
+        JavaType::Float => (PhysicalType::FLOAT, None, None),
+        JavaType::Double => (PhysicalType::DOUBLE, None, None),
+        JavaType::Decimal | JavaType::String | JavaType::Char => {
+            (PhysicalType::BYTE_ARRAY, Some(LogicalType::String), None)
+        }
+        JavaType::Uuid => (
+            PhysicalType::FIXED_LEN_BYTE_ARRAY,
+            Some(LogicalType::Uuid),
+            Some(16),
+        ),
+        JavaType::Date => (PhysicalType::INT32, Some(LogicalType::Date), None),
+        JavaType::Time => (
+            PhysicalType::INT64,
+            Some(LogicalType::time(false, TimeUnit::MICROS)),
+            None,
+        ),
+        JavaType::Timestamp => (
+            PhysicalType::INT64,
+            Some(LogicalType::timestamp(false, TimeUnit::MICROS)),
+            None,
+        ),
+        JavaType::Bytes => (PhysicalType::BYTE_ARRAY, None, None),
+        JavaType::Other(_) => (PhysicalType::BYTE_ARRAY, Some(LogicalType::Json), None),
+    };
+    let mut builder = Type::primitive_type_builder(&field.field_name, physical)
+        .with_repetition(Repetition::OPTIONAL)
+        .with_logical_type(logical);
+    if let Some(length) = length {
+        builder = builder.with_length(length);
+    }
+    builder.build()
+}
+
+fn write_column<'a>(
+    column: &mut SerializedColumnWriter<'_>,
+    java_type: &JavaType,
+    cells: impl Iterator<Item = &'a IgniteValue>,
+) -> Result<(), ParquetError> {
+    let text = |text: &str| ByteArray::from(text.as_bytes().to_vec());
+    match java_type {
+        JavaType::Boolean => {
+            write_values::<BoolType, _>(column.typed(), cells, |cell| match cell {
+                IgniteValue::Boolean(value) => Some(*value),
+                _ => None,
+            })
+        }
+        JavaType::Byte | JavaType::Short | JavaType::Integer | JavaType::Date => {
+            write_values::<Int32Type, _>(column.typed(), cells, |cell| match cell {
+                IgniteValue::Byte(value) => Some(i32::from(*value)),
+                IgniteValue::Short(value) => Some(i32::from(*value)),
+                IgniteValue::Int(value) => Some(*value),
+                IgniteValue::Date(date) => Some(epoch_days(*date)),
+                _ => None,
+            })
+        }
+        JavaType::Long | JavaType::Time | JavaType::Timestamp => {
+            write_values::<Int64Type, _>(column.typed(), cells, |cell| match cell {
+                IgniteValue::Long(value) => Some(*value),
+                IgniteValue::Time(time) => Some(micros_since_midnight(*time)),
+                IgniteValue::Timestamp(timestamp) => Some(timestamp.and_utc().timestamp_micros()),
+                _ => None,
+            })
+        }
+        JavaType::Float => write_values::<FloatType, _>(column.typed(), cells, |cell| match cell {
+            IgniteValue::Float(value) => Some(*value),
+            _ => None,
+        }),
+        JavaType::Double => {
+            write_values::<DoubleType, _>(column.typed(), cells, |cell| match cell {
+                IgniteValue::Double(value) => Some(*value),
+                _ => None,
+            })
+        }
+        JavaType::Uuid => {
+            write_values::<FixedLenByteArrayType, _>(column.typed(), cells, |cell| match cell {
+                IgniteValue::Uuid(uuid) => Some(FixedLenByteArray::from(uuid.as_bytes().to_vec())),
+                _ => None,
+            })
+        }
+        JavaType::Decimal
+        | JavaType::String
+        | JavaType::Char
+        | JavaType::Bytes
+        | JavaType::Other(_) => {
+            write_values::<ByteArrayType, _>(column.typed(), cells, |cell| match cell {
+                IgniteValue::Decimal(value) => Some(text(&value.to_string())),
+                IgniteValue::String(value) => Some(text(value)),
+                IgniteValue::Bytes(value) => Some(ByteArray::from(value.clone())),
+                IgniteValue::Other(value) => Some(text(&value.to_string())),
+                _ => None,
+            })
+        }
+    }
+}
+
+/// Writes the non-null cells as values, with a definition level of 0 for
+/// every null.
+fn write_values<'a, T: DataType, F>(
+    writer: &mut ColumnWriterImpl<'_, T>,
+    cells: impl Iterator<Item = &'a IgniteValue>,
+    value: F,
+) -> Result<(), ParquetError>
+where
+    F: Fn(&IgniteValue) -> Option<T::T>,
+{
+    let mut values = Vec::new();
+    let mut levels = Vec::new();
+    for cell in cells {
+        match value(cell) {
+            Some(value) => {
+                values.push(value);
+                levels.push(1);
+            }
+            None => levels.push(0),
+        }
+    }
+    writer.write_batch(&values, Some(&levels), None)?;
+    Ok(())
+}
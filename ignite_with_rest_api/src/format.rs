@@ -0,0 +1,339 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::response::FieldMetadata;
+
+/// How query results are written out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Aligned text table for humans; see [`render_table`].
+    #[default]
+    Table,
+    /// RFC 4180 CSV with a header row; NULL is an empty field.
+    Csv,
+    /// One JSON object per line, keyed by column name.
+    JsonLines,
+    /// GitHub-flavoured Markdown table.
+    Markdown,
+}
+
+impl OutputFormat {
+    /// Writer that emits rows in this format to `out` as they are given.
+    ///
+    /// Every format but [`OutputFormat::Table`] streams; the table has to
+    /// see all rows to size its columns and writes them on `finish`.
+    pub fn writer<'a, W: Write + 'a>(self, out: W) -> Box<dyn RowWriter + 'a> {
+        match self {
+            OutputFormat::Table => Box::new(TableWriter {
+                out,
+                fields: Vec::new(),
+                rows: Vec::new(),
+            }),
+            OutputFormat::Csv => Box::new(CsvWriter::new(out)),
+            OutputFormat::JsonLines => Box::new(JsonLinesWriter::new(out)),
+            OutputFormat::Markdown => Box::new(MarkdownWriter { out }),
+        }
+    }
+
+    /// Renders a complete result in this format.
+    pub fn render(self, fields: &[FieldMetadata], rows: &[Vec<Value>]) -> String {
+        let mut out = Vec::new();
+        let mut writer = self.writer(&mut out);
+        // Writing into a Vec cannot fail.
+        let _ = write_all(writer.as_mut(), fields, rows);
+        drop(writer);
+        String::from_utf8_lossy(&out).into_owned()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            OutputFormat::Table => "table",
+            OutputFormat::Csv => "csv",
+            OutputFormat::JsonLines => "jsonl",
+            OutputFormat::Markdown => "markdown",
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "table" => Ok(OutputFormat::Table),
+            "csv" => Ok(OutputFormat::Csv),
+            "jsonl" | "json-lines" | "ndjson" => Ok(OutputFormat::JsonLines),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            _ => Err(format!(
+                "unknown output format `{name}`, expected one of: table, csv, jsonl, markdown"
+            )),
+        }
+    }
+}
+
+/// Receives a result one row at a time: `begin` once with the columns, `row`
+/// per row, then `finish`.
+pub trait RowWriter {
+    fn begin(&mut self, fields: &[FieldMetadata]) -> io::Result<()>;
+    fn row(&mut self, row: &[Value]) -> io::Result<()>;
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+/// Feeds a complete result through `writer`.
+pub fn write_all(
+    writer: &mut dyn RowWriter,
+    fields: &[FieldMetadata],
+    rows: &[Vec<Value>],
+) -> io::Result<()> {
+    writer.begin(fields)?;
+    for row in rows {
+        writer.row(row)?;
+    }
+    writer.finish()
+}
+
+struct TableWriter<W> {
+    out: W,
+    fields: Vec<FieldMetadata>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl<W: Write> RowWriter for TableWriter<W> {
+    fn begin(&mut self, fields: &[FieldMetadata]) -> io::Result<()> {
+        self.fields = fields.to_vec();
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[Value]) -> io::Result<()> {
+        self.rows.push(row.to_vec());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.write_all(render_table(&self.fields, &self.rows).as_bytes())?;
+        self.out.flush()
+    }
+}
+
+pub(crate) struct CsvWriter<W> {
+    out: W,
+}
+
+impl<W: Write> CsvWriter<W> {
+    pub(crate) fn new(out: W) -> Self {
+        CsvWriter { out }
+    }
+
+    fn record<'a>(&mut self, fields: impl Iterator<Item = &'a str>) -> io::Result<()> {
+        let record = fields.map(csv_field).collect::<Vec<_>>().join(",");
+        self.out.write_all(record.as_bytes())?;
+        self.out.write_all(b"\r\n")
+    }
+}
+
+impl<W: Write> RowWriter for CsvWriter<W> {
+    fn begin(&mut self, fields: &[FieldMetadata]) -> io::Result<()> {
+        self.record(fields.iter().map(|field| field.field_name.as_str()))
+    }
+
+    fn row(&mut self, row: &[Value]) -> io::Result<()> {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|value| match value {
+                Value::Null => String::new(),
+                other => cell_text(other),
+            })
+            .collect();
+        self.record(cells.iter().map(String::as_str))
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Quotes a CSV field when it contains a comma, quote or line break,
+/// doubling any quotes inside (RFC 4180, section 2).
+pub fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+pub(crate) struct JsonLinesWriter<W> {
+    out: W,
+    names: Vec<String>,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    pub(crate) fn new(out: W) -> Self {
+        JsonLinesWriter {
+            out,
+            names: Vec::new(),
+        }
+    }
+}
+
+impl<W: Write> RowWriter for JsonLinesWriter<W> {
+    fn begin(&mut self, fields: &[FieldMetadata]) -> io::Result<()> {
+        self.names = fields
+            .iter()
+            .map(|field| Value::String(field.field_name.clone()).to_string())
+            .collect();
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[Value]) -> io::Result<()> {
+        // Written by hand so that keys keep the column order.
+        let mut line = String::from("{");
+        for (index, value) in row.iter().enumerate() {
+            if index > 0 {
+                line.push(',');
+            }
+            match self.names.get(index) {
+                Some(name) => line.push_str(name),
+                None => line.push_str(&Value::String(index.to_string()).to_string()),
+            }
+            line.push(':');
+            line.push_str(&value.to_string());
+        }
+        line.push_str("}\n");
+        self.out.write_all(line.as_bytes())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+struct MarkdownWriter<W> {
+    out: W,
+}
+
+impl<W: Write> MarkdownWriter<W> {
+    fn line(&mut self, cells: impl Iterator<Item = String>) -> io::Result<()> {
+        let mut line = String::from("|");
+        for cell in cells {
+            line.push(' ');
+            line.push_str(&cell);
+            line.push_str(" |");
+        }
+        line.push('\n');
+        self.out.write_all(line.as_bytes())
+    }
+}
+
+impl<W: Write> RowWriter for MarkdownWriter<W> {
+    fn begin(&mut self, fields: &[FieldMetadata]) -> io::Result<()> {
+        self.line(fields.iter().map(|field| markdown_cell(&field.field_name)))?;
+        self.line(fields.iter().map(|_| "---".to_string()))
+    }
+
+    fn row(&mut self, row: &[Value]) -> io::Result<()> {
+        self.line(row.iter().map(|value| markdown_cell(&cell_text(value))))
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Escapes pipes and turns line breaks into `<br>` so a cell stays on its row.
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\r', '\n'], "<br>")
+}
+
+/// Renders rows as an aligned text table. The header shows each column's
+/// name with its Java type below it, e.g. `AGE` / `Integer`. Line breaks
+/// and tabs inside cells are shown as `\n`, `\r` and `\t` so that every
+/// row stays on one line.
+pub fn render_table(fields: &[FieldMetadata], rows: &[Vec<Value>]) -> String {
+    let names: Vec<String> = fields
+        .iter()
+        .map(|field| table_cell(&field.field_name))
+        .collect();
+    let types: Vec<String> = fields
+        .iter()
+        .map(|field| short_type_name(&field.field_type_name).to_string())
+        .collect();
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|value| table_cell(&cell_text(value))).collect())
+        .collect();
+
+    let columns = names.len().max(cells.iter().map(Vec::len).max().unwrap_or(0));
+    let mut widths = vec![0; columns];
+    for line in [&names, &types].into_iter().chain(&cells) {
+        for (width, text) in widths.iter_mut().zip(line) {
+            *width = (*width).max(text.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let separator = separator_line(&widths);
+    out.push_str(&separator);
+    push_line(&mut out, &widths, &names);
+    push_line(&mut out, &widths, &types);
+    out.push_str(&separator);
+    for line in &cells {
+        push_line(&mut out, &widths, line);
+    }
+    out.push_str(&separator);
+    out
+}
+
+fn table_cell(text: &str) -> String {
+    text.replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+}
+
+/// Text of a single cell: strings unquoted, `NULL` for null, JSON otherwise.
+pub fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// `java.lang.Integer` -> `Integer`; array types such as `[B` are kept.
+pub fn short_type_name(java_type: &str) -> &str {
+    java_type.rsplit('.').next().unwrap_or(java_type)
+}
+
+fn separator_line(widths: &[usize]) -> String {
+    let mut line = String::from("+");
+    for width in widths {
+        line.push_str(&"-".repeat(width + 2));
+        line.push('+');
+    }
+    line.push('\n');
+    line
+}
+
+fn push_line(out: &mut String, widths: &[usize], texts: &[String]) {
+    out.push('|');
+    for (index, width) in widths.iter().enumerate() {
+        let text = texts.get(index).map(String::as_str).unwrap_or("");
+        let padding = width - text.chars().count();
+        out.push(' ');
+        out.push_str(text);
+        out.push_str(&" ".repeat(padding + 1));
+        out.push('|');
+    }
+    out.push('\n');
+}
@@ -0,0 +1,612 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use csv::{ByteRecord, ReaderBuilder, WriterBuilder};
+use serde_json::Value;
+
+use crate::args::ToSqlArg;
+use crate::cache::RestCache;
+use crate::client::IgniteRestClient;
+use crate::error::{IgniteRestError, SqlErrorKind};
+use crate::sql::is_table_name;
+use crate::value::{IgniteValue, JavaType};
+
+/// Rows per `INSERT`/`MERGE` statement or `putall` unless
+/// [`CsvImporter::batch_size`] says otherwise.
+pub const DEFAULT_IMPORT_BATCH_SIZE: usize = 500;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("malformed CSV: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("invalid table name `{0}`")]
+    InvalidTable(String),
+
+    #[error("CSV header `{0}` matches no column of the table")]
+    UnmappedHeader(String),
+
+    #[error("CSV header `{header}` is mapped to `{column}`, which is not a column of the table")]
+    UnknownColumn { header: String, column: String },
+
+    /// Every header was skipped by [`CsvImporter::skip_unmapped`].
+    #[error("no CSV header matches a column of the table")]
+    NoMappedColumns,
+
+    #[error("column `{0}` is filled from more than one CSV header")]
+    DuplicateColumn(String),
+
+    #[error("CSV has no `{0}` column")]
+    MissingColumn(String),
+
+    /// Rows before `resume_line` are written or rejected; pass it to
+    /// [`CsvImporter::resume_from_line`] to continue once the cause is fixed.
+    #[error("import stopped, resume from line {resume_line}: {source}")]
+    Interrupted {
+        resume_line: u64,
+        #[source]
+        source: IgniteRestError,
+    },
+
+    #[error(transparent)]
+    Rest(#[from] IgniteRestError),
+}
+
+pub type ImportResult<T> = std::result::Result<T, ImportError>;
+
+/// Statement used to write table rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImportMode {
+    /// `INSERT`: rows whose key already exists are rejected.
+    #[default]
+    Insert,
+    /// `MERGE`: existing rows are overwritten, so a file can be imported
+    /// again, or resumed earlier than needed, without rejections.
+    Merge,
+}
+
+/// Counters reported after every batch and returned at the end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ImportProgress {
+    pub rows_written: u64,
+    pub rows_rejected: u64,
+    /// Line of the first record not yet written or rejected.
+    pub resume_line: u64,
+}
+
+/// Loads CSV files into a SQL table or a key-value cache.
+///
+/// The first record is a header naming the target column of each field.
+/// For tables, headers match column names case-insensitively and every
+/// field is converted to the column's type as reported by the table's
+/// live metadata; an empty field is NULL. Records that cannot be converted,
+/// and rows the cluster refuses as a duplicate key, a NULL in a NOT NULL
+/// column or a value it cannot convert, go to the
+/// [rejects file](Self::rejects_file) instead of stopping the import. Any
+/// other failure stops it with [`ImportError::Interrupted`].
+///
+/// A batch the cluster rejects as a whole is written again row by row to
+/// find the offending rows. With [`ImportMode::Insert`], rows of that batch
+/// which Ignite had already stored before failing are then reported as
+/// duplicates; [`ImportMode::Merge`] avoids that.
+pub struct CsvImporter {
+    target: Target,
+    batch_size: usize,
+    delimiter: u8,
+    null_value: String,
+    column_map: Vec<(String, String)>,
+    skip_unmapped: bool,
+    rejects_file: Option<PathBuf>,
+    resume_from_line: u64,
+    on_progress: Option<ProgressFn>,
+}
+
+type ProgressFn = Box<dyn FnMut(&ImportProgress) + Send>;
+
+enum Target {
+    Table {
+        client: IgniteRestClient,
+        table: String,
+        mode: ImportMode,
+    },
+    Cache {
+        cache: RestCache,
+        key_column: String,
+        value_column: String,
+    },
+}
+
+impl CsvImporter {
+    /// Importer that writes to SQL table `table`, optionally `SCHEMA.TABLE`.
+    pub fn into_table(client: IgniteRestClient, table: impl Into<String>) -> ImportResult<Self> {
+        let table = table.into();
+        if !is_table_name(&table) {
+            return Err(ImportError::InvalidTable(table));
+        }
+        Ok(Self::new(Target::Table {
+            client,
+            table,
+            mode: ImportMode::default(),
+        }))
+    }
+
+    /// Importer that stores each record with `putall`, taking the key and
+    /// value from the named CSV columns. They are sent as text, typed by the
+    /// cache's [`key_type`](RestCache::key_type) and
+    /// [`value_type`](RestCache::value_type).
+    pub fn into_cache(
+        cache: RestCache,
+        key_column: impl Into<String>,
+        value_column: impl Into<String>,
+    ) -> Self {
+        Self::new(Target::Cache {
+            cache,
+            key_column: key_column.into(),
+            value_column: value_column.into(),
+        })
+    }
+
+    fn new(target: Target) -> Self {
+        CsvImporter {
+            target,
+            batch_size: DEFAULT_IMPORT_BATCH_SIZE,
+            delimiter: b',',
+            null_value: String::new(),
+            column_map: Vec::new(),
+            skip_unmapped: false,
+            rejects_file: None,
+            resume_from_line: 0,
+            on_progress: None,
+        }
+    }
+
+    /// Whether table rows are inserted or merged; ignored for caches.
+    pub fn mode(mut self, mode: ImportMode) -> Self {
+        if let Target::Table { mode: current, .. } = &mut self.target {
+            *current = mode;
+        }
+        self
+    }
+
+    /// Rows per request; at least one.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Field text read as NULL, empty by default.
+    pub fn null_value(mut self, null_value: impl Into<String>) -> Self {
+        self.null_value = null_value.into();
+        self
+    }
+
+    /// Fills table column `column` from the CSV field headed `header`.
+    pub fn map_column(mut self, header: impl Into<String>, column: impl Into<String>) -> Self {
+        self.column_map.push((header.into(), column.into()));
+        self
+    }
+
+    /// Ignores CSV fields whose header matches no column instead of failing.
+    /// The import still fails if that leaves no column to write.
+    pub fn skip_unmapped(mut self, skip_unmapped: bool) -> Self {
+        self.skip_unmapped = skip_unmapped;
+        self
+    }
+
+    /// Writes rejected records to `path` as CSV: the line number, the reason
+    /// and the original fields. The file is created on the first rejection,
+    /// and appended to when resuming.
+    pub fn rejects_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.rejects_file = Some(path.into());
+        self
+    }
+
+    /// Skips records that start before `line`, counting the header as line 1,
+    /// as reported by [`ImportProgress::resume_line`].
+    pub fn resume_from_line(mut self, line: u64) -> Self {
+        self.resume_from_line = line;
+        self
+    }
+
+    /// Called after every batch.
+    pub fn on_progress(
+        mut self,
+        on_progress: impl FnMut(&ImportProgress) + Send + 'static,
+    ) -> Self {
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+
+    pub async fn import_file(self, path: impl AsRef<Path>) -> ImportResult<ImportProgress> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|source| ImportError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        self.import_reader(file).await
+    }
+
+    pub async fn import_reader<R: Read>(mut self, input: R) -> ImportResult<ImportProgress> {
+        let mut reader = ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .flexible(true)
+            .from_reader(input);
+        let headers: Vec<String> = reader
+            .byte_headers()?
+            .iter()
+            .map(|header| String::from_utf8_lossy(header).trim().to_string())
+            .collect();
+        let plan = self.plan(&headers).await?;
+        let mut rejects = Rejects::new(
+            self.rejects_file.clone(),
+            &headers,
+            self.resume_from_line > 0,
+        );
+        let mut progress = ImportProgress {
+            resume_line: reader.position().line(),
+            ..ImportProgress::default()
+        };
+        let mut batch = Vec::new();
+        let mut record = ByteRecord::new();
+
+        loop {
+            let line = reader.position().line();
+            if !reader.read_byte_record(&mut record)? {
+                break;
+            }
+            if line < self.resume_from_line {
+                progress.resume_line = reader.position().line();
+                continue;
+            }
+            let row = plan.convert(&record, &self.null_value);
+            batch.push(Pending {
+                line,
+                record: record.clone(),
+                row,
+            });
+            if batch.len() >= self.batch_size {
+                self.flush(&plan, &mut batch, &mut rejects, &mut progress)
+                    .await?;
+                progress.resume_line = reader.position().line();
+                self.report(&progress);
+            }
+        }
+        if !batch.is_empty() {
+            self.flush(&plan, &mut batch, &mut rejects, &mut progress)
+                .await?;
+            progress.resume_line = reader.position().line();
+            self.report(&progress);
+        }
+        Ok(progress)
+    }
+
+    fn report(&mut self, progress: &ImportProgress) {
+        if let Some(on_progress) = &mut self.on_progress {
+            on_progress(progress);
+        }
+    }
+
+    /// Resolves the CSV headers against the target.
+    async fn plan(&self, headers: &[String]) -> ImportResult<Plan> {
+        let (client, table) = match &self.target {
+            Target::Table { client, table, .. } => (client, table),
+            Target::Cache {
+                key_column,
+                value_column,
+                ..
+            } => {
+                let find = |name: &String| {
+                    headers
+                        .iter()
+                        .position(|header| header.eq_ignore_ascii_case(name))
+                        .ok_or_else(|| ImportError::MissingColumn(name.clone()))
+                };
+                return Ok(Plan::Cache {
+                    key: find(key_column)?,
+                    value: find(value_column)?,
+                });
+            }
+        };
+
+        // Column names and types without reading any rows.
+        let metadata = client
+            .execute_sql(&format!("SELECT * FROM {table} LIMIT 0"), &[])
+            .await?
+            .fields_metadata
+            .unwrap_or_default();
+        let mut columns: Vec<Column> = Vec::new();
+        for (index, header) in headers.iter().enumerate() {
+            let mapped = self
+                .column_map
+                .iter()
+                .find(|(from, _)| from.eq_ignore_ascii_case(header))
+                .map(|(_, column)| column);
+            let name = mapped.unwrap_or(header);
+            let Some(field) = metadata
+                .iter()
+                .find(|field| field.field_name.eq_ignore_ascii_case(name))
+            else {
+                match mapped {
+                    Some(column) => {
+                        return Err(ImportError::UnknownColumn {
+                            header: header.clone(),
+                            column: column.clone(),
+                        });
+                    }
+                    None if self.skip_unmapped => continue,
+                    None => return Err(ImportError::UnmappedHeader(header.clone())),
+                }
+            };
+            if columns.iter().any(|column| column.name == field.field_name) {
+                return Err(ImportError::DuplicateColumn(field.field_name.clone()));
+            }
+            columns.push(Column {
+                index,
+                name: field.field_name.clone(),
+                java_type: field.java_type(),
+            });
+        }
+        if columns.is_empty() {
+            return Err(ImportError::NoMappedColumns);
+        }
+        Ok(Plan::Table(columns))
+    }
+
+    /// Writes `batch`, falling back to one row at a time when the cluster
+    /// refuses it, and records the outcome of every row.
+    async fn flush(
+        &self,
+        plan: &Plan,
+        batch: &mut Vec<Pending>,
+        rejects: &mut Rejects,
+        progress: &mut ImportProgress,
+    ) -> ImportResult<()> {
+        let pending = std::mem::take(batch);
+        let rows: Vec<&Row> = pending
+            .iter()
+            .filter_map(|pending| pending.row.as_ref().ok())
+            .collect();
+        let resume_line = progress.resume_line;
+        let interrupted = |source| ImportError::Interrupted {
+            resume_line,
+            source,
+        };
+
+        let mut failed = Vec::new();
+        match self.write(plan, &rows).await {
+            Ok(()) => progress.rows_written += rows.len() as u64,
+            Err(err) if is_rejection(&err) => {
+                for pending in &pending {
+                    let Ok(row) = &pending.row else { continue };
+                    match self.write(plan, &[row]).await {
+                        Ok(()) => progress.rows_written += 1,
+                        Err(err) if is_rejection(&err) => {
+                            failed.push((pending.line, err.to_string()))
+                        }
+                        Err(err) => return Err(interrupted(err)),
+                    }
+                }
+            }
+            Err(err) => return Err(interrupted(err)),
+        }
+
+        for pending in &pending {
+            let reason = match &pending.row {
+                Err(reason) => reason.clone(),
+                Ok(_) => match failed.iter().find(|(line, _)| *line == pending.line) {
+                    Some((_, reason)) => reason.clone(),
+                    None => continue,
+                },
+            };
+            rejects.write(pending.line, &reason, &pending.record)?;
+            progress.rows_rejected += 1;
+        }
+        Ok(())
+    }
+
+    async fn write(&self, plan: &Plan, rows: &[&Row]) -> Result<(), IgniteRestError> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        match &self.target {
+            Target::Table {
+                client,
+                table,
+                mode,
+            } => {
+                let verb = match mode {
+                    ImportMode::Insert => "INSERT",
+                    ImportMode::Merge => "MERGE",
+                };
+                let columns = plan.columns();
+                let names: Vec<String> = columns
+                    .iter()
+                    .map(|column| format!("\"{}\"", column.name.replace('"', "\"\"")))
+                    .collect();
+                let placeholders = format!("({})", vec!["?"; columns.len()].join(", "));
+                let sql = format!(
+                    "{verb} INTO {table} ({}) VALUES {}",
+                    names.join(", "),
+                    vec![placeholders; rows.len()].join(", ")
+                );
+                let mut args: Vec<&dyn ToSqlArg> = Vec::new();
+                for row in rows {
+                    if let Row::Table(values) = row {
+                        args.extend(values.iter().map(|value| value as &dyn ToSqlArg));
+                    }
+                }
+                client.execute_sql(&sql, &args).await.map(drop)
+            }
+            Target::Cache { cache, .. } => {
+                let entries: Vec<(&str, &str)> = rows
+                    .iter()
+                    .filter_map(|row| match row {
+                        Row::Cache(key, value) => Some((key.as_str(), value.as_str())),
+                        Row::Table(_) => None,
+                    })
+                    .collect();
+                cache.put_all(&entries).await.map(drop)
+            }
+        }
+    }
+}
+
+/// Errors that blame the rows rather than the connection, the table or the
+/// cluster; anything else stops the import so it can be resumed.
+fn is_rejection(err: &IgniteRestError) -> bool {
+    matches!(
+        err.sql_kind(),
+        Some(
+            SqlErrorKind::DuplicateKey
+                | SqlErrorKind::NullNotAllowed
+                | SqlErrorKind::ConversionFailed
+        )
+    ) || matches!(err, IgniteRestError::Encode(_))
+}
+
+struct Column {
+    /// Position of the field in the CSV record.
+    index: usize,
+    name: String,
+    java_type: JavaType,
+}
+
+enum Plan {
+    Table(Vec<Column>),
+    Cache { key: usize, value: usize },
+}
+
+enum Row {
+    Table(Vec<IgniteValue>),
+    Cache(String, String),
+}
+
+struct Pending {
+    line: u64,
+    record: ByteRecord,
+    /// The converted row, or why the record is rejected.
+    row: Result<Row, String>,
+}
+
+impl Plan {
+    fn columns(&self) -> &[Column] {
+        match self {
+            Plan::Table(columns) => columns,
+            Plan::Cache { .. } => &[],
+        }
+    }
+
+    fn convert(&self, record: &ByteRecord, null_value: &str) -> Result<Row, String> {
+        let field = |index: usize| -> Result<Option<String>, String> {
+            let bytes = record.get(index).unwrap_or_default();
+            let text = std::str::from_utf8(bytes)
+                .map_err(|_| format!("field {} is not valid UTF-8", index + 1))?;
+            Ok((text != null_value).then(|| text.to_string()))
+        };
+        match self {
+            Plan::Table(columns) => columns
+                .iter()
+                .map(|column| match field(column.index)? {
+                    None => Ok(IgniteValue::Null),
+                    Some(text) => coerce(&column.java_type, &text)
+                        .map_err(|message| format!("column `{}`: {message}", column.name)),
+                })
+                .collect::<Result<_, _>>()
+                .map(Row::Table),
+            Plan::Cache { key, value } => match (field(*key)?, field(*value)?) {
+                (Some(key), Some(value)) => Ok(Row::Cache(key, value)),
+                (None, _) => Err("empty key".to_string()),
+                (_, None) => Err("empty value".to_string()),
+            },
+        }
+    }
+}
+
+/// Converts CSV text the way a JSON string cell of `java_type` would be,
+/// also taking `1`/`0` and `yes`/`no` for booleans.
+fn coerce(java_type: &JavaType, text: &str) -> Result<IgniteValue, String> {
+    if *java_type == JavaType::Boolean {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "true" | "t" | "yes" | "y" | "1" => return Ok(IgniteValue::Boolean(true)),
+            "false" | "f" | "no" | "n" | "0" => return Ok(IgniteValue::Boolean(false)),
+            _ => {}
+        }
+    }
+    IgniteValue::from_json(java_type, &Value::String(text.to_string())).map_err(|err| err.message)
+}
+
+/// The rejects file, opened on first use.
+struct Rejects {
+    path: Option<PathBuf>,
+    headers: Vec<String>,
+    append: bool,
+    writer: Option<csv::Writer<File>>,
+}
+
+impl Rejects {
+    fn new(path: Option<PathBuf>, headers: &[String], append: bool) -> Self {
+        Rejects {
+            path,
+            headers: headers.to_vec(),
+            append,
+            writer: None,
+        }
+    }
+
+    fn write(&mut self, line: u64, reason: &str, record: &ByteRecord) -> ImportResult<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let io_error = |source| ImportError::Io {
+            path: path.clone(),
+            source,
+        };
+        let writer = match &mut self.writer {
+            Some(writer) => writer,
+            None => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .append(self.append)
+                    .truncate(!self.append)
+                    .open(path)
+                    .map_err(io_error)?;
+                let is_new = file.metadata().map_err(io_error)?.len() == 0;
+                let mut writer = WriterBuilder::new().flexible(true).from_writer(file);
+                if is_new {
+                    let header = ["line", "error"]
+                        .into_iter()
+                        .chain(self.headers.iter().map(String::as_str));
+                    writer
+                        .write_record(header)
+                        .map_err(|err| io_error(err.into()))?;
+                }
+                self.writer.insert(writer)
+            }
+        };
+
+        let line = line.to_string();
+        let mut fields = ByteRecord::new();
+        fields.push_field(line.as_bytes());
+        fields.push_field(reason.as_bytes());
+        fields.extend(record.iter());
+        writer
+            .write_byte_record(&fields)
+            .map_err(|err| io_error(err.into()))?;
+        // Flushed per record so the file is complete if the import stops.
+        writer.flush().map_err(io_error)
+    }
+}
@@ -0,0 +1,72 @@
+mod args;
+mod auth;
+mod cache;
+mod cache_admin;
+mod client;
+mod cluster;
+mod config;
+mod cursor;
+mod endpoint;
+mod error;
+mod export;
+mod format;
+mod import;
+mod migrate;
+mod query;
+#[cfg(feature = "arrow")]
+mod record_batch;
+#[cfg(feature = "mock")]
+pub mod mock;
+mod response;
+mod retry;
+mod row;
+mod scan;
+mod sql;
+mod table;
+mod tls;
+mod value;
+
+pub use args::{SqlArg, ToSqlArg};
+pub use auth::{Credentials, LOGIN_ENV, PASSWORD_ENV};
+pub use cache::RestCache;
+pub use cache_admin::{
+    CacheIndex, CacheMetadata, CacheMetrics, CacheOptions, CachePeekMode, CacheTemplate,
+    WriteSynchronizationMode,
+};
+pub use client::{
+    DEFAULT_BASE_URL, DEFAULT_CONNECT_TIMEOUT, DEFAULT_PAGE_SIZE, DEFAULT_POST_THRESHOLD,
+    DEFAULT_TIMEOUT, IgniteRestClient, IgniteRestClientBuilder,
+};
+pub use cluster::{ClusterNode, ClusterState, NodeCache, NodeMetrics, NodeSelector};
+pub use cursor::{EntryCursor, QueryCursor, SqlCursor};
+pub use endpoint::{DEFAULT_EJECT_AFTER, DEFAULT_EJECTION_PERIOD, EndpointSelection};
+pub use error::{
+    IgniteRestError, Result, STATUS_AUTH_FAILED, STATUS_FAILED, STATUS_SECURITY_CHECK_FAILED,
+    STATUS_SUCCESS, SqlError, SqlErrorKind,
+};
+pub use export::{
+    DEFAULT_ROW_GROUP_SIZE, ExportError, ExportFormat, ExportResult, Exporter,
+};
+pub use format::{
+    OutputFormat, RowWriter, cell_text, csv_field, render_table, short_type_name, write_all,
+};
+pub use ignite_with_rest_api_derive::IgniteTable;
+pub use import::{
+    CsvImporter, DEFAULT_IMPORT_BATCH_SIZE, ImportError, ImportMode, ImportProgress, ImportResult,
+};
+pub use migrate::{
+    DEFAULT_HISTORY_TABLE, Migration, MigrationError, MigrationResult, MigrationState,
+    MigrationStatus, Migrator,
+};
+pub use query::{
+    Delete, Expr, Ident, Insert, OrderBy, Select, Statement, TableRef, Update, col, count_all,
+};
+pub use response::{FieldMetadata, KeyValue, QueryPage, RestResponse, SqlResponse, SqlResult};
+#[cfg(feature = "arrow")]
+pub use record_batch::arrow_schema;
+pub use retry::RetryPolicy;
+pub use row::RowDecodeError;
+pub use sql::{is_terminated, split_statements};
+pub use table::{ColumnDef, IgniteTable, IndexDef, TableDef};
+pub use tls::TlsConfig;
+pub use value::{IgniteValue, JavaType};
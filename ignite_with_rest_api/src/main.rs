@@ -1,88 +1,135 @@
-use reqwest::Client;
+use clap::Parser;
+use futures::StreamExt;
+use ignite_config::{ConfigArgs, IgniteConfig};
+use ignite_with_rest_api::{
+    CacheOptions, CacheTemplate, IgniteRestClient, IgniteRestClientBuilder, IgniteTable, Migrator,
+    OutputFormat, Select, Statement, col, count_all,
+};
 use serde::Deserialize;
 use std::error::Error;
-use urlencoding::encode;
 
-#[derive(Deserialize, Debug)]
-struct SqlResponse {
-    successStatus: u32,
-    response: Option<SqlResult>,
-    error: Option<String>,
+// Rows of the Person table created by migrations/V001__create_person.sql.
+// Fields are matched to Ignite's upper-cased column names (ID, NAME, AGE)
+// case-insensitively.
+#[derive(Deserialize, IgniteTable)]
+struct Person {
+    #[ignite(primary_key)]
+    id: i32,
+    #[ignite(length = 50)]
+    name: String,
+    age: i32,
 }
-#[derive(Deserialize, Debug)]
-struct SqlResult {
-    fieldsMetadata: Option<Vec<FieldMetadata>>,
-    items: Option<Vec<Vec<serde_json::Value>>>,
-}
-#[derive(Deserialize, Debug)]
-struct FieldMetadata {
-    fieldName: String,
-    fieldTypeName: String,
+
+/// Runs the CRUD example against the Ignite REST API.
+#[derive(Parser)]
+struct Args {
+    /// How query results are printed: table, csv, jsonl or markdown.
+    #[arg(long, default_value_t = OutputFormat::Table)]
+    format: OutputFormat,
+
+    #[command(flatten)]
+    config: ConfigArgs,
 }
 
-async fn execute_sql(cache: &str, query: &str) -> Result<(), Box<dyn Error>> {
-    let client = Client::new();
-    let encoded_query = encode(query);
-    let cmd = "qryfldexe";
-    let url = format!(
-        "http://localhost:8080/ignite?cmd={}&cacheName={}&qry={}&pageSize=10",
-        cmd, cache, encoded_query
-    );
+async fn execute_sql(
+    client: &IgniteRestClient,
+    format: OutputFormat,
+    statement: &Statement,
+) -> Result<(), Box<dyn Error>> {
+    // The cursor pages through the whole result with qryfetch, so SELECTs
+    // are no longer cut off at the page size.
+    let mut rows = statement.query(client).await?;
+
+    eprintln!("\n\nQuery executed successfully.");
 
-    let res = client.get(&url).send().await?;
-    let body = res.text().await?;
-    
-    let parsed: SqlResponse = serde_json::from_str(&body)?;
-    
-    if let Some(error) = parsed.error {
-        eprintln!("Error: {}", error);
-    } else if let Some(response) = parsed.response {
-        println!("\n\nQuery executed successfully.");
-        
-        if let Some(fields) = response.fieldsMetadata {
-            println!("Fields: {:?}", fields);
-        }
-        
-        if let Some(items) = response.items {
-            for row in items {
-                println!("Row: {:?}", row);
-            }
-        }
+    // Rows are written as they arrive; status lines go to stderr so that
+    // machine-readable formats can be piped straight into other tools.
+    let mut out = format.writer(std::io::stdout());
+    out.begin(rows.fields())?;
+    while let Some(row) = rows.next().await {
+        out.row(&row?)?;
     }
-    
+    out.finish()?;
+
     Ok(())
 }
 
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    // Create table
-    execute_sql(
-        "PersonCache",
-        "CREATE TABLE Person (id INT PRIMARY KEY, name VARCHAR(50), age INT)",
-    )
-    .await?;
-
-    // INSERT
-    execute_sql(
-        "PersonCache",
-        "INSERT INTO Person (id, name, age) VALUES (1, 'John Doe', 30), (2, 'Will Smith', 10)",
-    )
-    .await?;
-
-    // SELECT all
-    execute_sql("PersonCache", "SELECT * FROM Person").await?;
-
-    // SELECT with WHERE
-    execute_sql("PersonCache", "SELECT * FROM Person WHERE age > 25").await?;
+    let args = Args::parse();
+    let format = args.format;
+
+    // Endpoints, cache and credentials come from ignite.toml, IGNITE_REST_URL,
+    // IGNITE_LOGIN / IGNITE_PASSWORD and the flags, in increasing priority.
+    // Invalid settings are reported here, before anything is sent.
+    let config = IgniteConfig::load(&args.config)?;
+
+    // One client for the whole program: it keeps the connection pool alive
+    // and can be cloned into other tasks.
+    let client = IgniteRestClientBuilder::from_config(&config).build()?;
+
+    // The DDL below needs an active cluster.
+    println!(
+        "Connected to Ignite {} ({} node(s))",
+        client.version().await?,
+        client.topology_size().await?
+    );
+    client.activate().await?;
+
+    // Make sure the cache used as the SQL entry point exists, so it does not
+    // have to be declared in ignite-rest-config.xml.
+    client
+        .get_or_create_cache(
+            &config.rest.cache,
+            &CacheOptions::new().template(CacheTemplate::Partitioned),
+        )
+        .await?;
+
+    // Create the schema from migrations/; versions already applied by a
+    // previous run are skipped.
+    let migrator =
+        Migrator::from_dir(client.clone(), concat!(env!("CARGO_MANIFEST_DIR"), "/migrations"))?;
+    for version in migrator.migrate().await? {
+        eprintln!("Applied migration V{version:03}");
+    }
+
+    // INSERT, or replace the rows left by a previous run. The SQL and its
+    // bind parameters come from #[derive(IgniteTable)].
+    let people = [
+        Person { id: 1, name: "John Doe".to_string(), age: 30 },
+        Person { id: 2, name: "Will Smith".to_string(), age: 10 },
+    ];
+    for person in &people {
+        person.upsert(&client).await?;
+    }
+
+    // SELECT all, built with the query builder
+    let everyone = Select::from("Person").order_by(col("id")).build();
+    execute_sql(&client, format, &everyone).await?;
+
+    // SELECT with WHERE, decoded into structs
+    for person in Person::find_where(&client, "age > ?", &[&25]).await? {
+        println!("{} (id {}) is {} years old", person.name, person.id, person.age);
+    }
+
+    // SELECT with an aggregate
+    let adults = Select::from("Person")
+        .column(count_all().alias("adults"))
+        .filter(col("age").ge(18))
+        .build();
+    execute_sql(&client, format, &adults).await?;
 
     // UPDATE
-    execute_sql("PersonCache", "UPDATE Person SET age = 31 WHERE id = 2").await?;
-    execute_sql("PersonCache", "SELECT * FROM Person").await?;
+    if let Some(mut will) = Person::select_by_pk(&client, &2).await? {
+        will.age = 31;
+        will.update(&client).await?;
+    }
+    execute_sql(&client, format, &everyone).await?;
 
     // DELETE
-    execute_sql("PersonCache", "DELETE FROM Person WHERE age = 30").await?;
-    execute_sql("PersonCache", "SELECT * FROM Person").await?;
+    Person::delete(&client, &1).await?;
+    execute_sql(&client, format, &everyone).await?;
 
     Ok(())
 }
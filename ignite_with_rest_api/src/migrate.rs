@@ -0,0 +1,440 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use serde::Deserialize;
+
+use crate::client::IgniteRestClient;
+use crate::error::{IgniteRestError, SqlErrorKind};
+use crate::sql::{is_identifier, split_statements};
+
+/// Table that records applied migrations unless [`Migrator::history_table`]
+/// picks another one.
+pub const DEFAULT_HISTORY_TABLE: &str = "SCHEMA_HISTORY";
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error("failed to read migrations from {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("invalid migration file name `{0}`, expected V<version>__<description>.sql")]
+    InvalidFileName(String),
+
+    #[error("migration version {0} is defined more than once")]
+    DuplicateVersion(u32),
+
+    #[error("invalid history table name `{0}`")]
+    InvalidHistoryTable(String),
+
+    /// An applied migration's file was edited afterwards.
+    #[error(
+        "migration V{version} was modified after it was applied \
+         (recorded checksum {recorded}, file checksum {actual})"
+    )]
+    ChecksumMismatch {
+        version: u32,
+        recorded: u32,
+        actual: u32,
+    },
+
+    /// A pending migration sorts before one that is already applied.
+    #[error("migration V{version} is pending but V{latest} is already applied")]
+    OutOfOrder { version: u32, latest: u32 },
+
+    #[error("cannot baseline: the history table already has entries")]
+    AlreadyBaselined,
+
+    /// Statements before `statement` (1-based) were applied and are not
+    /// rolled back: Ignite DDL is not transactional.
+    #[error("migration V{version}, statement {statement} failed: {source}")]
+    Failed {
+        version: u32,
+        statement: usize,
+        #[source]
+        source: IgniteRestError,
+    },
+
+    #[error(transparent)]
+    Rest(#[from] IgniteRestError),
+}
+
+pub type MigrationResult<T> = std::result::Result<T, MigrationError>;
+
+/// A versioned SQL script, usually loaded from a `V001__create_person.sql` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    pub version: u32,
+    pub description: String,
+    pub script: String,
+}
+
+impl Migration {
+    pub fn new(version: u32, description: impl Into<String>, script: impl Into<String>) -> Self {
+        Migration {
+            version,
+            description: description.into(),
+            script: script.into(),
+        }
+    }
+
+    /// Parses `V<version>__<description>.sql`; underscores in the
+    /// description become spaces.
+    pub fn parse_file_name(file_name: &str) -> Option<(u32, String)> {
+        let stem = file_name.strip_suffix(".sql")?.strip_prefix('V')?;
+        let (version, description) = stem.split_once("__")?;
+        let version = version.parse().ok()?;
+        Some((version, description.replace('_', " ")))
+    }
+
+    /// Loads every `.sql` file of `dir`, sorted by version.
+    pub fn load_dir(dir: impl AsRef<Path>) -> MigrationResult<Vec<Migration>> {
+        let dir = dir.as_ref();
+        let io_error = |source| MigrationError::Io {
+            path: dir.to_path_buf(),
+            source,
+        };
+
+        let mut migrations = Vec::new();
+        for entry in fs::read_dir(dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if !file_name.ends_with(".sql") {
+                continue;
+            }
+            let (version, description) = Migration::parse_file_name(file_name)
+                .ok_or_else(|| MigrationError::InvalidFileName(file_name.to_string()))?;
+            let script = fs::read_to_string(&path).map_err(|source| MigrationError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            migrations.push(Migration::new(version, description, script));
+        }
+        migrations.sort_by_key(|migration| migration.version);
+        Ok(migrations)
+    }
+
+    /// CRC32 of the script with line endings normalized, so a checkout with
+    /// CRLF endings does not look edited.
+    pub fn checksum(&self) -> u32 {
+        crc32fast::hash(self.script.replace("\r\n", "\n").as_bytes())
+    }
+}
+
+/// Where a migration stands relative to the history table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationState {
+    Applied {
+        installed_on: String,
+    },
+    Pending,
+    /// Applied, but the file changed since.
+    Modified {
+        installed_on: String,
+    },
+    /// At or below the baseline version, so never run.
+    BelowBaseline,
+    /// The baseline entry itself.
+    Baseline {
+        installed_on: String,
+    },
+    /// Recorded as applied, but no file has this version any more.
+    Missing {
+        installed_on: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    pub version: u32,
+    pub description: String,
+    pub state: MigrationState,
+}
+
+/// One row of the history table.
+#[derive(Debug, Clone, Deserialize)]
+struct HistoryEntry {
+    version: u32,
+    description: String,
+    checksum: Option<i64>,
+    kind: String,
+    installed_on: String,
+}
+
+const KIND_SQL: &str = "SQL";
+const KIND_BASELINE: &str = "BASELINE";
+
+/// Applies versioned SQL migrations through `qryfldexe` and records them in
+/// a history table.
+#[derive(Debug, Clone)]
+pub struct Migrator {
+    client: IgniteRestClient,
+    migrations: Vec<Migration>,
+    history_table: String,
+}
+
+impl Migrator {
+    /// Migrator for `migrations`, which may be given in any order.
+    pub fn new(client: IgniteRestClient, mut migrations: Vec<Migration>) -> MigrationResult<Self> {
+        migrations.sort_by_key(|migration| migration.version);
+        if let Some(pair) = migrations
+            .windows(2)
+            .find(|pair| pair[0].version == pair[1].version)
+        {
+            return Err(MigrationError::DuplicateVersion(pair[0].version));
+        }
+        Ok(Migrator {
+            client,
+            migrations,
+            history_table: DEFAULT_HISTORY_TABLE.to_string(),
+        })
+    }
+
+    /// Migrator for the `V*__*.sql` files of `dir`.
+    pub fn from_dir(client: IgniteRestClient, dir: impl AsRef<Path>) -> MigrationResult<Self> {
+        Migrator::new(client, Migration::load_dir(dir)?)
+    }
+
+    /// Records applied migrations in `table` instead of [`DEFAULT_HISTORY_TABLE`].
+    pub fn history_table(mut self, table: impl Into<String>) -> MigrationResult<Self> {
+        let table = table.into();
+        if !is_identifier(&table) {
+            return Err(MigrationError::InvalidHistoryTable(table));
+        }
+        self.history_table = table;
+        Ok(self)
+    }
+
+    pub fn migrations(&self) -> &[Migration] {
+        &self.migrations
+    }
+
+    /// State of every known migration, in version order.
+    ///
+    /// Only reads the history table: without one, every migration is
+    /// pending and the table is left to [`migrate`](Self::migrate) or
+    /// [`baseline`](Self::baseline) to create.
+    pub async fn status(&self) -> MigrationResult<Vec<MigrationStatus>> {
+        let history = match self.history().await {
+            Err(MigrationError::Rest(err))
+                if err.sql_kind() == Some(SqlErrorKind::TableNotFound) =>
+            {
+                BTreeMap::new()
+            }
+            history => history?,
+        };
+        let baseline = baseline_version(&history);
+
+        let mut statuses = Vec::new();
+        for migration in &self.migrations {
+            let state = match history.get(&migration.version) {
+                Some(entry) if entry.kind == KIND_BASELINE => MigrationState::Baseline {
+                    installed_on: entry.installed_on.clone(),
+                },
+                Some(entry) if entry.checksum != Some(i64::from(migration.checksum())) => {
+                    MigrationState::Modified {
+                        installed_on: entry.installed_on.clone(),
+                    }
+                }
+                Some(entry) => MigrationState::Applied {
+                    installed_on: entry.installed_on.clone(),
+                },
+                None if baseline.is_some_and(|baseline| migration.version <= baseline) => {
+                    MigrationState::BelowBaseline
+                }
+                None => MigrationState::Pending,
+            };
+            statuses.push(MigrationStatus {
+                version: migration.version,
+                description: migration.description.clone(),
+                state,
+            });
+        }
+
+        for entry in history.values() {
+            if self.migration(entry.version).is_none() {
+                let state = if entry.kind == KIND_BASELINE {
+                    MigrationState::Baseline {
+                        installed_on: entry.installed_on.clone(),
+                    }
+                } else {
+                    MigrationState::Missing {
+                        installed_on: entry.installed_on.clone(),
+                    }
+                };
+                statuses.push(MigrationStatus {
+                    version: entry.version,
+                    description: entry.description.clone(),
+                    state,
+                });
+            }
+        }
+        statuses.sort_by_key(|status| status.version);
+        Ok(statuses)
+    }
+
+    /// Applies every pending migration in version order and returns the
+    /// versions that were applied.
+    ///
+    /// Nothing runs if an applied migration was edited or a pending one
+    /// sorts before the latest applied version.
+    pub async fn migrate(&self) -> MigrationResult<Vec<u32>> {
+        self.ensure_history_table().await?;
+        let history = self.history().await?;
+        let baseline = baseline_version(&history);
+        let latest = history.keys().next_back().copied();
+
+        let mut pending = Vec::new();
+        for migration in &self.migrations {
+            match history.get(&migration.version) {
+                Some(entry) if entry.kind == KIND_SQL => {
+                    let recorded = entry.checksum.unwrap_or_default() as u32;
+                    if recorded != migration.checksum() {
+                        return Err(MigrationError::ChecksumMismatch {
+                            version: migration.version,
+                            recorded,
+                            actual: migration.checksum(),
+                        });
+                    }
+                }
+                Some(_) => {}
+                None if baseline.is_some_and(|baseline| migration.version <= baseline) => {}
+                None => {
+                    if let Some(latest) = latest.filter(|latest| *latest > migration.version) {
+                        return Err(MigrationError::OutOfOrder {
+                            version: migration.version,
+                            latest,
+                        });
+                    }
+                    pending.push(migration);
+                }
+            }
+        }
+
+        let mut applied = Vec::new();
+        for migration in pending {
+            self.apply(migration).await?;
+            applied.push(migration.version);
+        }
+        Ok(applied)
+    }
+
+    /// Marks `version` and everything below it as applied without running
+    /// them, for databases whose schema predates the migrations.
+    pub async fn baseline(&self, version: u32) -> MigrationResult<()> {
+        self.ensure_history_table().await?;
+        if !self.history().await?.is_empty() {
+            return Err(MigrationError::AlreadyBaselined);
+        }
+        self.record(version, "<< Baseline >>", None, KIND_BASELINE, 0)
+            .await?;
+        Ok(())
+    }
+
+    fn migration(&self, version: u32) -> Option<&Migration> {
+        self.migrations
+            .iter()
+            .find(|migration| migration.version == version)
+    }
+
+    async fn apply(&self, migration: &Migration) -> MigrationResult<()> {
+        let started = Instant::now();
+        for (index, statement) in split_statements(&migration.script).into_iter().enumerate() {
+            self.client
+                .execute_sql(statement, &[])
+                .await
+                .map_err(|source| MigrationError::Failed {
+                    version: migration.version,
+                    statement: index + 1,
+                    source,
+                })?;
+        }
+        let elapsed = started.elapsed().as_millis() as i64;
+
+        self.record(
+            migration.version,
+            &migration.description,
+            Some(migration.checksum()),
+            KIND_SQL,
+            elapsed,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn ensure_history_table(&self) -> MigrationResult<()> {
+        let ddl = format!(
+            "CREATE TABLE IF NOT EXISTS {} (\
+             VERSION INT PRIMARY KEY, \
+             DESCRIPTION VARCHAR(200), \
+             CHECKSUM BIGINT, \
+             KIND VARCHAR(20), \
+             INSTALLED_ON TIMESTAMP, \
+             EXECUTION_MS BIGINT\
+             ) WITH \"template=replicated\"",
+            self.history_table
+        );
+        self.client.execute_sql(&ddl, &[]).await?;
+        Ok(())
+    }
+
+    async fn history(&self) -> MigrationResult<BTreeMap<u32, HistoryEntry>> {
+        // The timestamp is read as text so it does not depend on how the
+        // REST connector serializes dates.
+        let query = format!(
+            "SELECT VERSION, DESCRIPTION, CHECKSUM, KIND, CAST(INSTALLED_ON AS VARCHAR) AS INSTALLED_ON \
+             FROM {} ORDER BY VERSION",
+            self.history_table
+        );
+        let entries: Vec<HistoryEntry> = self.client.query_as(&query, &[]).await?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| (entry.version, entry))
+            .collect())
+    }
+
+    async fn record(
+        &self,
+        version: u32,
+        description: &str,
+        checksum: Option<u32>,
+        kind: &str,
+        execution_ms: i64,
+    ) -> MigrationResult<()> {
+        let insert = format!(
+            "INSERT INTO {} (VERSION, DESCRIPTION, CHECKSUM, KIND, INSTALLED_ON, EXECUTION_MS) \
+             VALUES (?, ?, ?, ?, ?, ?)",
+            self.history_table
+        );
+        let installed_on = chrono::Utc::now().naive_utc();
+        let checksum = checksum.map(i64::from);
+        self.client
+            .execute_sql(
+                &insert,
+                &[
+                    &version,
+                    &description,
+                    &checksum,
+                    &kind,
+                    &installed_on,
+                    &execution_ms,
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+fn baseline_version(history: &BTreeMap<u32, HistoryEntry>) -> Option<u32> {
+    history
+        .values()
+        .filter(|entry| entry.kind == KIND_BASELINE)
+        .map(|entry| entry.version)
+        .max()
+}
@@ -0,0 +1,401 @@
+//! In-process stand-in for the Ignite REST connector, for tests that should
+//! not need a running cluster.
+//!
+//! Replies are scripted per command (and optionally per query or parameter);
+//! every request is recorded so tests can assert on what was sent.
+//!
+//! ```no_run
+//! # async fn demo() -> ignite_with_rest_api::Result<()> {
+//! use ignite_with_rest_api::mock::{MockIgnite, MockReply};
+//! use serde_json::json;
+//!
+//! let server = MockIgnite::start().await;
+//! server
+//!     .on("qryfldexe")
+//!     .query("SELECT name FROM Person")
+//!     .reply(MockReply::rows(&[("NAME", "java.lang.String")], vec![vec![json!("John")]]));
+//!
+//! let client = server.client_builder().build()?;
+//! let rows = client.execute_sql("SELECT name FROM Person", &[]).await?;
+//! assert_eq!(rows.items.unwrap().len(), 1);
+//! assert_eq!(server.requests_for("qryfldexe").len(), 1);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{RawQuery, State};
+use axum::http::{Method, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use serde_json::{Value, json};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+use crate::client::IgniteRestClientBuilder;
+use crate::error::{STATUS_FAILED, STATUS_SUCCESS};
+
+/// Mock REST server listening on an ephemeral localhost port.
+///
+/// The server stops when this handle is dropped.
+pub struct MockIgnite {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+#[derive(Default)]
+struct Shared {
+    rules: Mutex<Vec<Rule>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+struct Rule {
+    command: String,
+    query: Option<String>,
+    params: Vec<(String, String)>,
+    /// Replies still to be given; the last one is repeated forever unless
+    /// the rule was limited with [`MockRule::times`].
+    replies: VecDeque<MockReply>,
+    remaining: Option<usize>,
+}
+
+impl Rule {
+    fn matches(&self, request: &RecordedRequest) -> bool {
+        if self.remaining == Some(0) || request.command.as_deref() != Some(self.command.as_str()) {
+            return false;
+        }
+        if let Some(query) = &self.query
+            && request.param("qry").map(normalize_sql) != Some(normalize_sql(query))
+        {
+            return false;
+        }
+        self.params
+            .iter()
+            .all(|(name, value)| request.param(name) == Some(value.as_str()))
+    }
+
+    fn next_reply(&mut self) -> MockReply {
+        if let Some(remaining) = &mut self.remaining {
+            *remaining -= 1;
+        }
+        if self.replies.len() > 1 {
+            self.replies.pop_front().unwrap_or_default()
+        } else {
+            self.replies.front().cloned().unwrap_or_default()
+        }
+    }
+}
+
+/// A request as received by the mock server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    /// `GET` or `POST`.
+    pub method: String,
+    /// Value of the `cmd` parameter.
+    pub command: Option<String>,
+    /// Every parameter in the order sent, from the query string followed by
+    /// a form-encoded body.
+    pub params: Vec<(String, String)>,
+}
+
+impl RecordedRequest {
+    /// First value of parameter `name`.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// What the mock server sends back for a matched request.
+#[derive(Debug, Clone)]
+pub struct MockReply {
+    http_status: u16,
+    body: String,
+    delay: Duration,
+}
+
+impl Default for MockReply {
+    fn default() -> Self {
+        MockReply::success(Value::Null)
+    }
+}
+
+impl MockReply {
+    /// `successStatus` 0 with `response` as the payload.
+    pub fn success(response: impl Serialize) -> Self {
+        MockReply::envelope(json!({
+            "successStatus": STATUS_SUCCESS,
+            "response": response,
+            "error": null,
+            "sessionToken": null,
+        }))
+    }
+
+    /// Reply of `authenticate`: success carrying `token` as `sessionToken`.
+    pub fn session(token: &str) -> Self {
+        MockReply::envelope(json!({
+            "successStatus": STATUS_SUCCESS,
+            "response": true,
+            "error": null,
+            "sessionToken": token,
+        }))
+    }
+
+    /// A complete single-page `qryfldexe` result; `fields` are
+    /// `(fieldName, fieldTypeName)` pairs.
+    pub fn rows(fields: &[(&str, &str)], rows: Vec<Vec<Value>>) -> Self {
+        MockReply::success(MockReply::page(fields, rows, None))
+    }
+
+    /// A `qryfldexe`/`qryfetch` page: `query_id` is the open cursor id for
+    /// pages that are not the last, `None` for the final one. Pass no
+    /// `fields` for `qryfetch` pages, which do not repeat the metadata.
+    pub fn page(fields: &[(&str, &str)], rows: Vec<Vec<Value>>, query_id: Option<i64>) -> Value {
+        let fields_metadata: Vec<Value> = fields
+            .iter()
+            .map(|(name, java_type)| json!({ "fieldName": name, "fieldTypeName": java_type }))
+            .collect();
+        json!({
+            "fieldsMetadata": if fields.is_empty() { Value::Null } else { Value::from(fields_metadata) },
+            "items": rows,
+            "queryId": query_id,
+            "last": query_id.is_none(),
+        })
+    }
+
+    /// Non-zero `successStatus`, e.g. [`STATUS_FAILED`] with an Ignite
+    /// error message.
+    pub fn failure(success_status: u32, error: &str) -> Self {
+        MockReply::envelope(json!({
+            "successStatus": success_status,
+            "response": null,
+            "error": error,
+            "sessionToken": null,
+        }))
+    }
+
+    /// `successStatus` 1, the status of most server-side errors.
+    pub fn error(error: &str) -> Self {
+        MockReply::failure(STATUS_FAILED, error)
+    }
+
+    /// An HTTP error status with an empty body.
+    pub fn http_status(status: u16) -> Self {
+        MockReply {
+            http_status: status,
+            body: String::new(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// `body` sent verbatim with status 200, e.g. truncated JSON.
+    pub fn raw(body: impl Into<String>) -> Self {
+        MockReply {
+            http_status: 200,
+            body: body.into(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Waits `delay` before answering.
+    pub fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn envelope(body: Value) -> Self {
+        MockReply::raw(body.to_string())
+    }
+}
+
+/// Rule under construction, returned by [`MockIgnite::on`].
+#[must_use = "a rule is only installed by `reply` or `replies`"]
+pub struct MockRule<'a> {
+    server: &'a MockIgnite,
+    rule: Rule,
+}
+
+impl MockRule<'_> {
+    /// Only matches requests whose `qry` equals `query`, ignoring
+    /// differences in whitespace.
+    pub fn query(mut self, query: &str) -> Self {
+        self.rule.query = Some(query.to_string());
+        self
+    }
+
+    /// Only matches requests where parameter `name` is `value`.
+    pub fn param(mut self, name: &str, value: &str) -> Self {
+        self.rule.params.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Stops matching after `times` requests, so that a later rule for the
+    /// same command takes over.
+    pub fn times(mut self, times: usize) -> Self {
+        self.rule.remaining = Some(times);
+        self
+    }
+
+    /// Answers every matching request with `reply`.
+    pub fn reply(self, reply: MockReply) {
+        self.replies([reply]);
+    }
+
+    /// Answers matching requests with `replies` in turn, then keeps
+    /// repeating the last one.
+    pub fn replies(mut self, replies: impl IntoIterator<Item = MockReply>) {
+        self.rule.replies = replies.into_iter().collect();
+        self.server.lock_rules().push(self.rule);
+    }
+}
+
+impl MockIgnite {
+    /// Starts a server on `127.0.0.1` with no rules. Unmatched requests get
+    /// `successStatus` 1 naming the command, so a missing rule shows up in
+    /// the test's error.
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .expect("bind mock Ignite server");
+        let addr = listener.local_addr().expect("mock Ignite server address");
+        let shared = Arc::new(Shared::default());
+        let (shutdown, stopped) = oneshot::channel::<()>();
+
+        let app = Router::new().fallback(handle).with_state(shared.clone());
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app)
+                .with_graceful_shutdown(async {
+                    let _ = stopped.await;
+                })
+                .await;
+        });
+
+        MockIgnite {
+            addr,
+            shared,
+            shutdown: Some(shutdown),
+        }
+    }
+
+    /// REST endpoint URL, e.g. `http://127.0.0.1:41234/ignite`.
+    pub fn url(&self) -> String {
+        format!("http://{}/ignite", self.addr)
+    }
+
+    /// Client builder pointed at this server.
+    pub fn client_builder(&self) -> IgniteRestClientBuilder {
+        IgniteRestClientBuilder::default().base_url(self.url())
+    }
+
+    /// Starts a rule for `command`. Rules are tried in the order they were
+    /// installed; the first match answers.
+    pub fn on(&self, command: &str) -> MockRule<'_> {
+        MockRule {
+            server: self,
+            rule: Rule {
+                command: command.to_string(),
+                query: None,
+                params: Vec::new(),
+                replies: VecDeque::new(),
+                remaining: None,
+            },
+        }
+    }
+
+    /// Every request received so far.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        lock(&self.shared.requests).clone()
+    }
+
+    /// Requests received so far for `command`.
+    pub fn requests_for(&self, command: &str) -> Vec<RecordedRequest> {
+        lock(&self.shared.requests)
+            .iter()
+            .filter(|request| request.command.as_deref() == Some(command))
+            .cloned()
+            .collect()
+    }
+
+    /// Forgets the recorded requests; rules are kept.
+    pub fn clear_requests(&self) {
+        lock(&self.shared.requests).clear();
+    }
+
+    fn lock_rules(&self) -> MutexGuard<'_, Vec<Rule>> {
+        lock(&self.shared.rules)
+    }
+}
+
+impl Drop for MockIgnite {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+async fn handle(
+    State(shared): State<Arc<Shared>>,
+    method: Method,
+    RawQuery(query): RawQuery,
+    body: Bytes,
+) -> Response {
+    let mut params: Vec<(String, String)> =
+        url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+            .into_owned()
+            .collect();
+    if method == Method::POST {
+        params.extend(url::form_urlencoded::parse(&body).into_owned());
+    }
+    let request = RecordedRequest {
+        method: method.to_string(),
+        command: params
+            .iter()
+            .find(|(name, _)| name == "cmd")
+            .map(|(_, value)| value.clone()),
+        params,
+    };
+
+    let reply = lock(&shared.rules)
+        .iter_mut()
+        .find(|rule| rule.matches(&request))
+        .map(Rule::next_reply)
+        .unwrap_or_else(|| {
+            MockReply::error(&format!(
+                "mock: no reply scripted for cmd={}",
+                request.command.as_deref().unwrap_or("<none>")
+            ))
+        });
+    lock(&shared.requests).push(request);
+
+    if !reply.delay.is_zero() {
+        tokio::time::sleep(reply.delay).await;
+    }
+    let status =
+        StatusCode::from_u16(reply.http_status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    (
+        status,
+        [(header::CONTENT_TYPE, "application/json")],
+        reply.body,
+    )
+        .into_response()
+}
+
+/// Collapses runs of whitespace so rules do not depend on line breaks.
+fn normalize_sql(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A panicking test must not poison the mock for the server task.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
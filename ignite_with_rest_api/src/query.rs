@@ -0,0 +1,687 @@
+use std::fmt;
+use std::ops::Not;
+
+use serde::de::DeserializeOwned;
+
+use crate::args::{SqlArg, ToSqlArg};
+use crate::client::IgniteRestClient;
+use crate::cursor::SqlCursor;
+use crate::error::Result;
+use crate::response::SqlResult;
+
+/// Words that cannot be used as unquoted identifiers in Ignite SQL.
+#[rustfmt::skip]
+const KEYWORDS: &[&str] = &[
+    "ALL", "AND", "ARRAY", "AS", "BETWEEN", "BOTH", "CASE", "CHECK", "CONSTRAINT", "CROSS",
+    "CURRENT_DATE", "CURRENT_TIME", "CURRENT_TIMESTAMP", "CURRENT_USER", "DISTINCT", "EXCEPT",
+    "EXISTS", "FALSE", "FETCH", "FOR", "FOREIGN", "FROM", "FULL", "GROUP", "HAVING", "IF",
+    "ILIKE", "IN", "INNER", "INTERSECT", "INTERSECTS", "INTERVAL", "IS", "JOIN", "KEY",
+    "LEADING", "LEFT", "LIKE", "LIMIT", "LOCALTIME", "LOCALTIMESTAMP", "MINUS", "NATURAL", "NOT",
+    "NULL", "OFFSET", "ON", "OR", "ORDER", "PRIMARY", "QUALIFY", "REGEXP", "RIGHT", "ROW",
+    "ROWNUM", "SELECT", "SYSDATE", "SYSTIME", "SYSTIMESTAMP", "TABLE", "TODAY", "TOP",
+    "TRAILING", "TRUE", "UNION", "UNIQUE", "UNKNOWN", "USING", "VALUE", "VALUES", "WHERE",
+    "WINDOW", "WITH", "_ROWID_",
+];
+
+/// A table, column or alias name, possibly qualified as `schema.table` or
+/// `alias.column`.
+///
+/// Ignite upper-cases unquoted names, so `person.name` and `PERSON.NAME`
+/// are the same column while `"name"` is another one. [`Ident::new`] follows
+/// that rule: plain names are written unquoted, keywords such as `KEY` or
+/// `VALUE` are quoted in upper case, and anything else, which can only have
+/// been created quoted, is quoted as is. [`Ident::quoted`] keeps the case of
+/// a name created with quotes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ident(String);
+
+impl Ident {
+    /// Name as it would be written unquoted; each `.`-separated part is
+    /// quoted where needed.
+    pub fn new(name: &str) -> Self {
+        Ident(
+            name.split('.')
+                .map(quote_part)
+                .collect::<Vec<_>>()
+                .join("."),
+        )
+    }
+
+    /// Case-sensitive name, written in double quotes.
+    pub fn quoted(name: &str) -> Self {
+        Ident(quote(name))
+    }
+
+    /// The name as written in SQL.
+    pub fn as_sql(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Ident {
+    fn from(name: &str) -> Self {
+        Ident::new(name)
+    }
+}
+
+impl fmt::Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn quote_part(part: &str) -> String {
+    let plain = part
+        .chars()
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+        && part
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+    let upper = part.to_ascii_uppercase();
+    if !plain {
+        quote(part)
+    } else if KEYWORDS.contains(&upper.as_str()) {
+        quote(&upper)
+    } else {
+        part.to_string()
+    }
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Piece of SQL with the arguments bound to its `?` placeholders.
+///
+/// Values converted into an `Expr` become placeholders, so `col("age").gt(25)`
+/// renders as `age > ?` bound to `25`. Column references come from [`col`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    sql: String,
+    args: Vec<SqlArg>,
+}
+
+/// Reference to a column, e.g. `col("age")` or `col("p.name")`.
+pub fn col(name: &str) -> Expr {
+    Expr::ident(Ident::new(name))
+}
+
+/// `COUNT(*)`.
+pub fn count_all() -> Expr {
+    Expr::raw("COUNT(*)")
+}
+
+impl<T: ToSqlArg> From<T> for Expr {
+    fn from(value: T) -> Self {
+        Expr {
+            sql: "?".to_string(),
+            args: vec![value.to_sql_arg()],
+        }
+    }
+}
+
+impl Expr {
+    pub fn ident(ident: Ident) -> Self {
+        Expr::raw(ident.0)
+    }
+
+    /// SQL text inserted verbatim, e.g. a function call. Never build it from
+    /// user input; bind values with [`Expr::from`] instead.
+    pub fn raw(sql: impl Into<String>) -> Self {
+        Expr {
+            sql: sql.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// The SQL text, with a `?` for every argument.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    pub fn args(&self) -> &[SqlArg] {
+        &self.args
+    }
+
+    fn binary(self, op: &str, other: impl Into<Expr>) -> Expr {
+        let other = other.into();
+        let mut args = self.args;
+        args.extend(other.args);
+        Expr {
+            sql: format!("{} {op} {}", self.sql, other.sql),
+            args,
+        }
+    }
+
+    fn wrap(self, before: &str, after: &str) -> Expr {
+        Expr {
+            sql: format!("{before}{}{after}", self.sql),
+            args: self.args,
+        }
+    }
+
+    pub fn eq(self, other: impl Into<Expr>) -> Expr {
+        self.binary("=", other)
+    }
+
+    pub fn ne(self, other: impl Into<Expr>) -> Expr {
+        self.binary("<>", other)
+    }
+
+    pub fn lt(self, other: impl Into<Expr>) -> Expr {
+        self.binary("<", other)
+    }
+
+    pub fn le(self, other: impl Into<Expr>) -> Expr {
+        self.binary("<=", other)
+    }
+
+    pub fn gt(self, other: impl Into<Expr>) -> Expr {
+        self.binary(">", other)
+    }
+
+    pub fn ge(self, other: impl Into<Expr>) -> Expr {
+        self.binary(">=", other)
+    }
+
+    pub fn like(self, pattern: impl Into<Expr>) -> Expr {
+        self.binary("LIKE", pattern)
+    }
+
+    pub fn between(self, low: impl Into<Expr>, high: impl Into<Expr>) -> Expr {
+        self.binary("BETWEEN", low).binary("AND", high)
+    }
+
+    /// `self IN (...)`; an empty list is always false.
+    pub fn in_list<I>(self, values: I) -> Expr
+    where
+        I: IntoIterator,
+        I::Item: Into<Expr>,
+    {
+        let list = join(values.into_iter().map(Into::into), ", ");
+        if list.sql.is_empty() {
+            return Expr::raw("FALSE");
+        }
+        self.binary("IN", list.wrap("(", ")"))
+    }
+
+    pub fn is_null(self) -> Expr {
+        self.wrap("", " IS NULL")
+    }
+
+    pub fn is_not_null(self) -> Expr {
+        self.wrap("", " IS NOT NULL")
+    }
+
+    pub fn and(self, other: impl Into<Expr>) -> Expr {
+        self.binary("AND", other).wrap("(", ")")
+    }
+
+    pub fn or(self, other: impl Into<Expr>) -> Expr {
+        self.binary("OR", other).wrap("(", ")")
+    }
+
+    pub fn count(self) -> Expr {
+        self.wrap("COUNT(", ")")
+    }
+
+    pub fn sum(self) -> Expr {
+        self.wrap("SUM(", ")")
+    }
+
+    pub fn avg(self) -> Expr {
+        self.wrap("AVG(", ")")
+    }
+
+    pub fn min(self) -> Expr {
+        self.wrap("MIN(", ")")
+    }
+
+    pub fn max(self) -> Expr {
+        self.wrap("MAX(", ")")
+    }
+
+    /// `self AS alias`, for select lists.
+    pub fn alias(self, alias: &str) -> Expr {
+        let alias = Ident::new(alias);
+        self.wrap("", &format!(" AS {alias}"))
+    }
+
+    pub fn asc(self) -> OrderBy {
+        OrderBy(self.wrap("", " ASC"))
+    }
+
+    pub fn desc(self) -> OrderBy {
+        OrderBy(self.wrap("", " DESC"))
+    }
+}
+
+impl Not for Expr {
+    type Output = Expr;
+
+    fn not(self) -> Expr {
+        self.wrap("NOT (", ")")
+    }
+}
+
+/// Expressions joined by `separator`.
+fn join(exprs: impl IntoIterator<Item = Expr>, separator: &str) -> Expr {
+    let mut joined = Expr::raw("");
+    for (index, expr) in exprs.into_iter().enumerate() {
+        if index > 0 {
+            joined.sql.push_str(separator);
+        }
+        joined.sql.push_str(&expr.sql);
+        joined.args.extend(expr.args);
+    }
+    joined
+}
+
+/// Term of an `ORDER BY` clause, made with [`Expr::asc`] or [`Expr::desc`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderBy(Expr);
+
+impl From<Expr> for OrderBy {
+    fn from(expr: Expr) -> Self {
+        expr.asc()
+    }
+}
+
+/// Table in a `FROM` or `JOIN` clause, optionally aliased.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableRef {
+    name: Ident,
+    alias: Option<Ident>,
+}
+
+impl TableRef {
+    pub fn new(name: &str) -> Self {
+        TableRef {
+            name: Ident::new(name),
+            alias: None,
+        }
+    }
+
+    pub fn alias(mut self, alias: &str) -> Self {
+        self.alias = Some(Ident::new(alias));
+        self
+    }
+}
+
+impl From<&str> for TableRef {
+    fn from(name: &str) -> Self {
+        TableRef::new(name)
+    }
+}
+
+impl fmt::Display for TableRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.alias {
+            Some(alias) => write!(f, "{} {alias}", self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// SQL text with its bound arguments, ready to run through the REST client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    sql: String,
+    args: Vec<SqlArg>,
+}
+
+impl Statement {
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    pub fn args(&self) -> &[SqlArg] {
+        &self.args
+    }
+
+    fn bound(&self) -> Vec<&dyn ToSqlArg> {
+        self.args.iter().map(|arg| arg as &dyn ToSqlArg).collect()
+    }
+
+    /// Runs the statement, see [`IgniteRestClient::execute_sql`].
+    pub async fn execute(&self, client: &IgniteRestClient) -> Result<SqlResult> {
+        client.execute_sql(&self.sql, &self.bound()).await
+    }
+
+    /// Runs the statement and pages through its rows, see
+    /// [`IgniteRestClient::query`].
+    pub async fn query(&self, client: &IgniteRestClient) -> Result<SqlCursor> {
+        client.query(&self.sql, &self.bound()).await
+    }
+
+    /// Runs the statement and decodes every row into `T`, see
+    /// [`IgniteRestClient::query_as`].
+    pub async fn query_as<T: DeserializeOwned>(&self, client: &IgniteRestClient) -> Result<Vec<T>> {
+        client.query_as(&self.sql, &self.bound()).await
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.sql)
+    }
+}
+
+/// Accumulates the text and arguments of a statement.
+#[derive(Default)]
+struct SqlBuilder {
+    sql: String,
+    args: Vec<SqlArg>,
+}
+
+impl SqlBuilder {
+    fn push(&mut self, sql: &str) -> &mut Self {
+        self.sql.push_str(sql);
+        self
+    }
+
+    fn expr(&mut self, expr: &Expr) -> &mut Self {
+        self.sql.push_str(&expr.sql);
+        self.args.extend(expr.args.iter().cloned());
+        self
+    }
+
+    /// ` WHERE a AND b` for the conditions added with `filter`.
+    fn filter(&mut self, conditions: &[Expr]) -> &mut Self {
+        if !conditions.is_empty() {
+            self.push(" WHERE ")
+                .expr(&join(conditions.iter().cloned(), " AND "));
+        }
+        self
+    }
+
+    fn build(self) -> Statement {
+        Statement {
+            sql: self.sql,
+            args: self.args,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JoinKind {
+    Inner,
+    Left,
+}
+
+/// `SELECT` query.
+///
+/// ```
+/// use ignite_with_rest_api::{Select, col};
+///
+/// let query = Select::from("Person")
+///     .columns(["id", "name"])
+///     .filter(col("age").gt(25))
+///     .order_by(col("name").asc())
+///     .limit(10)
+///     .build();
+/// assert_eq!(
+///     query.sql(),
+///     "SELECT id, name FROM Person WHERE age > ? ORDER BY name ASC LIMIT 10"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Select {
+    from: TableRef,
+    distinct: bool,
+    columns: Vec<Expr>,
+    joins: Vec<(JoinKind, TableRef, Expr)>,
+    conditions: Vec<Expr>,
+    group_by: Vec<Expr>,
+    having: Vec<Expr>,
+    order_by: Vec<OrderBy>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+}
+
+impl Select {
+    /// Selects every column (`*`) of `table` until columns are added.
+    pub fn from(table: impl Into<TableRef>) -> Self {
+        Select {
+            from: table.into(),
+            distinct: false,
+            columns: Vec::new(),
+            joins: Vec::new(),
+            conditions: Vec::new(),
+            group_by: Vec::new(),
+            having: Vec::new(),
+            order_by: Vec::new(),
+            limit: None,
+            offset: None,
+        }
+    }
+
+    pub fn distinct(mut self) -> Self {
+        self.distinct = true;
+        self
+    }
+
+    /// Adds an expression to the select list.
+    pub fn column(mut self, column: Expr) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Adds columns by name to the select list.
+    pub fn columns<'a>(mut self, names: impl IntoIterator<Item = &'a str>) -> Self {
+        self.columns.extend(names.into_iter().map(col));
+        self
+    }
+
+    pub fn join(mut self, table: impl Into<TableRef>, on: Expr) -> Self {
+        self.joins.push((JoinKind::Inner, table.into(), on));
+        self
+    }
+
+    pub fn left_join(mut self, table: impl Into<TableRef>, on: Expr) -> Self {
+        self.joins.push((JoinKind::Left, table.into(), on));
+        self
+    }
+
+    /// Adds a `WHERE` condition; several conditions are joined with `AND`.
+    pub fn filter(mut self, condition: Expr) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    pub fn group_by(mut self, expr: Expr) -> Self {
+        self.group_by.push(expr);
+        self
+    }
+
+    /// Adds a `HAVING` condition; several conditions are joined with `AND`.
+    pub fn having(mut self, condition: Expr) -> Self {
+        self.having.push(condition);
+        self
+    }
+
+    pub fn order_by(mut self, order: impl Into<OrderBy>) -> Self {
+        self.order_by.push(order.into());
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    pub fn build(&self) -> Statement {
+        let mut out = SqlBuilder::default();
+        out.push("SELECT ");
+        if self.distinct {
+            out.push("DISTINCT ");
+        }
+        if self.columns.is_empty() {
+            out.push("*");
+        } else {
+            out.expr(&join(self.columns.iter().cloned(), ", "));
+        }
+        out.push(&format!(" FROM {}", self.from));
+        for (kind, table, on) in &self.joins {
+            let kind = match kind {
+                JoinKind::Inner => "JOIN",
+                JoinKind::Left => "LEFT JOIN",
+            };
+            out.push(&format!(" {kind} {table} ON ")).expr(on);
+        }
+        out.filter(&self.conditions);
+        if !self.group_by.is_empty() {
+            out.push(" GROUP BY ")
+                .expr(&join(self.group_by.iter().cloned(), ", "));
+        }
+        if !self.having.is_empty() {
+            out.push(" HAVING ")
+                .expr(&join(self.having.iter().cloned(), " AND "));
+        }
+        if !self.order_by.is_empty() {
+            let terms = self.order_by.iter().map(|order| order.0.clone());
+            out.push(" ORDER BY ").expr(&join(terms, ", "));
+        }
+        if let Some(limit) = self.limit {
+            out.push(&format!(" LIMIT {limit}"));
+        }
+        match (self.limit, self.offset) {
+            (Some(_), Some(offset)) => out.push(&format!(" OFFSET {offset}")),
+            // OFFSET without LIMIT needs the standard form.
+            (None, Some(offset)) => out.push(&format!(" OFFSET {offset} ROWS")),
+            (_, None) => &mut out,
+        };
+        out.build()
+    }
+}
+
+/// `INSERT` or `MERGE` of one or more rows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Insert {
+    verb: &'static str,
+    table: Ident,
+    columns: Vec<Ident>,
+    rows: Vec<Vec<Expr>>,
+}
+
+impl Insert {
+    pub fn into(table: &str) -> Self {
+        Insert::new("INSERT", table)
+    }
+
+    /// `MERGE INTO`: inserts rows, replacing those with the same primary key.
+    pub fn merge_into(table: &str) -> Self {
+        Insert::new("MERGE", table)
+    }
+
+    fn new(verb: &'static str, table: &str) -> Self {
+        Insert {
+            verb,
+            table: Ident::new(table),
+            columns: Vec::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn columns<'a>(mut self, names: impl IntoIterator<Item = &'a str>) -> Self {
+        self.columns.extend(names.into_iter().map(Ident::new));
+        self
+    }
+
+    /// Adds a row with one value per column, in column order.
+    pub fn values(mut self, values: &[&dyn ToSqlArg]) -> Self {
+        self.rows
+            .push(values.iter().map(|value| Expr::from(*value)).collect());
+        self
+    }
+
+    pub fn build(&self) -> Statement {
+        let mut out = SqlBuilder::default();
+        let columns: Vec<_> = self.columns.iter().map(Ident::as_sql).collect();
+        out.push(&format!(
+            "{} INTO {} ({}) VALUES ",
+            self.verb,
+            self.table,
+            columns.join(", ")
+        ));
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| join(row.iter().cloned(), ", ").wrap("(", ")"));
+        out.expr(&join(rows, ", "));
+        out.build()
+    }
+}
+
+/// `UPDATE` of the rows matching its conditions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Update {
+    table: Ident,
+    assignments: Vec<Expr>,
+    conditions: Vec<Expr>,
+}
+
+impl Update {
+    pub fn table(table: &str) -> Self {
+        Update {
+            table: Ident::new(table),
+            assignments: Vec::new(),
+            conditions: Vec::new(),
+        }
+    }
+
+    /// Sets `column` to `value`, either a bound value or an expression.
+    pub fn set(mut self, column: &str, value: impl Into<Expr>) -> Self {
+        self.assignments.push(col(column).eq(value));
+        self
+    }
+
+    /// Adds a `WHERE` condition; several conditions are joined with `AND`.
+    pub fn filter(mut self, condition: Expr) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    pub fn build(&self) -> Statement {
+        let mut out = SqlBuilder::default();
+        out.push(&format!("UPDATE {} SET ", self.table))
+            .expr(&join(self.assignments.iter().cloned(), ", "))
+            .filter(&self.conditions);
+        out.build()
+    }
+}
+
+/// `DELETE` of the rows matching its conditions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Delete {
+    table: Ident,
+    conditions: Vec<Expr>,
+}
+
+impl Delete {
+    pub fn from(table: &str) -> Self {
+        Delete {
+            table: Ident::new(table),
+            conditions: Vec::new(),
+        }
+    }
+
+    /// Adds a `WHERE` condition; several conditions are joined with `AND`.
+    pub fn filter(mut self, condition: Expr) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    pub fn build(&self) -> Statement {
+        let mut out = SqlBuilder::default();
+        out.push(&format!("DELETE FROM {}", self.table))
+            .filter(&self.conditions);
+        out.build()
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, BinaryArray, BooleanArray, Date32Array, FixedSizeBinaryArray, Float32Array,
+    Float64Array, Int8Array, Int16Array, Int32Array, Int64Array, StringArray,
+    Time64MicrosecondArray, TimestampMicrosecondArray,
+};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use futures::stream::{Stream, StreamExt};
+
+use crate::cursor::SqlCursor;
+use crate::error::Result;
+use crate::response::{FieldMetadata, SqlResult};
+use crate::value::{IgniteValue, JavaType, epoch_days, micros_since_midnight};
+
+/// Key of the Arrow field metadata naming a canonical extension type.
+const EXTENSION_NAME: &str = "ARROW:extension:name";
+
+impl FieldMetadata {
+    /// Nullable Arrow field for this column.
+    ///
+    /// Numbers, booleans, dates and byte arrays map to their Arrow
+    /// counterparts; times and timestamps to microsecond `Time64` and
+    /// `Timestamp` without a time zone. UUIDs are 16-byte `FixedSizeBinary`
+    /// tagged as the `arrow.uuid` extension. `BigDecimal` cells are `Utf8`
+    /// so that no digit is lost, as their scale may differ from row to row;
+    /// cells of other classes are `Utf8` holding JSON, tagged `arrow.json`.
+    pub fn arrow_field(&self) -> Field {
+        let field = |data_type| Field::new(&self.field_name, data_type, true);
+        let extension = |data_type, name: &str| {
+            field(data_type).with_metadata(HashMap::from([(
+                EXTENSION_NAME.to_string(),
+                name.to_string(),
+            )]))
+        };
+        match self.java_type() {
+            JavaType::Boolean => field(DataType::Boolean),
+            JavaType::Byte => field(DataType::Int8),
+            JavaType::Short => field(DataType::Int16),
+            JavaType::Integer => field(DataType::Int32),
+            JavaType::Long => field(DataType::Int64),
+            JavaType::Float => field(DataType::Float32),
+            JavaType::Double => field(DataType::Float64),
+            JavaType::Decimal | JavaType::String | JavaType::Char => field(DataType::Utf8),
+            JavaType::Uuid => extension(DataType::FixedSizeBinary(16), "arrow.uuid"),
+            JavaType::Date => field(DataType::Date32),
+            JavaType::Time => field(DataType::Time64(TimeUnit::Microsecond)),
+            JavaType::Timestamp => field(DataType::Timestamp(TimeUnit::Microsecond, None)),
+            JavaType::Bytes => field(DataType::Binary),
+            JavaType::Other(_) => extension(DataType::Utf8, "arrow.json"),
+        }
+    }
+}
+
+/// Arrow schema with one field per column, see [`FieldMetadata::arrow_field`].
+pub fn arrow_schema(fields: &[FieldMetadata]) -> Schema {
+    Schema::new(
+        fields
+            .iter()
+            .map(FieldMetadata::arrow_field)
+            .collect::<Vec<_>>(),
+    )
+}
+
+impl SqlResult {
+    /// Converts the rows of this page into a record batch whose schema comes
+    /// from `fieldsMetadata`. SQL NULL becomes an Arrow null in any column.
+    pub fn to_record_batch(&self) -> Result<RecordBatch> {
+        let fields = self.fields_metadata.as_deref().unwrap_or_default();
+        let schema = Arc::new(arrow_schema(fields));
+        record_batch(&schema, fields, &self.values()?)
+    }
+}
+
+impl SqlCursor {
+    /// Converts the result into record batches of up to `batch_size` rows
+    /// as pages arrive, so only one batch is held at a time. Every batch has
+    /// the same schema, derived from the first page's `fieldsMetadata`.
+    pub fn into_record_batches(
+        self,
+        batch_size: usize,
+    ) -> impl Stream<Item = Result<RecordBatch>> + Send {
+        let fields = self.fields().to_vec();
+        let schema = Arc::new(arrow_schema(&fields));
+        self.into_values()
+            .chunks(batch_size.max(1))
+            .map(move |rows| {
+                let rows = rows.into_iter().collect::<Result<Vec<_>>>()?;
+                record_batch(&schema, &fields, &rows)
+            })
+    }
+}
+
+fn record_batch(
+    schema: &SchemaRef,
+    fields: &[FieldMetadata],
+    rows: &[Vec<IgniteValue>],
+) -> Result<RecordBatch> {
+    let columns = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| column(&field.java_type(), rows.iter().map(|row| &row[index])))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+/// Arrow array of one column. Cells are already converted to the column's
+/// type, so any other variant can only be [`IgniteValue::Null`].
+fn column<'a>(
+    java_type: &JavaType,
+    cells: impl Iterator<Item = &'a IgniteValue>,
+) -> Result<ArrayRef> {
+    let array = match java_type {
+        JavaType::Boolean => array::<BooleanArray, _>(cells, |cell| match cell {
+            IgniteValue::Boolean(value) => Some(*value),
+            _ => None,
+        }),
+        JavaType::Byte => array::<Int8Array, _>(cells, |cell| match cell {
+            IgniteValue::Byte(value) => Some(*value),
+            _ => None,
+        }),
+        JavaType::Short => array::<Int16Array, _>(cells, |cell| match cell {
+            IgniteValue::Short(value) => Some(*value),
+            _ => None,
+        }),
+        JavaType::Integer => array::<Int32Array, _>(cells, |cell| match cell {
+            IgniteValue::Int(value) => Some(*value),
+            _ => None,
+        }),
+        JavaType::Long => array::<Int64Array, _>(cells, |cell| match cell {
+            IgniteValue::Long(value) => Some(*value),
+            _ => None,
+        }),
+        JavaType::Float => array::<Float32Array, _>(cells, |cell| match cell {
+            IgniteValue::Float(value) => Some(*value),
+            _ => None,
+        }),
+        JavaType::Double => array::<Float64Array, _>(cells, |cell| match cell {
+            IgniteValue::Double(value) => Some(*value),
+            _ => None,
+        }),
+        JavaType::Decimal | JavaType::String | JavaType::Char | JavaType::Other(_) => {
+            array::<StringArray, _>(cells, |cell| match cell {
+                IgniteValue::Decimal(value) => Some(value.to_string()),
+                IgniteValue::String(value) => Some(value.clone()),
+                IgniteValue::Other(value) => Some(value.to_string()),
+                _ => None,
+            })
+        }
+        JavaType::Uuid => Arc::new(FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+            cells.map(|cell| match cell {
+                IgniteValue::Uuid(value) => Some(*value.as_bytes()),
+                _ => None,
+            }),
+            16,
+        )?),
+        JavaType::Date => array::<Date32Array, _>(cells, |cell| match cell {
+            IgniteValue::Date(value) => Some(epoch_days(*value)),
+            _ => None,
+        }),
+        JavaType::Time => array::<Time64MicrosecondArray, _>(cells, |cell| match cell {
+            IgniteValue::Time(value) => Some(micros_since_midnight(*value)),
+            _ => None,
+        }),
+        JavaType::Timestamp => array::<TimestampMicrosecondArray, _>(cells, |cell| match cell {
+            IgniteValue::Timestamp(value) => Some(value.and_utc().timestamp_micros()),
+            _ => None,
+        }),
+        JavaType::Bytes => array::<BinaryArray, _>(cells, |cell| match cell {
+            IgniteValue::Bytes(value) => Some(value.clone()),
+            _ => None,
+        }),
+    };
+    Ok(array)
+}
+
+/// Array of type `A` holding `value` of each cell, or null where it is `None`.
+fn array<'a, A, T>(
+    cells: impl Iterator<Item = &'a IgniteValue>,
+    value: impl Fn(&IgniteValue) -> Option<T>,
+) -> ArrayRef
+where
+    A: Array + FromIterator<Option<T>> + 'static,
+{
+    Arc::new(cells.map(value).collect::<A>())
+}
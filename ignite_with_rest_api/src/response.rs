@@ -0,0 +1,76 @@
+use serde::Deserialize;
+
+use crate::error::{IgniteRestError, Result, STATUS_SUCCESS};
+
+/// Envelope returned by every Ignite REST command.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RestResponse<T> {
+    pub success_status: u32,
+    pub response: Option<T>,
+    pub error: Option<String>,
+    pub session_token: Option<String>,
+}
+
+impl<T> RestResponse<T> {
+    /// Returns the payload of a successful response, or the error described
+    /// by `successStatus` and `error`.
+    pub fn into_result(self, command: &str) -> Result<Option<T>> {
+        if self.success_status == STATUS_SUCCESS {
+            Ok(self.response)
+        } else {
+            Err(IgniteRestError::from_status(command, self.success_status, self.error))
+        }
+    }
+}
+
+/// Response of the `qryfldexe` SQL fields query command.
+pub type SqlResponse = RestResponse<SqlResult>;
+
+/// One page of a query result, as returned by `qryfldexe`, `qryexe`,
+/// `qryscanexe` and `qryfetch`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryPage<I> {
+    /// Only set on the first page of SQL fields queries.
+    pub fields_metadata: Option<Vec<FieldMetadata>>,
+    pub items: Option<Vec<I>>,
+    /// Server-side cursor id, used with `qryfetch`/`qrycls` to page through
+    /// the rest of the result.
+    pub query_id: Option<i64>,
+    /// `true` once the page holds the final rows of the result.
+    #[serde(default = "default_last")]
+    pub last: bool,
+}
+
+/// Page of a SQL fields query: one JSON array of cells per row.
+pub type SqlResult = QueryPage<Vec<serde_json::Value>>;
+
+/// Entry returned by scan and type-based SQL queries.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct KeyValue<K = serde_json::Value, V = serde_json::Value> {
+    pub key: K,
+    pub value: V,
+}
+
+fn default_last() -> bool {
+    true
+}
+
+impl<I> Default for QueryPage<I> {
+    fn default() -> Self {
+        QueryPage {
+            fields_metadata: None,
+            items: None,
+            query_id: None,
+            last: true,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldMetadata {
+    pub field_name: String,
+    pub field_type_name: String,
+}
@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+use crate::error::IgniteRestError;
+use crate::sql::is_read_only;
+
+/// How often, and after how long a pause, a command is sent again when the
+/// server could not be reached or answered with a 5xx status.
+///
+/// Only idempotent commands are retried once a request may have reached the
+/// server: reads, `SELECT` statements, and writes such as `put` whose repeat
+/// leaves the same state behind. DML, DDL and conditional cache writes fail
+/// with the original error instead, because the first attempt may already
+/// have been applied. A request whose connection was refused never arrived,
+/// so it is sent again whatever the command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub(crate) max_retries: u32,
+    pub(crate) initial_backoff: Duration,
+    pub(crate) max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Never retries; the first failure is returned as is.
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Retries after the first attempt, in total over all endpoints.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Pause before the first retry; it doubles with every further retry.
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Upper bound of the doubled pause.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Pause before retry number `retry`, counting from 1: the exponential
+    /// backoff, of which a random half is taken off so that clients that
+    /// failed together do not come back together.
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(retry.saturating_sub(1));
+        let ceiling = self
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff);
+        let half = ceiling / 2;
+        half + half.mul_f64(fastrand::f64())
+    }
+}
+
+/// Whether sending `cmd` twice has the same effect as sending it once.
+pub(crate) fn is_idempotent(cmd: &str, params: &[(&str, &str)]) -> bool {
+    match cmd {
+        "qryfldexe" => params
+            .iter()
+            .any(|(name, query)| *name == "qry" && is_read_only(query)),
+        "version" | "probe" | "name" | "top" | "node" | "state" | "get" | "getall"
+        | "containskey" | "containskeys" | "size" | "metadata" | "cache" | "qryexe"
+        | "qryscanexe" | "qrycls" | "put" | "putall" | "getorcreate" | "setstate" => true,
+        // `qryfetch` advances the cursor, so a retry could skip a page;
+        // `rmv`, `cas`, `getandput` and the like answer differently the
+        // second time.
+        _ => false,
+    }
+}
+
+/// Whether `err` says the endpoint, not the command, failed: the request got
+/// no response or a 5xx one. A node that is still starting answers `probe`
+/// with 503; that is the answer to the command, not a failure of the node.
+pub(crate) fn is_transient(cmd: &str, err: &IgniteRestError) -> bool {
+    match err {
+        _ if cmd == "probe" && is_unavailable(err) => false,
+        IgniteRestError::Transport(err) => {
+            err.status().is_none_or(|status| status.is_server_error())
+        }
+        _ => false,
+    }
+}
+
+/// Whether `err` is an HTTP 503 answer.
+pub(crate) fn is_unavailable(err: &IgniteRestError) -> bool {
+    matches!(
+        err,
+        IgniteRestError::Transport(err) if err.status() == Some(StatusCode::SERVICE_UNAVAILABLE)
+    )
+}
+
+/// Whether the request behind `err` certainly never reached the server.
+pub(crate) fn is_undelivered(err: &IgniteRestError) -> bool {
+    matches!(err, IgniteRestError::Transport(err) if err.is_connect())
+}
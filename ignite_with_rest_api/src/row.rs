@@ -0,0 +1,235 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::Value;
+
+use crate::response::{FieldMetadata, SqlResult};
+
+/// Failure to map one result row onto a Rust type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowDecodeError {
+    /// Zero-based index of the row within the result.
+    pub row: usize,
+    /// Column that could not be decoded, when known.
+    pub column: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for RowDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.column {
+            Some(column) => write!(f, "row {}, column `{}`: {}", self.row, column, self.message),
+            None => write!(f, "row {}: {}", self.row, self.message),
+        }
+    }
+}
+
+impl std::error::Error for RowDecodeError {}
+
+impl de::Error for RowDecodeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        RowDecodeError {
+            row: 0,
+            column: None,
+            message: msg.to_string(),
+        }
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        RowDecodeError {
+            row: 0,
+            column: Some(field.to_string()),
+            message: "missing column".to_string(),
+        }
+    }
+}
+
+impl SqlResult {
+    /// Decodes every row of this page into `T`.
+    ///
+    /// Structs are matched by column name (`fieldsMetadata`), ignoring ASCII
+    /// case so that Ignite's upper-cased `ID` lands in a field called `id`.
+    /// Tuples and sequences are filled by position.
+    pub fn rows_as<T: DeserializeOwned>(&self) -> Result<Vec<T>, RowDecodeError> {
+        let fields = self.fields_metadata.as_deref().unwrap_or_default();
+        let items = self.items.as_deref().unwrap_or_default();
+
+        items
+            .iter()
+            .enumerate()
+            .map(|(index, row)| decode_row(fields, row, index))
+            .collect()
+    }
+}
+
+/// Decodes a single row; `index` is only used to label errors.
+pub(crate) fn decode_row<T: DeserializeOwned>(
+    fields: &[FieldMetadata],
+    row: &[Value],
+    index: usize,
+) -> Result<T, RowDecodeError> {
+    T::deserialize(RowDeserializer { fields, row }).map_err(|mut err| {
+        err.row = index;
+        err
+    })
+}
+
+/// Deserializes a single row, pairing cells with their column metadata.
+struct RowDeserializer<'a> {
+    fields: &'a [FieldMetadata],
+    row: &'a [Value],
+}
+
+impl<'a> RowDeserializer<'a> {
+    fn check_width(&self) -> Result<(), RowDecodeError> {
+        if self.fields.len() != self.row.len() {
+            return Err(de::Error::custom(format_args!(
+                "row has {} cells but {} columns were described",
+                self.row.len(),
+                self.fields.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for RowDeserializer<'a> {
+    type Error = RowDecodeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.check_width()?;
+        let columns = self
+            .fields
+            .iter()
+            .map(|field| field.field_name.clone())
+            .zip(self.row)
+            .collect();
+        visitor.visit_map(RowMap { columns, value: None })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.check_width()?;
+        let columns = self
+            .fields
+            .iter()
+            .zip(self.row)
+            .map(|(meta, cell)| (match_field(&meta.field_name, fields), cell))
+            .collect();
+        visitor.visit_map(RowMap { columns, value: None })
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let columns = self
+            .row
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| {
+                let name = self
+                    .fields
+                    .get(index)
+                    .map_or_else(|| index.to_string(), |meta| meta.field_name.clone());
+                (name, cell)
+            })
+            .collect::<Vec<_>>();
+        visitor.visit_seq(RowSeq { columns: columns.into_iter() })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct enum identifier ignored_any
+    }
+}
+
+/// Picks the struct field a column maps to: exact name first, then ASCII
+/// case-insensitive, otherwise the column name itself.
+fn match_field(column: &str, fields: &'static [&'static str]) -> String {
+    fields
+        .iter()
+        .find(|field| **field == column)
+        .or_else(|| fields.iter().find(|field| field.eq_ignore_ascii_case(column)))
+        .map_or_else(|| column.to_string(), |field| field.to_string())
+}
+
+fn decode_cell<'de, T: DeserializeSeed<'de>>(
+    seed: T,
+    column: String,
+    cell: &Value,
+) -> Result<T::Value, RowDecodeError> {
+    seed.deserialize(cell.clone()).map_err(|err| RowDecodeError {
+        row: 0,
+        column: Some(column),
+        message: err.to_string(),
+    })
+}
+
+struct RowMap<'a> {
+    columns: VecDeque<(String, &'a Value)>,
+    value: Option<(String, &'a Value)>,
+}
+
+impl<'de, 'a> MapAccess<'de> for RowMap<'a> {
+    type Error = RowDecodeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        let Some((name, cell)) = self.columns.pop_front() else {
+            return Ok(None);
+        };
+        let key = seed.deserialize(IntoDeserializer::<RowDecodeError>::into_deserializer(name.as_str()))?;
+        self.value = Some((name, cell));
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        let (name, cell) = self
+            .value
+            .take()
+            .ok_or_else(|| <RowDecodeError as de::Error>::custom("value requested before key"))?;
+        decode_cell(seed, name, cell)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.columns.len())
+    }
+}
+
+struct RowSeq<'a> {
+    columns: std::vec::IntoIter<(String, &'a Value)>,
+}
+
+impl<'de, 'a> SeqAccess<'de> for RowSeq<'a> {
+    type Error = RowDecodeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+        match self.columns.next() {
+            Some((name, cell)) => decode_cell(seed, name, cell).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.columns.len())
+    }
+}
@@ -0,0 +1,62 @@
+use crate::args::{ToSqlArg, bind};
+use crate::cache::RestCache;
+use crate::cursor::EntryCursor;
+use crate::error::Result;
+
+impl RestCache {
+    /// `qryscanexe`: every entry of the cache, including caches that have no
+    /// SQL schema.
+    pub async fn scan(&self) -> Result<EntryCursor> {
+        self.open_scan(None).await
+    }
+
+    /// `qryscanexe` with a server-side filter: `filter_class` is the fully
+    /// qualified name of an `IgniteBiPredicate` deployed on the cluster.
+    pub async fn scan_filtered(&self, filter_class: &str) -> Result<EntryCursor> {
+        self.open_scan(Some(filter_class)).await
+    }
+
+    /// `qryexe`: entries whose value is of SQL type `value_type` and matches
+    /// `clause`, the part of the query after `WHERE`, e.g. `age > ?`.
+    pub async fn sql_query(
+        &self,
+        value_type: &str,
+        clause: &str,
+        args: &[&dyn ToSqlArg],
+    ) -> Result<EntryCursor> {
+        let cmd = "qryexe";
+        let bound = bind(clause, args)?;
+        let page_size = self.client().page_size().to_string();
+        let mut params = vec![
+            ("cacheName", self.name()),
+            ("type", value_type),
+            ("qry", bound.sql.as_str()),
+            ("pageSize", page_size.as_str()),
+        ];
+        let arg_names = bound.arg_names();
+        params.extend(bound.arg_params(&arg_names));
+
+        self.open_entries(cmd, &params).await
+    }
+
+    async fn open_scan(&self, filter_class: Option<&str>) -> Result<EntryCursor> {
+        let page_size = self.client().page_size().to_string();
+        let mut params = vec![("cacheName", self.name()), ("pageSize", page_size.as_str())];
+        if let Some(filter_class) = filter_class {
+            params.push(("className", filter_class));
+        }
+
+        self.open_entries("qryscanexe", &params).await
+    }
+
+    async fn open_entries(&self, cmd: &'static str, params: &[(&str, &str)]) -> Result<EntryCursor> {
+        let (first_page, endpoint) = self.client().command_on(None, cmd, params).await?;
+
+        Ok(EntryCursor::new(
+            self.client().clone(),
+            cmd,
+            endpoint,
+            first_page.unwrap_or_default(),
+        ))
+    }
+}
@@ -0,0 +1,137 @@
+/// Calls `on_code` with the byte offset and character of everything that is
+/// SQL code, skipping `'...'` literals, `"..."` identifiers and comments.
+pub(crate) fn scan_code(sql: &str, mut on_code: impl FnMut(usize, char)) {
+    let mut chars = sql.char_indices().peekable();
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            '\'' | '"' => {
+                // A doubled quote is an escaped quote and keeps us inside.
+                while let Some((_, next)) = chars.next() {
+                    if next == ch {
+                        if chars.peek().map(|(_, c)| *c) == Some(ch) {
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+            '-' if chars.peek().map(|(_, c)| *c) == Some('-') => {
+                for (_, next) in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek().map(|(_, c)| *c) == Some('*') => {
+                chars.next();
+                let mut previous = '\0';
+                for (_, next) in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            _ => on_code(index, ch),
+        }
+    }
+}
+
+/// Byte offsets of the `?` placeholders in `sql`.
+pub(crate) fn placeholders(sql: &str) -> Vec<usize> {
+    let mut positions = Vec::new();
+    scan_code(sql, |index, ch| {
+        if ch == '?' {
+            positions.push(index);
+        }
+    });
+    positions
+}
+
+/// Whether `sql` ends with a `;` that is not inside a literal or comment,
+/// ignoring trailing whitespace and comments.
+pub fn is_terminated(sql: &str) -> bool {
+    let mut last = None;
+    scan_code(sql, |_, ch| {
+        if !ch.is_whitespace() {
+            last = Some(ch);
+        }
+    });
+    last == Some(';')
+}
+
+/// Splits a script into its `;`-separated statements, without the
+/// separators. Blank statements are dropped.
+pub fn split_statements(script: &str) -> Vec<&str> {
+    let mut ends = Vec::new();
+    scan_code(script, |index, ch| {
+        if ch == ';' {
+            ends.push(index);
+        }
+    });
+
+    let mut statements = Vec::new();
+    let mut start = 0;
+    for end in ends.into_iter().chain([script.len()]) {
+        let statement = script[start..end].trim();
+        if !is_blank(statement) {
+            statements.push(statement);
+        }
+        start = (end + 1).min(script.len());
+    }
+    statements
+}
+
+/// Whether `sql` holds nothing but whitespace and comments.
+fn is_blank(sql: &str) -> bool {
+    let mut blank = true;
+    scan_code(sql, |_, ch| blank &= ch.is_whitespace());
+    blank
+}
+
+/// Whether `sql` only reads data, so running it twice has no further
+/// effect: it starts with a query keyword and names no writing statement
+/// anywhere outside literals and comments, which also rules out
+/// `SELECT ... FOR UPDATE`.
+pub(crate) fn is_read_only(sql: &str) -> bool {
+    let mut code = String::with_capacity(sql.len());
+    let mut next = 0;
+    scan_code(sql, |index, ch| {
+        // Keep skipped literals and comments as word breaks.
+        if index != next {
+            code.push(' ');
+        }
+        code.push(ch);
+        next = index + ch.len_utf8();
+    });
+
+    let mut words = code
+        .split(|ch: char| !ch.is_alphanumeric() && ch != '_')
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_uppercase);
+    let starts_as_query = words.next().is_some_and(|word| {
+        matches!(word.as_str(), "SELECT" | "WITH" | "EXPLAIN" | "SHOW" | "VALUES" | "TABLE")
+    });
+    starts_as_query
+        && !words.any(|word| {
+            matches!(
+                word.as_str(),
+                "INSERT" | "UPDATE" | "DELETE" | "MERGE" | "CREATE" | "DROP" | "ALTER" | "COPY"
+            )
+        })
+}
+
+/// Whether `name` is an unquoted SQL identifier, safe to paste into a
+/// statement.
+pub(crate) fn is_identifier(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+        && name.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+/// Whether `name` is `TABLE` or `SCHEMA.TABLE` made of unquoted identifiers.
+pub(crate) fn is_table_name(name: &str) -> bool {
+    name.split('.').count() <= 2 && name.split('.').all(is_identifier)
+}
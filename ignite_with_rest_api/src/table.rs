@@ -0,0 +1,297 @@
+use std::future::Future;
+
+use serde::de::DeserializeOwned;
+
+use crate::args::ToSqlArg;
+use crate::client::IgniteRestClient;
+use crate::error::Result;
+use crate::query::Ident;
+use crate::response::SqlResult;
+
+/// Schema of a SQL table mapped to a struct, as generated by
+/// `#[derive(IgniteTable)]`.
+///
+/// Names are written into statements the way [`Ident::new`] writes them, so
+/// a column named after a keyword, such as `key` or `value`, is quoted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableDef {
+    /// `TABLE` or `SCHEMA.TABLE`.
+    pub name: &'static str,
+    /// Columns in field order.
+    pub columns: &'static [ColumnDef],
+    pub indexes: &'static [IndexDef],
+    /// Parameters of the `WITH "..."` clause of `CREATE TABLE`, such as
+    /// `TEMPLATE` or `AFFINITY_KEY`.
+    pub options: &'static [(&'static str, &'static str)],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnDef {
+    /// SQL column name.
+    pub name: &'static str,
+    /// Struct field the column is read into.
+    pub field: &'static str,
+    pub sql_type: &'static str,
+    pub primary_key: bool,
+    pub nullable: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexDef {
+    pub name: &'static str,
+    pub columns: &'static [&'static str],
+}
+
+impl TableDef {
+    /// `CREATE TABLE IF NOT EXISTS` statement for this table.
+    pub fn create_table_sql(&self) -> String {
+        let mut columns: Vec<_> = self
+            .columns
+            .iter()
+            .map(|column| {
+                let null = if column.nullable || column.primary_key {
+                    ""
+                } else {
+                    " NOT NULL"
+                };
+                format!("{} {}{null}", Ident::new(column.name), column.sql_type)
+            })
+            .collect();
+        columns.push(format!("PRIMARY KEY ({})", self.key_columns().join(", ")));
+        let mut sql = format!(
+            "CREATE TABLE IF NOT EXISTS {} ({})",
+            Ident::new(self.name),
+            columns.join(", ")
+        );
+        if !self.options.is_empty() {
+            let options: Vec<_> = self
+                .options
+                .iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect();
+            sql.push_str(&format!(" WITH \"{}\"", options.join(",")));
+        }
+        sql
+    }
+
+    /// `CREATE INDEX IF NOT EXISTS` statements, one per index.
+    pub fn create_index_sql(&self) -> Vec<String> {
+        self.indexes
+            .iter()
+            .map(|index| {
+                let columns: Vec<_> = index
+                    .columns
+                    .iter()
+                    .map(|column| Ident::new(column).to_string())
+                    .collect();
+                format!(
+                    "CREATE INDEX IF NOT EXISTS {} ON {} ({})",
+                    Ident::new(index.name),
+                    Ident::new(self.name),
+                    columns.join(", ")
+                )
+            })
+            .collect()
+    }
+
+    fn key_columns(&self) -> Vec<String> {
+        self.columns
+            .iter()
+            .filter(|column| column.primary_key)
+            .map(|column| Ident::new(column.name).to_string())
+            .collect()
+    }
+
+    /// Select list reading every column into the struct field of the same
+    /// name, aliasing columns that were renamed.
+    fn select_list(&self) -> String {
+        self.columns
+            .iter()
+            .map(|column| {
+                let name = Ident::new(column.name);
+                if column.name.eq_ignore_ascii_case(column.field) {
+                    name.to_string()
+                } else {
+                    format!("{name} AS {}", Ident::new(column.field))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn key_condition(&self) -> String {
+        self.key_columns()
+            .iter()
+            .map(|column| format!("{column} = ?"))
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    }
+
+    fn write_sql(&self, verb: &str) -> String {
+        let names: Vec<_> = self
+            .columns
+            .iter()
+            .map(|column| Ident::new(column.name).to_string())
+            .collect();
+        let placeholders = vec!["?"; names.len()].join(", ");
+        format!(
+            "{verb} INTO {} ({}) VALUES ({placeholders})",
+            Ident::new(self.name),
+            names.join(", ")
+        )
+    }
+
+    fn select_sql(&self, condition: &str) -> String {
+        format!(
+            "SELECT {} FROM {} WHERE {condition}",
+            self.select_list(),
+            Ident::new(self.name)
+        )
+    }
+
+    fn update_sql(&self) -> String {
+        let set: Vec<_> = self
+            .columns
+            .iter()
+            .filter(|column| !column.primary_key)
+            .map(|column| format!("{} = ?", Ident::new(column.name)))
+            .collect();
+        format!(
+            "UPDATE {} SET {} WHERE {}",
+            Ident::new(self.name),
+            set.join(", "),
+            self.key_condition()
+        )
+    }
+
+    fn delete_sql(&self) -> String {
+        format!(
+            "DELETE FROM {} WHERE {}",
+            Ident::new(self.name),
+            self.key_condition()
+        )
+    }
+}
+
+/// A struct stored as one row of a SQL table.
+///
+/// Usually derived: `#[derive(IgniteTable)]` maps each field to a column and
+/// takes the table layout from `#[ignite(...)]` attributes, see the
+/// [derive macro](macro@crate::IgniteTable). Rows are read back through
+/// [`serde::Deserialize`], which has to be derived as well.
+///
+/// Every statement binds the struct's values to placeholders.
+pub trait IgniteTable: DeserializeOwned + Send + Sync {
+    /// Primary key: the type of the key field, or a tuple of the key fields
+    /// in declaration order.
+    type Key: Sync;
+
+    const TABLE: TableDef;
+
+    /// Values of all columns, in the order of [`TableDef::columns`].
+    fn values(&self) -> Vec<&dyn ToSqlArg>;
+
+    /// Values of the primary key columns of `key`.
+    fn key_values(key: &Self::Key) -> Vec<&dyn ToSqlArg>;
+
+    /// Creates the table and its indexes unless they already exist.
+    fn create_table(client: &IgniteRestClient) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            client
+                .execute_sql(&Self::TABLE.create_table_sql(), &[])
+                .await?;
+            for sql in Self::TABLE.create_index_sql() {
+                client.execute_sql(&sql, &[]).await?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Inserts this row; fails with a duplicate key error if it exists.
+    fn insert(&self, client: &IgniteRestClient) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            client
+                .execute_sql(&Self::TABLE.write_sql("INSERT"), &self.values())
+                .await?;
+            Ok(())
+        }
+    }
+
+    /// Inserts this row or replaces the row with the same primary key.
+    fn upsert(&self, client: &IgniteRestClient) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            client
+                .execute_sql(&Self::TABLE.write_sql("MERGE"), &self.values())
+                .await?;
+            Ok(())
+        }
+    }
+
+    /// Row with the primary key `key`, if there is one.
+    fn select_by_pk(
+        client: &IgniteRestClient,
+        key: &Self::Key,
+    ) -> impl Future<Output = Result<Option<Self>>> + Send {
+        async move {
+            let sql = Self::TABLE.select_sql(&Self::TABLE.key_condition());
+            let result = client.execute_sql(&sql, &Self::key_values(key)).await?;
+            Ok(result.rows_as::<Self>()?.into_iter().next())
+        }
+    }
+
+    /// Writes every column of this row except the primary key. Returns
+    /// `false` if there is no row with this primary key.
+    fn update(&self, client: &IgniteRestClient) -> impl Future<Output = Result<bool>> + Send {
+        async move {
+            let values = self.values();
+            let (key, mut args): (Vec<_>, Vec<_>) = Self::TABLE
+                .columns
+                .iter()
+                .zip(values)
+                .partition(|(column, _)| column.primary_key);
+            args.extend(key);
+            let args: Vec<_> = args.into_iter().map(|(_, value)| value).collect();
+            let result = client.execute_sql(&Self::TABLE.update_sql(), &args).await?;
+            Ok(affected_rows(&result) > 0)
+        }
+    }
+
+    /// Deletes the row with the primary key `key`. Returns `false` if there
+    /// was none.
+    fn delete(
+        client: &IgniteRestClient,
+        key: &Self::Key,
+    ) -> impl Future<Output = Result<bool>> + Send {
+        async move {
+            let result = client
+                .execute_sql(&Self::TABLE.delete_sql(), &Self::key_values(key))
+                .await?;
+            Ok(affected_rows(&result) > 0)
+        }
+    }
+
+    /// Rows matching `condition`, a SQL boolean expression over the column
+    /// names whose `?` placeholders are bound to `args`. Columns named after
+    /// keywords have to be quoted there, e.g. `"KEY" = ?`. All pages of the
+    /// result are fetched.
+    fn find_where(
+        client: &IgniteRestClient,
+        condition: &str,
+        args: &[&dyn ToSqlArg],
+    ) -> impl Future<Output = Result<Vec<Self>>> + Send {
+        async move {
+            client
+                .query_as(&Self::TABLE.select_sql(condition), args)
+                .await
+        }
+    }
+}
+
+/// Update count of a DML statement, which Ignite returns as a single cell.
+fn affected_rows(result: &SqlResult) -> u64 {
+    result
+        .items
+        .as_ref()
+        .and_then(|items| items.first()?.first()?.as_u64())
+        .unwrap_or(0)
+}
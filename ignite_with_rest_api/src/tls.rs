@@ -0,0 +1,128 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use reqwest::ClientBuilder;
+use reqwest::tls::{Certificate, Identity};
+
+use crate::error::{IgniteRestError, Result};
+
+/// TLS settings for `https` endpoints: the client side of the
+/// `SslContextFactory` in the Jetty configuration that the REST connector
+/// loads from `ConnectorConfiguration.jettyPath`.
+///
+/// The server certificate (Jetty's `keyStorePath`) is checked against the
+/// system roots plus any [`ca_certificate`](Self::ca_certificate). A client
+/// certificate is only needed when the connector sets `needClientAuth` or
+/// `wantClientAuth`, and must chain to a CA in Jetty's `trustStorePath`.
+/// Files are read when the client is built.
+#[derive(Clone, Default)]
+pub struct TlsConfig {
+    ca_certificates: Vec<PathBuf>,
+    identity: Option<ClientIdentity>,
+    insecure_skip_verify: bool,
+}
+
+#[derive(Clone)]
+enum ClientIdentity {
+    Pem { certificate: PathBuf, key: PathBuf },
+    Pkcs12 { path: PathBuf, password: String },
+}
+
+impl TlsConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trusts the CA certificates in the PEM file at `path`, which may hold
+    /// several. Can be called more than once.
+    pub fn ca_certificate(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ca_certificates.push(path.into());
+        self
+    }
+
+    /// Presents the PEM certificate chain in `certificate` with the PKCS#8
+    /// private key (`BEGIN PRIVATE KEY`) in `key`.
+    pub fn client_pem(mut self, certificate: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        self.identity = Some(ClientIdentity::Pem {
+            certificate: certificate.into(),
+            key: key.into(),
+        });
+        self
+    }
+
+    /// Presents the certificate and key of a PKCS#12 keystore, as written by
+    /// `keytool -storetype PKCS12` or `openssl pkcs12 -export`.
+    pub fn client_pkcs12(mut self, path: impl Into<PathBuf>, password: impl Into<String>) -> Self {
+        self.identity = Some(ClientIdentity::Pkcs12 {
+            path: path.into(),
+            password: password.into(),
+        });
+        self
+    }
+
+    /// Accepts any server certificate, including self-signed ones, expired
+    /// ones and ones issued for another host. For development only.
+    pub fn insecure_skip_verify(mut self, insecure_skip_verify: bool) -> Self {
+        self.insecure_skip_verify = insecure_skip_verify;
+        self
+    }
+
+    pub(crate) fn apply(&self, mut builder: ClientBuilder) -> Result<ClientBuilder> {
+        for path in &self.ca_certificates {
+            let certificates = Certificate::from_pem_bundle(&read(path, "CA certificate")?)
+                .map_err(|err| invalid(path, "CA certificate", err))?;
+            if certificates.is_empty() {
+                return Err(invalid(path, "CA certificate", "no PEM certificate found"));
+            }
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        let identity = match &self.identity {
+            Some(ClientIdentity::Pem { certificate, key }) => Some(
+                Identity::from_pkcs8_pem(
+                    &read(certificate, "client certificate")?,
+                    &read(key, "client key")?,
+                )
+                .map_err(|err| invalid(certificate, "client certificate or key", err))?,
+            ),
+            Some(ClientIdentity::Pkcs12 { path, password }) => Some(
+                Identity::from_pkcs12_der(&read(path, "PKCS#12 keystore")?, password)
+                    .map_err(|err| invalid(path, "PKCS#12 keystore", err))?,
+            ),
+            None => None,
+        };
+        if let Some(identity) = identity {
+            builder = builder.identity(identity);
+        }
+
+        Ok(builder.danger_accept_invalid_certs(self.insecure_skip_verify))
+    }
+}
+
+impl fmt::Debug for TlsConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let identity = match &self.identity {
+            Some(ClientIdentity::Pem { certificate, .. }) => Some(certificate),
+            Some(ClientIdentity::Pkcs12 { path, .. }) => Some(path),
+            None => None,
+        };
+        f.debug_struct("TlsConfig")
+            .field("ca_certificates", &self.ca_certificates)
+            .field("identity", &identity)
+            .field("insecure_skip_verify", &self.insecure_skip_verify)
+            .finish()
+    }
+}
+
+fn read(path: &Path, what: &str) -> Result<Vec<u8>> {
+    fs::read(path).map_err(|err| {
+        IgniteRestError::Config(format!("failed to read {what} `{}`: {err}", path.display()))
+    })
+}
+
+fn invalid(path: &Path, what: &str, err: impl fmt::Display) -> IgniteRestError {
+    IgniteRestError::Config(format!("invalid {what} `{}`: {err}", path.display()))
+}
@@ -0,0 +1,384 @@
+use std::fmt;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use futures::stream::{Stream, StreamExt};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::args::{SqlArg, ToSqlArg};
+use crate::cursor::SqlCursor;
+use crate::error::Result;
+use crate::response::{FieldMetadata, SqlResult};
+use crate::row::RowDecodeError;
+
+/// Column type as named by `fieldTypeName`, e.g. `java.lang.Integer`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum JavaType {
+    Boolean,
+    Byte,
+    Short,
+    Integer,
+    Long,
+    Float,
+    Double,
+    /// `java.math.BigDecimal`.
+    Decimal,
+    String,
+    Char,
+    Uuid,
+    Date,
+    Time,
+    Timestamp,
+    /// `[B`, a `byte[]`.
+    Bytes,
+    /// Any other class, kept by name.
+    Other(String),
+}
+
+impl JavaType {
+    /// Parses a Java class name; primitive names such as `int` are accepted
+    /// too, as cache metadata uses them for primitive fields.
+    pub fn from_name(name: &str) -> JavaType {
+        match name {
+            "java.lang.Boolean" | "boolean" => JavaType::Boolean,
+            "java.lang.Byte" | "byte" => JavaType::Byte,
+            "java.lang.Short" | "short" => JavaType::Short,
+            "java.lang.Integer" | "int" => JavaType::Integer,
+            "java.lang.Long" | "long" => JavaType::Long,
+            "java.lang.Float" | "float" => JavaType::Float,
+            "java.lang.Double" | "double" => JavaType::Double,
+            "java.math.BigDecimal" => JavaType::Decimal,
+            "java.lang.String" => JavaType::String,
+            "java.lang.Character" | "char" => JavaType::Char,
+            "java.util.UUID" => JavaType::Uuid,
+            "java.sql.Date" | "java.time.LocalDate" => JavaType::Date,
+            "java.sql.Time" | "java.time.LocalTime" => JavaType::Time,
+            "java.sql.Timestamp" | "java.util.Date" | "java.time.LocalDateTime" => {
+                JavaType::Timestamp
+            }
+            "[B" | "byte[]" => JavaType::Bytes,
+            other => JavaType::Other(other.to_string()),
+        }
+    }
+
+    /// Canonical class name, as Ignite reports it in `fieldTypeName`.
+    pub fn name(&self) -> &str {
+        match self {
+            JavaType::Boolean => "java.lang.Boolean",
+            JavaType::Byte => "java.lang.Byte",
+            JavaType::Short => "java.lang.Short",
+            JavaType::Integer => "java.lang.Integer",
+            JavaType::Long => "java.lang.Long",
+            JavaType::Float => "java.lang.Float",
+            JavaType::Double => "java.lang.Double",
+            JavaType::Decimal => "java.math.BigDecimal",
+            JavaType::String => "java.lang.String",
+            JavaType::Char => "java.lang.Character",
+            JavaType::Uuid => "java.util.UUID",
+            JavaType::Date => "java.sql.Date",
+            JavaType::Time => "java.sql.Time",
+            JavaType::Timestamp => "java.sql.Timestamp",
+            JavaType::Bytes => "[B",
+            JavaType::Other(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for JavaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FieldMetadata {
+    pub fn java_type(&self) -> JavaType {
+        JavaType::from_name(&self.field_type_name)
+    }
+}
+
+/// A result cell converted according to its column's Java type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IgniteValue {
+    Null,
+    Boolean(bool),
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    Decimal(Decimal),
+    String(String),
+    Uuid(Uuid),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Timestamp(NaiveDateTime),
+    Bytes(Vec<u8>),
+    /// Cell of a [`JavaType::Other`] column, left as JSON.
+    Other(Value),
+}
+
+impl IgniteValue {
+    /// Converts a JSON cell of a column declared as `java_type`.
+    ///
+    /// A `Long` never goes through `f64`. A `BigDecimal` keeps every digit
+    /// with the `exact-decimal` feature; without it serde_json has already
+    /// read the number as an `f64`, which holds about 17 significant digits.
+    /// Decimals sent as JSON strings are exact either way. Dates and times are
+    /// accepted as epoch milliseconds, ISO 8601 text or the US medium format
+    /// (`Jan 2, 2024 3:04:05 PM`) that the REST connector's object mapper
+    /// uses; byte arrays as base64 text or an array of numbers.
+    pub fn from_json(java_type: &JavaType, cell: &Value) -> Result<IgniteValue, RowDecodeError> {
+        let mismatch = || {
+            <RowDecodeError as serde::de::Error>::custom(format_args!(
+                "cannot read {cell} as {java_type}"
+            ))
+        };
+        if cell.is_null() {
+            return Ok(IgniteValue::Null);
+        }
+
+        let value = match java_type {
+            JavaType::Boolean => match cell {
+                Value::Bool(value) => IgniteValue::Boolean(*value),
+                Value::String(text) => IgniteValue::Boolean(text.parse().map_err(|_| mismatch())?),
+                _ => return Err(mismatch()),
+            },
+            JavaType::Byte => IgniteValue::Byte(integer(cell).ok_or_else(mismatch)?),
+            JavaType::Short => IgniteValue::Short(integer(cell).ok_or_else(mismatch)?),
+            JavaType::Integer => IgniteValue::Int(integer(cell).ok_or_else(mismatch)?),
+            JavaType::Long => IgniteValue::Long(integer(cell).ok_or_else(mismatch)?),
+            JavaType::Float => IgniteValue::Float(float(cell).ok_or_else(mismatch)? as f32),
+            JavaType::Double => IgniteValue::Double(float(cell).ok_or_else(mismatch)?),
+            JavaType::Decimal => IgniteValue::Decimal(decimal(cell).ok_or_else(mismatch)?),
+            JavaType::String | JavaType::Char => match cell {
+                Value::String(text) => IgniteValue::String(text.clone()),
+                other => IgniteValue::String(other.to_string()),
+            },
+            JavaType::Uuid => match cell {
+                Value::String(text) => IgniteValue::Uuid(text.parse().map_err(|_| mismatch())?),
+                _ => return Err(mismatch()),
+            },
+            JavaType::Date => IgniteValue::Date(date(cell).ok_or_else(mismatch)?),
+            JavaType::Time => IgniteValue::Time(time(cell).ok_or_else(mismatch)?),
+            JavaType::Timestamp => IgniteValue::Timestamp(timestamp(cell).ok_or_else(mismatch)?),
+            JavaType::Bytes => IgniteValue::Bytes(bytes(cell).ok_or_else(mismatch)?),
+            JavaType::Other(_) => IgniteValue::Other(cell.clone()),
+        };
+        Ok(value)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, IgniteValue::Null)
+    }
+}
+
+impl ToSqlArg for IgniteValue {
+    fn to_sql_arg(&self) -> SqlArg {
+        match self {
+            IgniteValue::Null => SqlArg::Null,
+            IgniteValue::Boolean(value) => SqlArg::Bool(*value),
+            IgniteValue::Byte(value) => SqlArg::Int(i64::from(*value)),
+            IgniteValue::Short(value) => SqlArg::Int(i64::from(*value)),
+            IgniteValue::Int(value) => SqlArg::Int(i64::from(*value)),
+            IgniteValue::Long(value) => SqlArg::Int(*value),
+            IgniteValue::Float(value) => SqlArg::Float(f64::from(*value)),
+            IgniteValue::Double(value) => SqlArg::Float(*value),
+            IgniteValue::Decimal(value) => SqlArg::Decimal(*value),
+            IgniteValue::String(value) => SqlArg::String(value.clone()),
+            IgniteValue::Uuid(value) => SqlArg::Uuid(*value),
+            IgniteValue::Date(value) => SqlArg::Date(*value),
+            IgniteValue::Time(value) => SqlArg::Time(*value),
+            IgniteValue::Timestamp(value) => SqlArg::Timestamp(*value),
+            IgniteValue::Bytes(value) => SqlArg::Bytes(value.clone()),
+            IgniteValue::Other(Value::String(text)) => SqlArg::String(text.clone()),
+            IgniteValue::Other(other) => SqlArg::String(other.to_string()),
+        }
+    }
+}
+
+impl SqlResult {
+    /// Converts every row of this page into [`IgniteValue`]s, following the
+    /// types in `fieldsMetadata`.
+    pub fn values(&self) -> Result<Vec<Vec<IgniteValue>>, RowDecodeError> {
+        let fields = self.fields_metadata.as_deref().unwrap_or_default();
+        let types: Vec<JavaType> = fields.iter().map(FieldMetadata::java_type).collect();
+        let items = self.items.as_deref().unwrap_or_default();
+
+        items
+            .iter()
+            .enumerate()
+            .map(|(index, row)| decode_values(fields, &types, row, index))
+            .collect()
+    }
+}
+
+impl SqlCursor {
+    /// Converts each row into [`IgniteValue`]s as it arrives, see
+    /// [`SqlResult::values`].
+    pub fn into_values(self) -> impl Stream<Item = Result<Vec<IgniteValue>>> + Send {
+        let fields = self.fields().to_vec();
+        let types: Vec<JavaType> = fields.iter().map(FieldMetadata::java_type).collect();
+        self.enumerate().map(move |(index, row)| {
+            let row = row?;
+            Ok(decode_values(&fields, &types, &row, index)?)
+        })
+    }
+}
+
+pub(crate) fn decode_values(
+    fields: &[FieldMetadata],
+    types: &[JavaType],
+    row: &[Value],
+    index: usize,
+) -> Result<Vec<IgniteValue>, RowDecodeError> {
+    if row.len() != types.len() {
+        return Err(RowDecodeError {
+            row: index,
+            column: None,
+            message: format!(
+                "row has {} cells but {} columns were described",
+                row.len(),
+                types.len()
+            ),
+        });
+    }
+    row.iter()
+        .zip(types)
+        .zip(fields)
+        .map(|((cell, java_type), field)| {
+            IgniteValue::from_json(java_type, cell).map_err(|mut err| {
+                err.row = index;
+                err.column = Some(field.field_name.clone());
+                err
+            })
+        })
+        .collect()
+}
+
+/// Integer cell that fits `T`; quoted numbers are accepted too.
+fn integer<T: TryFrom<i64>>(cell: &Value) -> Option<T> {
+    let value = match cell {
+        Value::Number(number) => number.as_i64()?,
+        Value::String(text) => text.trim().parse().ok()?,
+        _ => return None,
+    };
+    T::try_from(value).ok()
+}
+
+fn float(cell: &Value) -> Option<f64> {
+    match cell {
+        Value::Number(number) => number.as_f64(),
+        // Jackson writes non-finite doubles as these strings.
+        Value::String(text) => match text.as_str() {
+            "NaN" => Some(f64::NAN),
+            "Infinity" => Some(f64::INFINITY),
+            "-Infinity" => Some(f64::NEG_INFINITY),
+            text => text.trim().parse().ok(),
+        },
+        _ => None,
+    }
+}
+
+fn decimal(cell: &Value) -> Option<Decimal> {
+    let text = match cell {
+        Value::Number(number) => number.to_string(),
+        Value::String(text) => text.trim().to_string(),
+        _ => return None,
+    };
+    // `BigDecimal.toString()` switches to exponent notation, e.g. `1E+3`.
+    Decimal::from_str_exact(&text)
+        .or_else(|_| Decimal::from_scientific(&text))
+        .ok()
+}
+
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%b %d, %Y"];
+
+const TIME_FORMATS: &[&str] = &["%H:%M:%S%.f", "%I:%M:%S %p"];
+
+const TIMESTAMP_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    // java.text.DateFormat MEDIUM in Locale.US; JDK 9+ adds the comma.
+    "%b %d, %Y %I:%M:%S %p",
+    "%b %d, %Y, %I:%M:%S %p",
+];
+
+fn timestamp(cell: &Value) -> Option<NaiveDateTime> {
+    match cell {
+        Value::Number(number) => {
+            DateTime::from_timestamp_millis(number.as_i64()?).map(|time| time.naive_utc())
+        }
+        Value::String(text) => {
+            let text = normalize_spaces(text);
+            TIMESTAMP_FORMATS
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(&text, format).ok())
+                .or_else(|| {
+                    DateTime::parse_from_rfc3339(&text)
+                        .ok()
+                        .map(|time| time.naive_utc())
+                })
+        }
+        _ => None,
+    }
+}
+
+fn date(cell: &Value) -> Option<NaiveDate> {
+    if let Value::String(text) = cell {
+        let text = normalize_spaces(text);
+        if let Some(date) = DATE_FORMATS
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(&text, format).ok())
+        {
+            return Some(date);
+        }
+    }
+    timestamp(cell).map(|time| time.date())
+}
+
+fn time(cell: &Value) -> Option<NaiveTime> {
+    if let Value::String(text) = cell {
+        let text = normalize_spaces(text);
+        if let Some(time) = TIME_FORMATS
+            .iter()
+            .find_map(|format| NaiveTime::parse_from_str(&text, format).ok())
+        {
+            return Some(time);
+        }
+    }
+    timestamp(cell).map(|time| time.time())
+}
+
+/// Newer JDKs put a narrow no-break space before AM/PM.
+fn normalize_spaces(text: &str) -> String {
+    text.trim().replace(['\u{202f}', '\u{a0}'], " ")
+}
+
+fn bytes(cell: &Value) -> Option<Vec<u8>> {
+    match cell {
+        Value::String(text) => BASE64.decode(text).ok(),
+        // Java bytes are signed, so -1 is 0xFF.
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match item.as_i64()? {
+                byte @ -128..=-1 => Some(byte as u8),
+                byte => u8::try_from(byte).ok(),
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+/// Days since 1970-01-01, as Arrow and Parquet store dates.
+pub(crate) fn epoch_days(date: NaiveDate) -> i32 {
+    date.signed_duration_since(NaiveDate::default()).num_days() as i32
+}
+
+/// Microseconds since midnight, as Arrow and Parquet store times.
+pub(crate) fn micros_since_midnight(time: NaiveTime) -> i64 {
+    i64::from(time.num_seconds_from_midnight()) * 1_000_000 + i64::from(time.nanosecond() / 1_000)
+}
@@ -0,0 +1,222 @@
+use arrow::array::{
+    Array, AsArray, BinaryArray, FixedSizeBinaryArray, StringArray, TimestampMicrosecondArray,
+};
+use arrow::datatypes::{
+    DataType, Date32Type, Float64Type, Int8Type, Int32Type, Int64Type, Time64MicrosecondType,
+    TimeUnit,
+};
+use futures::TryStreamExt;
+use ignite_with_rest_api::IgniteRestError;
+use ignite_with_rest_api::mock::{MockIgnite, MockReply};
+use serde_json::json;
+
+const ID_FIELDS: &[(&str, &str)] = &[("ID", "java.lang.Integer")];
+
+#[tokio::test]
+async fn pages_become_record_batches_typed_by_field_metadata() {
+    let server = MockIgnite::start().await;
+    server.on("qryfldexe").reply(MockReply::rows(
+        &[
+            ("ID", "java.lang.Long"),
+            ("LEVEL", "java.lang.Byte"),
+            ("SCORE", "java.lang.Double"),
+            ("NAME", "java.lang.String"),
+            ("PRICE", "java.math.BigDecimal"),
+            ("PAID", "java.lang.Boolean"),
+            ("BORN", "java.sql.Date"),
+            ("AT", "java.sql.Time"),
+            ("PLACED", "java.sql.Timestamp"),
+            ("REF", "java.util.UUID"),
+            ("DATA", "[B"),
+            ("ADDRESS", "com.example.Address"),
+        ],
+        vec![
+            vec![
+                json!(7),
+                json!(-3),
+                json!(0.5),
+                json!("John Doe"),
+                // Parsed from text so that `exact-decimal` keeps the
+                // trailing zero.
+                serde_json::from_str("123.450").unwrap(),
+                json!(true),
+                json!("1970-01-11"),
+                json!("00:00:01"),
+                json!("2024-01-02T15:04:05"),
+                json!("7f1b8c5e-2b1a-4a43-9a3b-1a2b3c4d5e6f"),
+                json!("AQL/"),
+                json!({ "city": "Oslo" }),
+            ],
+            std::iter::once(json!(8))
+                .chain(std::iter::repeat_n(json!(null), 11))
+                .collect(),
+        ],
+    ));
+    let client = server.client_builder().build().unwrap();
+
+    let batch = client
+        .execute_sql("SELECT * FROM Orders", &[])
+        .await
+        .unwrap()
+        .to_record_batch()
+        .unwrap();
+
+    let schema = batch.schema();
+    let types: Vec<_> = schema
+        .fields()
+        .iter()
+        .map(|field| field.data_type().clone())
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            DataType::Int64,
+            DataType::Int8,
+            DataType::Float64,
+            DataType::Utf8,
+            DataType::Utf8,
+            DataType::Boolean,
+            DataType::Date32,
+            DataType::Time64(TimeUnit::Microsecond),
+            DataType::Timestamp(TimeUnit::Microsecond, None),
+            DataType::FixedSizeBinary(16),
+            DataType::Binary,
+            DataType::Utf8,
+        ]
+    );
+    assert!(schema.fields().iter().all(|field| field.is_nullable()));
+    assert_eq!(
+        schema.field(9).metadata()["ARROW:extension:name"],
+        "arrow.uuid"
+    );
+    assert_eq!(
+        schema.field(11).metadata()["ARROW:extension:name"],
+        "arrow.json"
+    );
+
+    assert_eq!(batch.num_rows(), 2);
+    assert_eq!(
+        batch.column(0).as_primitive::<Int64Type>().values(),
+        &[7, 8]
+    );
+    assert_eq!(batch.column(1).as_primitive::<Int8Type>().value(0), -3);
+    assert_eq!(batch.column(2).as_primitive::<Float64Type>().value(0), 0.5);
+    let text = |index: usize| {
+        batch
+            .column(index)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap()
+    };
+    assert_eq!(text(3).value(0), "John Doe");
+    let price = if cfg!(feature = "exact-decimal") {
+        "123.450"
+    } else {
+        "123.45"
+    };
+    assert_eq!(text(4).value(0), price);
+    assert_eq!(text(11).value(0), r#"{"city":"Oslo"}"#);
+    assert!(batch.column(5).as_boolean().value(0));
+    assert_eq!(batch.column(6).as_primitive::<Date32Type>().value(0), 10);
+    assert_eq!(
+        batch
+            .column(7)
+            .as_primitive::<Time64MicrosecondType>()
+            .value(0),
+        1_000_000
+    );
+    let placed = chrono::NaiveDate::from_ymd_opt(2024, 1, 2)
+        .unwrap()
+        .and_hms_opt(15, 4, 5)
+        .unwrap();
+    let timestamps = batch
+        .column(8)
+        .as_any()
+        .downcast_ref::<TimestampMicrosecondArray>()
+        .unwrap();
+    assert_eq!(timestamps.value(0), placed.and_utc().timestamp_micros());
+    let uuids = batch
+        .column(9)
+        .as_any()
+        .downcast_ref::<FixedSizeBinaryArray>()
+        .unwrap();
+    assert_eq!(
+        uuids.value(0),
+        uuid::Uuid::from_u128(0x7f1b8c5e_2b1a_4a43_9a3b_1a2b3c4d5e6f).as_bytes()
+    );
+    let data = batch
+        .column(10)
+        .as_any()
+        .downcast_ref::<BinaryArray>()
+        .unwrap();
+    assert_eq!(data.value(0), [1, 2, 255]);
+
+    for column in &batch.columns()[1..] {
+        assert_eq!(column.null_count(), 1, "{column:?}");
+        assert!(column.is_null(1));
+    }
+}
+
+#[tokio::test]
+async fn cursors_stream_batches_of_the_requested_size() {
+    let server = MockIgnite::start().await;
+    server
+        .on("qryfldexe")
+        .reply(MockReply::success(MockReply::page(
+            ID_FIELDS,
+            vec![vec![json!(1)], vec![json!(null)], vec![json!(3)]],
+            Some(7),
+        )));
+    server
+        .on("qryfetch")
+        .reply(MockReply::success(MockReply::page(
+            &[],
+            vec![vec![json!(4)], vec![json!(5)]],
+            None,
+        )));
+    let client = server.client_builder().build().unwrap();
+
+    let batches: Vec<_> = client
+        .query("SELECT id FROM Person", &[])
+        .await
+        .unwrap()
+        .into_record_batches(2)
+        .try_collect()
+        .await
+        .unwrap();
+
+    let sizes: Vec<_> = batches.iter().map(|batch| batch.num_rows()).collect();
+    assert_eq!(sizes, [2, 2, 1]);
+    let ids: Vec<Option<i32>> = batches
+        .iter()
+        .flat_map(|batch| batch.column(0).as_primitive::<Int32Type>().iter())
+        .collect();
+    assert_eq!(ids, [Some(1), None, Some(3), Some(4), Some(5)]);
+    assert!(
+        batches
+            .iter()
+            .all(|batch| batch.schema() == batches[0].schema())
+    );
+}
+
+#[tokio::test]
+async fn cells_that_do_not_match_their_type_fail_the_batch() {
+    let server = MockIgnite::start().await;
+    server.on("qryfldexe").reply(MockReply::rows(
+        ID_FIELDS,
+        vec![vec![json!(1)], vec![json!("one")]],
+    ));
+    let client = server.client_builder().build().unwrap();
+
+    let err = client
+        .execute_sql("SELECT id FROM Person", &[])
+        .await
+        .unwrap()
+        .to_record_batch()
+        .unwrap_err();
+
+    assert!(
+        matches!(&err, IgniteRestError::RowDecode(err) if err.row == 1),
+        "{err:?}"
+    );
+}
@@ -0,0 +1,118 @@
+use chrono::{NaiveDate, NaiveTime};
+use ignite_with_rest_api::mock::{MockIgnite, MockReply, RecordedRequest};
+use ignite_with_rest_api::{IgniteRestError, ToSqlArg};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+/// The `qryfldexe` request sent for `sql` bound to `args`.
+async fn bound(sql: &str, args: &[&dyn ToSqlArg]) -> RecordedRequest {
+    let server = MockIgnite::start().await;
+    server.on("qryfldexe").reply(MockReply::rows(&[], vec![]));
+    let client = server.client_builder().build().unwrap();
+
+    client.execute_sql(sql, args).await.unwrap();
+
+    server.requests_for("qryfldexe").remove(0)
+}
+
+#[tokio::test]
+async fn placeholders_in_literals_and_comments_are_not_bound() {
+    let request = bound(
+        "SELECT '?', 'it''s ?', \"a?\" FROM t -- id = ?\n\
+         WHERE /* ? */ id = ? AND name = ?",
+        &[&1, &"x"],
+    )
+    .await;
+
+    assert_eq!(
+        request.param("qry"),
+        Some(
+            "SELECT '?', 'it''s ?', \"a?\" FROM t -- id = ?\n\
+             WHERE /* ? */ id = CAST(? AS BIGINT) AND name = ?"
+        )
+    );
+    assert_eq!(request.param("arg1"), Some("1"));
+    assert_eq!(request.param("arg2"), Some("x"));
+    assert_eq!(request.param("arg3"), None);
+}
+
+#[tokio::test]
+async fn typed_arguments_are_cast_and_null_is_inlined() {
+    let uuid = Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+    let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+    let time = NaiveTime::from_hms_milli_opt(13, 5, 9, 250).unwrap();
+    let request = bound(
+        "VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        &[
+            &true,
+            &1.5,
+            &Decimal::new(1234, 2),
+            &date,
+            &time,
+            &date.and_time(time),
+            &uuid,
+            &vec![0xCA_u8, 0xFE],
+            &None::<i32>,
+            &"text",
+        ],
+    )
+    .await;
+
+    assert_eq!(
+        request.param("qry"),
+        Some(
+            "VALUES (CAST(? AS BOOLEAN), CAST(? AS DOUBLE), CAST(? AS DECIMAL), \
+             CAST(? AS DATE), CAST(? AS TIME), CAST(? AS TIMESTAMP), CAST(? AS UUID), \
+             CAST(? AS BINARY), NULL, ?)"
+        )
+    );
+    let args: Vec<_> = (1..=10)
+        .map_while(|n| request.param(&format!("arg{n}")))
+        .collect();
+    assert_eq!(
+        args,
+        [
+            "true",
+            "1.5",
+            "12.34",
+            "2024-02-29",
+            "13:05:09.250",
+            "2024-02-29 13:05:09.250",
+            "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "CAFE",
+            "text",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn argument_count_must_match_the_placeholders() {
+    let server = MockIgnite::start().await;
+    let client = server.client_builder().build().unwrap();
+
+    let cases: [(&str, &[&dyn ToSqlArg], &str); 3] = [
+        (
+            "SELECT * FROM t WHERE id = ?",
+            &[&1, &2],
+            "query has 1 placeholder(s) but 2 argument(s) were given",
+        ),
+        (
+            "SELECT * FROM t WHERE id = ? AND name = ?",
+            &[&1],
+            "query has 2 placeholder(s) but 1 argument(s) were given",
+        ),
+        (
+            "SELECT '?' FROM t",
+            &[&1],
+            "query has 0 placeholder(s) but 1 argument(s) were given",
+        ),
+    ];
+    for (sql, args, message) in cases {
+        match client.execute_sql(sql, args).await {
+            Err(IgniteRestError::Encode(err)) => assert_eq!(err, message),
+            other => panic!("{sql}: {other:?}"),
+        }
+    }
+
+    assert!(server.requests().is_empty());
+}
@@ -0,0 +1,119 @@
+use ignite_with_rest_api::mock::{MockIgnite, MockReply};
+use ignite_with_rest_api::{Credentials, IgniteRestError, STATUS_AUTH_FAILED};
+
+#[tokio::test]
+async fn put_and_get_send_typed_parameters() {
+    let server = MockIgnite::start().await;
+    server.on("put").reply(MockReply::success(true));
+    server
+        .on("get")
+        .param("key", "1")
+        .reply(MockReply::success("John Doe"));
+    server.on("get").reply(MockReply::success(()));
+    let client = server.client_builder().build().unwrap();
+    let cache = client
+        .cache("PersonCache")
+        .key_type("int")
+        .value_type("string");
+
+    assert!(cache.put(&1, &"John Doe").await.unwrap());
+    assert_eq!(
+        cache.get::<_, String>(&1).await.unwrap().as_deref(),
+        Some("John Doe")
+    );
+    assert_eq!(cache.get::<_, String>(&2).await.unwrap(), None);
+
+    let put = &server.requests_for("put")[0];
+    assert_eq!(put.param("cacheName"), Some("PersonCache"));
+    assert_eq!(put.param("keyType"), Some("int"));
+    assert_eq!(put.param("valueType"), Some("string"));
+    assert_eq!(put.param("key"), Some("1"));
+    assert_eq!(put.param("val"), Some("John Doe"));
+}
+
+#[tokio::test]
+async fn put_all_numbers_its_entries() {
+    let server = MockIgnite::start().await;
+    server.on("putall").reply(MockReply::success(true));
+    let client = server.client_builder().build().unwrap();
+
+    client
+        .cache("PersonCache")
+        .put_all(&[(1, "a"), (2, "b")])
+        .await
+        .unwrap();
+
+    let request = &server.requests_for("putall")[0];
+    assert_eq!(request.param("k1"), Some("1"));
+    assert_eq!(request.param("v1"), Some("a"));
+    assert_eq!(request.param("k2"), Some("2"));
+    assert_eq!(request.param("v2"), Some("b"));
+}
+
+#[tokio::test]
+async fn non_sql_failures_name_the_command() {
+    let server = MockIgnite::start().await;
+    server.on("rmv").reply(MockReply::error(
+        "Failed to find cache for given cache name",
+    ));
+    let client = server.client_builder().build().unwrap();
+
+    let err = client.cache("Missing").remove(&1).await.unwrap_err();
+    assert!(
+        matches!(&err, IgniteRestError::CommandFailed { command, .. } if command == "rmv"),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn expired_sessions_log_in_again() {
+    let server = MockIgnite::start().await;
+    server
+        .on("authenticate")
+        .replies([MockReply::session("first"), MockReply::session("second")]);
+    server
+        .on("get")
+        .param("sessionToken", "first")
+        .reply(MockReply::failure(
+            STATUS_AUTH_FAILED,
+            "Failed to authenticate remote client",
+        ));
+    server
+        .on("get")
+        .param("sessionToken", "second")
+        .reply(MockReply::success(42));
+    let client = server
+        .client_builder()
+        .credentials(Credentials::new("ignite", "secret"))
+        .build()
+        .unwrap();
+
+    let value: Option<i32> = client.cache("PersonCache").get(&1).await.unwrap();
+
+    assert_eq!(value, Some(42));
+    let logins = server.requests_for("authenticate");
+    assert_eq!(logins.len(), 2);
+    assert_eq!(logins[0].param("ignite.login"), Some("ignite"));
+    assert_eq!(logins[0].param("ignite.password"), Some("secret"));
+    assert_eq!(server.requests_for("get").len(), 2);
+}
+
+#[tokio::test]
+async fn logins_are_always_posted() {
+    let server = MockIgnite::start().await;
+    server.on("authenticate").reply(MockReply::session("token"));
+    server.on("get").reply(MockReply::success(42));
+    let client = server
+        .client_builder()
+        .credentials(Credentials::new("ignite", "secret"))
+        .post_threshold(usize::MAX)
+        .build()
+        .unwrap();
+
+    let _: Option<i32> = client.cache("PersonCache").get(&1).await.unwrap();
+
+    let login = &server.requests_for("authenticate")[0];
+    assert_eq!(login.method, "POST");
+    assert_eq!(login.param("ignite.password"), Some("secret"));
+    assert_eq!(server.requests_for("get")[0].method, "GET");
+}
@@ -0,0 +1,82 @@
+// Not every test binary uses every helper.
+#![allow(dead_code)]
+
+use std::io::{BufRead, BufReader};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+use ignite_with_rest_api::IgniteRestClient;
+
+/// Runs `ignite-emulator` on a free port and kills it when dropped.
+pub struct Emulator {
+    child: Child,
+    pub url: String,
+}
+
+impl Emulator {
+    pub fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_ignite-emulator"))
+            .args(["--listen", "127.0.0.1:0"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("start ignite-emulator");
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let url = line
+            .rsplit(' ')
+            .next()
+            .expect("listening line")
+            .trim()
+            .to_string();
+        Emulator { child, url }
+    }
+
+    pub fn client(&self) -> IgniteRestClient {
+        IgniteRestClient::builder()
+            .base_url(&self.url)
+            .cache_name("PersonCache")
+            .page_size(2)
+            .build()
+            .unwrap()
+    }
+}
+
+impl Drop for Emulator {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Path in the system temp directory, unique to this test process and
+/// `name`. The file is removed when the guard is dropped.
+pub struct ScratchFile(PathBuf);
+
+pub fn scratch(name: &str) -> ScratchFile {
+    let path = std::env::temp_dir().join(format!("ignite-rest-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    ScratchFile(path)
+}
+
+impl Deref for ScratchFile {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for ScratchFile {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for ScratchFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
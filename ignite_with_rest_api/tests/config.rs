@@ -0,0 +1,76 @@
+mod common;
+
+use std::process::Command;
+
+use ignite_config::{ConfigArgs, ConfigLoader};
+use ignite_with_rest_api::IgniteRestClientBuilder;
+use ignite_with_rest_api::mock::{MockIgnite, MockReply};
+use serde_json::json;
+
+use common::Emulator;
+
+#[tokio::test]
+async fn clients_are_built_from_loaded_settings() {
+    let server = MockIgnite::start().await;
+    server.on("authenticate").reply(MockReply::session("token"));
+    server.on("qryfldexe").reply(MockReply::rows(
+        &[("X", "java.lang.Integer")],
+        vec![vec![json!(1)]],
+    ));
+    let url = server.url();
+    let config = ConfigLoader::new()
+        .env([
+            ("IGNITE_REST_URL", url.as_str()),
+            ("IGNITE_LOGIN", "ignite"),
+            ("IGNITE_PASSWORD", "secret"),
+        ])
+        .args(ConfigArgs {
+            rest_cache: Some("OrderCache".into()),
+            page_size: Some(3),
+            ..ConfigArgs::default()
+        })
+        .load()
+        .unwrap();
+
+    let client = IgniteRestClientBuilder::from_config(&config)
+        .build()
+        .unwrap();
+    client.execute_sql("SELECT 1", &[]).await.unwrap();
+
+    assert_eq!(client.cache_name(), Some("OrderCache"));
+    let query = &server.requests_for("qryfldexe")[0];
+    assert_eq!(query.param("cacheName"), Some("OrderCache"));
+    assert_eq!(query.param("pageSize"), Some("3"));
+    assert_eq!(query.param("sessionToken"), Some("token"));
+    let login = &server.requests_for("authenticate")[0];
+    assert_eq!(login.param("ignite.login"), Some("ignite"));
+    assert_eq!(login.param("ignite.password"), Some("secret"));
+}
+
+#[test]
+fn tools_fail_over_between_configured_endpoints() {
+    let emulator = Emulator::start();
+    let dead = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}/ignite", listener.local_addr().unwrap())
+    };
+    let migrate = |command: &str| {
+        Command::new(env!("CARGO_BIN_EXE_ignite-migrate"))
+            .env("IGNITE_REST_URL", format!("{dead}, {}", emulator.url))
+            .env_remove("IGNITE_CONFIG")
+            .env_remove("IGNITE_LOGIN")
+            .env_remove("IGNITE_PASSWORD")
+            .args(["--dir", concat!(env!("CARGO_MANIFEST_DIR"), "/migrations")])
+            .arg(command)
+            .output()
+            .unwrap()
+    };
+
+    let output = migrate("migrate");
+    assert!(output.status.success(), "{output:?}");
+    let output = migrate("status");
+    assert!(output.status.success(), "{output:?}");
+    let status = String::from_utf8(output.stdout).unwrap();
+    assert!(status.starts_with("V001  create person"), "{status}");
+    assert!(status.contains("applied"), "{status}");
+}
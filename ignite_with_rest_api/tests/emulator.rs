@@ -0,0 +1,324 @@
+mod common;
+
+use ignite_with_rest_api::{
+    CacheOptions, ClusterState, IgniteRestClient, IgniteValue, Migration, Migrator, SqlErrorKind,
+    ToSqlArg,
+};
+use serde::Deserialize;
+
+use common::Emulator;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Person {
+    id: i32,
+    name: String,
+    age: i32,
+}
+
+#[tokio::test]
+async fn crud_scenario_runs_end_to_end() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+
+    assert_eq!(client.topology_size().await.unwrap(), 1);
+    client.activate().await.unwrap();
+    assert_eq!(client.cluster_state().await.unwrap(), ClusterState::Active);
+
+    let migrator = Migrator::new(
+        client.clone(),
+        vec![Migration::new(
+            1,
+            "create person",
+            "CREATE TABLE Person (id INT PRIMARY KEY, name VARCHAR(50), age INT) \
+             WITH \"template=replicated\";",
+        )],
+    )
+    .unwrap();
+    assert_eq!(migrator.migrate().await.unwrap(), vec![1]);
+    assert!(migrator.migrate().await.unwrap().is_empty());
+
+    let inserted = client
+        .execute_sql(
+            "INSERT INTO Person (id, name, age) VALUES (?, ?, ?), (?, ?, ?), (?, ?, ?)",
+            &[
+                &1,
+                &"John Doe",
+                &30,
+                &2,
+                &"Will Smith",
+                &10,
+                &3,
+                &"Jane Roe",
+                &42,
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(inserted.items.unwrap(), vec![vec![serde_json::json!(3)]]);
+
+    let duplicate = client
+        .execute_sql(
+            "INSERT INTO Person (id, name, age) VALUES (?, ?, ?)",
+            &[&1, &"Again", &1],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(duplicate.sql_kind(), Some(SqlErrorKind::DuplicateKey));
+
+    // Three rows with a page size of two: the second page comes from qryfetch.
+    let people: Vec<Person> = client
+        .query_as("SELECT id, name, age FROM Person ORDER BY id", &[])
+        .await
+        .unwrap();
+    assert_eq!(people.len(), 3);
+    assert_eq!(
+        people[1],
+        Person {
+            id: 2,
+            name: "Will Smith".into(),
+            age: 10
+        }
+    );
+
+    let older = client
+        .execute_sql("SELECT name FROM Person WHERE age > ? ORDER BY id", &[&25])
+        .await
+        .unwrap();
+    let fields = older.fields_metadata.clone().unwrap();
+    assert_eq!(fields[0].field_name, "NAME");
+    assert_eq!(fields[0].field_type_name, "java.lang.String");
+    assert_eq!(
+        older.values().unwrap(),
+        vec![
+            vec![IgniteValue::String("John Doe".into())],
+            vec![IgniteValue::String("Jane Roe".into())],
+        ]
+    );
+
+    client
+        .execute_sql("UPDATE Person SET age = ? WHERE id = ?", &[&31, &2])
+        .await
+        .unwrap();
+    client
+        .execute_sql("DELETE FROM Person WHERE age = ?", &[&30])
+        .await
+        .unwrap();
+    let count = client
+        .execute_sql("SELECT COUNT(*) FROM Person", &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        count.fields_metadata.unwrap()[0].field_type_name,
+        "java.lang.Long"
+    );
+    assert_eq!(count.items.unwrap(), vec![vec![serde_json::json!(2)]]);
+
+    let missing = client
+        .execute_sql("SELECT * FROM Nope", &[])
+        .await
+        .unwrap_err();
+    assert_eq!(missing.sql_kind(), Some(SqlErrorKind::TableNotFound));
+}
+
+#[tokio::test]
+async fn column_types_are_reported_as_java_classes() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    client
+        .execute_sql(
+            "CREATE TABLE Orders (id BIGINT PRIMARY KEY, price DECIMAL(20, 3), paid BOOLEAN, \
+             placed TIMESTAMP, ref UUID, data VARBINARY)",
+            &[],
+        )
+        .await
+        .unwrap();
+    let placed = chrono::NaiveDate::from_ymd_opt(2024, 1, 2)
+        .unwrap()
+        .and_hms_opt(15, 4, 5)
+        .unwrap();
+    let reference = uuid::Uuid::from_u128(0x7f1b8c5e_2b1a_4a43_9a3b_1a2b3c4d5e6f);
+    client
+        .execute_sql(
+            "INSERT INTO Orders VALUES (?, ?, ?, ?, ?, ?)",
+            &[
+                &7_i64,
+                &rust_decimal::Decimal::new(12_345, 2),
+                &true,
+                &placed,
+                &reference,
+                &vec![1_u8, 2, 255],
+            ],
+        )
+        .await
+        .unwrap();
+
+    let result = client
+        .execute_sql("SELECT * FROM Orders", &[])
+        .await
+        .unwrap();
+    let types: Vec<_> = result
+        .fields_metadata
+        .iter()
+        .flatten()
+        .map(|field| field.field_type_name.as_str())
+        .collect();
+    assert_eq!(
+        types,
+        [
+            "java.lang.Long",
+            "java.math.BigDecimal",
+            "java.lang.Boolean",
+            "java.sql.Timestamp",
+            "java.util.UUID",
+            "[B"
+        ]
+    );
+    assert_eq!(
+        result.values().unwrap(),
+        vec![vec![
+            IgniteValue::Long(7),
+            IgniteValue::Decimal(rust_decimal::Decimal::new(12_345, 2)),
+            IgniteValue::Boolean(true),
+            IgniteValue::Timestamp(placed),
+            IgniteValue::Uuid(reference),
+            IgniteValue::Bytes(vec![1, 2, 255]),
+        ]]
+    );
+}
+
+#[tokio::test]
+async fn key_value_commands_use_the_cache() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    let cache = client
+        .get_or_create_cache("PersonCache", &CacheOptions::new())
+        .await
+        .unwrap()
+        .key_type("int")
+        .value_type("int");
+
+    assert!(cache.put(&1, &10).await.unwrap());
+    assert!(!cache.put_if_absent(&1, &11).await.unwrap());
+    assert_eq!(cache.get::<_, i32>(&1).await.unwrap(), Some(10));
+    assert!(cache.cas(&1, &12, &10).await.unwrap());
+    assert!(cache.put_all(&[(2, 20), (3, 30)]).await.unwrap());
+    assert_eq!(cache.size(&[]).await.unwrap(), 3);
+    assert!(cache.contains_keys(&[1, 2, 3]).await.unwrap());
+    let all = cache.get_all::<i32, i32>(&[1, 2, 4]).await.unwrap();
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[&1], 12);
+    assert!(cache.remove(&2).await.unwrap());
+    assert!(!cache.contains_key(&2).await.unwrap());
+
+    let err = client.cache("Unknown").get::<_, i32>(&1).await.unwrap_err();
+    assert!(err.to_string().contains("Failed to find cache"), "{err}");
+}
+
+#[tokio::test]
+async fn conditional_writes_are_atomic() {
+    let emulator = Emulator::start();
+    let cache = emulator
+        .client()
+        .get_or_create_cache("PersonCache", &CacheOptions::new())
+        .await
+        .unwrap()
+        .key_type("int")
+        .value_type("int");
+    assert!(cache.put(&1, &10).await.unwrap());
+
+    let writers: Vec<_> = (0..16)
+        .map(|value| {
+            let cache = cache.clone();
+            tokio::spawn(async move {
+                let absent = cache.put_if_absent(&2, &value).await.unwrap();
+                let swapped = cache.cas(&1, &value, &10).await.unwrap();
+                (absent, swapped)
+            })
+        })
+        .collect();
+    let mut absent = 0;
+    let mut swapped = 0;
+    for writer in writers {
+        let (stored, replaced) = writer.await.unwrap();
+        absent += usize::from(stored);
+        swapped += usize::from(replaced);
+    }
+
+    assert_eq!((absent, swapped), (1, 1));
+    assert!(!cache.replace(&3, &30).await.unwrap());
+    assert!(!cache.contains_key(&3).await.unwrap());
+    assert_eq!(cache.get_and_put::<_, i32>(&3, &31).await.unwrap(), None);
+    assert_eq!(cache.get_and_put(&3, &32).await.unwrap(), Some(31));
+}
+
+/// Creates `table` and fills it with `rows` people in one `INSERT`.
+async fn insert_people(client: &IgniteRestClient, table: &str, rows: i32) {
+    let ids: Vec<i32> = (1..=rows).collect();
+    let names: Vec<String> = ids.iter().map(|id| format!("Person #{id}")).collect();
+    let ages: Vec<i32> = ids.iter().map(|id| id % 90).collect();
+    let args: Vec<&dyn ToSqlArg> = ids
+        .iter()
+        .zip(&names)
+        .zip(&ages)
+        .flat_map(|((id, name), age)| [id as &dyn ToSqlArg, name, age])
+        .collect();
+    let values = vec!["(?, ?, ?)"; ids.len()].join(", ");
+
+    client
+        .execute_sql(
+            &format!("CREATE TABLE {table} (id INT PRIMARY KEY, name VARCHAR, age INT)"),
+            &[],
+        )
+        .await
+        .unwrap();
+    let inserted = client
+        .execute_sql(
+            &format!("INSERT INTO {table} (id, name, age) VALUES {values}"),
+            &args,
+        )
+        .await
+        .unwrap();
+    assert_eq!(inserted.items.unwrap(), vec![vec![serde_json::json!(rows)]]);
+}
+
+#[tokio::test]
+async fn large_inserts_are_posted_with_the_same_results() {
+    let emulator = Emulator::start();
+
+    let mut people = Vec::new();
+    for (table, post_threshold) in [("ByGet", usize::MAX), ("ByPost", 0)] {
+        let client = IgniteRestClient::builder()
+            .base_url(&emulator.url)
+            .post_threshold(post_threshold)
+            .page_size(200)
+            .build()
+            .unwrap();
+        insert_people(&client, table, 500).await;
+        let selected: Vec<Person> = client
+            .query_as(
+                &format!("SELECT id, name, age FROM {table} ORDER BY id"),
+                &[],
+            )
+            .await
+            .unwrap();
+        people.push(selected);
+    }
+    assert_eq!(people[0].len(), 500);
+    assert_eq!(people[0], people[1]);
+
+    // Far beyond what fits in a URL; the default threshold posts it.
+    let client = emulator.client();
+    insert_people(&client, "Many", 5000).await;
+    let count = client
+        .execute_sql("SELECT COUNT(*), SUM(age) FROM Many", &[])
+        .await
+        .unwrap();
+    let expected_ages: i64 = (1..=5000).map(|id| i64::from(id % 90)).sum();
+    assert_eq!(
+        count.items.unwrap(),
+        vec![vec![
+            serde_json::json!(5000),
+            serde_json::json!(expected_ages)
+        ]]
+    );
+}
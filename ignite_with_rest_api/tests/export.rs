@@ -0,0 +1,256 @@
+mod common;
+
+use ignite_with_rest_api::mock::{MockIgnite, MockReply};
+use ignite_with_rest_api::{ExportError, ExportFormat, Exporter, IgniteRestClient, ToSqlArg};
+use parquet::basic::{LogicalType, Type as PhysicalType};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::{Field, Row};
+use serde_json::json;
+
+use common::{Emulator, scratch};
+
+async fn create_people(client: &IgniteRestClient) {
+    client
+        .execute_sql(
+            "CREATE TABLE Person (id INT PRIMARY KEY, name VARCHAR(50), age INT)",
+            &[],
+        )
+        .await
+        .unwrap();
+    client
+        .execute_sql(
+            "INSERT INTO Person (id, name, age) VALUES \
+             (1, 'John Doe', 30), (2, 'Roe, Jane', NULL), (3, 'Will', 10), \
+             (4, 'Ann', 52), (5, 'Bo', 7)",
+            &[],
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn every_page_is_exported_as_csv_and_json_lines() {
+    let emulator = Emulator::start();
+    // Five rows with a page size of two.
+    let client = emulator.client();
+    create_people(&client).await;
+    let path = scratch("people.csv");
+
+    // Spawned to check that the export can run on any task.
+    let export = Exporter::table(client.clone(), "Person")
+        .unwrap()
+        .export_file(path.to_path_buf());
+    let written = tokio::spawn(export).await.unwrap().unwrap();
+
+    assert_eq!(written, 5);
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "ID,NAME,AGE\r\n1,John Doe,30\r\n2,\"Roe, Jane\",\r\n3,Will,10\r\n4,Ann,52\r\n5,Bo,7\r\n"
+    );
+
+    let mut out = Vec::new();
+    let written = Exporter::query(
+        client,
+        "SELECT id, age FROM Person WHERE id > ? ORDER BY id",
+        &[&2 as &dyn ToSqlArg],
+    )
+    .format(ExportFormat::JsonLines)
+    .export_writer(&mut out)
+    .await
+    .unwrap();
+
+    assert_eq!(written, 3);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "{\"ID\":3,\"AGE\":10}\n{\"ID\":4,\"AGE\":52}\n{\"ID\":5,\"AGE\":7}\n"
+    );
+}
+
+#[tokio::test]
+async fn parquet_columns_are_typed_from_field_types() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    client
+        .execute_sql(
+            "CREATE TABLE Orders (id BIGINT PRIMARY KEY, price DECIMAL(20, 3), paid BOOLEAN, \
+             placed TIMESTAMP, ref UUID, note VARCHAR)",
+            &[],
+        )
+        .await
+        .unwrap();
+    let placed = chrono::NaiveDate::from_ymd_opt(2024, 1, 2)
+        .unwrap()
+        .and_hms_opt(15, 4, 5)
+        .unwrap();
+    let reference = uuid::Uuid::from_u128(0x7f1b8c5e_2b1a_4a43_9a3b_1a2b3c4d5e6f);
+    for id in 1..=3_i64 {
+        client
+            .execute_sql(
+                "INSERT INTO Orders VALUES (?, ?, ?, ?, ?, ?)",
+                &[
+                    &id,
+                    &rust_decimal::Decimal::new(12_345 * id, 2),
+                    &(id % 2 == 1),
+                    &placed,
+                    &reference,
+                    &(id != 2).then_some("rush"),
+                ],
+            )
+            .await
+            .unwrap();
+    }
+    let path = scratch("orders.parquet");
+
+    let written = Exporter::query(client, "SELECT * FROM Orders ORDER BY id", &[])
+        .format(ExportFormat::from_path(&path).unwrap())
+        .row_group_size(2)
+        .export_file(&path)
+        .await
+        .unwrap();
+
+    assert_eq!(written, 3);
+    let reader = SerializedFileReader::try_from(&*path).unwrap();
+    assert_eq!(reader.metadata().num_row_groups(), 2);
+    let schema = reader.metadata().file_metadata().schema_descr();
+    let columns: Vec<_> = schema
+        .columns()
+        .iter()
+        .map(|column| {
+            (
+                column.name().to_string(),
+                column.physical_type(),
+                column.logical_type_ref().cloned(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        columns,
+        vec![
+            ("ID".to_string(), PhysicalType::INT64, None),
+            (
+                "PRICE".to_string(),
+                PhysicalType::BYTE_ARRAY,
+                Some(LogicalType::String)
+            ),
+            ("PAID".to_string(), PhysicalType::BOOLEAN, None),
+            (
+                "PLACED".to_string(),
+                PhysicalType::INT64,
+                Some(LogicalType::timestamp(
+                    false,
+                    parquet::basic::TimeUnit::MICROS
+                ))
+            ),
+            (
+                "REF".to_string(),
+                PhysicalType::FIXED_LEN_BYTE_ARRAY,
+                Some(LogicalType::Uuid)
+            ),
+            (
+                "NOTE".to_string(),
+                PhysicalType::BYTE_ARRAY,
+                Some(LogicalType::String)
+            ),
+        ]
+    );
+
+    let rows: Vec<Row> = reader
+        .get_row_iter(None)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    let cell = |row: usize, name: &str| {
+        rows[row]
+            .get_column_iter()
+            .find(|(column, _)| column.as_str() == name)
+            .map(|(_, field)| field.clone())
+            .unwrap()
+    };
+    assert_eq!(rows.len(), 3);
+    assert_eq!(cell(1, "ID"), Field::Long(2));
+    assert_eq!(cell(1, "PRICE"), Field::Str("246.9".into()));
+    assert_eq!(cell(1, "PAID"), Field::Bool(false));
+    assert_eq!(
+        cell(1, "PLACED"),
+        Field::TimestampMicros(placed.and_utc().timestamp_micros())
+    );
+    assert_eq!(cell(1, "NOTE"), Field::Null);
+    assert_eq!(cell(2, "NOTE"), Field::Str("rush".into()));
+}
+
+#[tokio::test]
+async fn failed_exports_leave_no_file_behind() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    let path = scratch("missing.csv");
+
+    let err = Exporter::table(client.clone(), "Missing")
+        .unwrap()
+        .export_file(&path)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, ExportError::Rest(_)), "{err:?}");
+    assert!(!path.exists());
+    assert!(!path.with_extension("csv.partial").exists());
+    assert!(matches!(
+        Exporter::table(client, "Person; DROP TABLE Person"),
+        Err(ExportError::InvalidTable(_))
+    ));
+}
+
+#[tokio::test]
+async fn parquet_rejects_rows_wider_than_the_schema() {
+    let server = MockIgnite::start().await;
+    server.on("qryfldexe").reply(MockReply::rows(
+        &[("ID", "java.lang.Integer")],
+        vec![vec![json!(1)], vec![json!(2), json!("extra")]],
+    ));
+    let client = server.client_builder().build().unwrap();
+
+    let err = Exporter::table(client, "Person")
+        .unwrap()
+        .format(ExportFormat::Parquet)
+        .export_writer(Vec::new())
+        .await
+        .unwrap_err();
+
+    match err {
+        ExportError::Write(err) => {
+            assert!(err.to_string().contains("2 cells but 1 columns"), "{err}")
+        }
+        other => panic!("{other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn cache_entries_are_exported_from_a_scan() {
+    let server = MockIgnite::start().await;
+    server.on("qryscanexe").reply(MockReply::success(json!({
+        "items": [
+            { "key": 1, "value": { "name": "John Doe" } },
+            { "key": 2, "value": "plain" },
+        ],
+        "last": true,
+        "queryId": null,
+        "fieldsMetadata": null,
+    })));
+    let client = server.client_builder().build().unwrap();
+    let mut out = Vec::new();
+
+    let written = Exporter::cache(client.cache("PersonCache"))
+        .format(ExportFormat::JsonLines)
+        .export_writer(&mut out)
+        .await
+        .unwrap();
+
+    assert_eq!(written, 2);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "{\"KEY\":1,\"VALUE\":{\"name\":\"John Doe\"}}\n{\"KEY\":2,\"VALUE\":\"plain\"}\n"
+    );
+    assert_eq!(
+        server.requests_for("qryscanexe")[0].param("cacheName"),
+        Some("PersonCache")
+    );
+}
@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+use ignite_with_rest_api::mock::{MockIgnite, MockReply};
+use ignite_with_rest_api::{EndpointSelection, IgniteRestClient, IgniteRestError, RetryPolicy};
+use serde::Deserialize;
+use serde_json::json;
+
+const ID_FIELDS: &[(&str, &str)] = &[("ID", "java.lang.Integer")];
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Id {
+    id: i32,
+}
+
+/// URL of a port nothing listens on, so connecting is refused.
+async fn dead_url() -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    format!("http://{addr}/ignite")
+}
+
+fn fast_retries() -> RetryPolicy {
+    RetryPolicy::new()
+        .max_retries(2)
+        .initial_backoff(Duration::from_millis(1))
+}
+
+fn client(endpoints: &[String], selection: EndpointSelection) -> IgniteRestClient {
+    IgniteRestClient::builder()
+        .endpoints(endpoints)
+        .endpoint_selection(selection)
+        .retry(fast_retries())
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn refused_connections_fail_over_even_for_dml() {
+    let server = MockIgnite::start().await;
+    server.on("qryfldexe").reply(MockReply::rows(
+        &[("UPDATED", "java.lang.Long")],
+        vec![vec![json!(1)]],
+    ));
+    let client = client(
+        &[dead_url().await, server.url()],
+        EndpointSelection::Failover,
+    );
+
+    client
+        .execute_sql("INSERT INTO Person (id) VALUES (?)", &[&1])
+        .await
+        .unwrap();
+
+    assert_eq!(server.requests_for("qryfldexe").len(), 1);
+}
+
+#[tokio::test]
+async fn selects_are_retried_after_server_errors() {
+    let server = MockIgnite::start().await;
+    server.on("qryfldexe").replies([
+        MockReply::http_status(503),
+        MockReply::rows(ID_FIELDS, vec![vec![json!(1)]]),
+    ]);
+    let client = client(&[server.url()], EndpointSelection::Failover);
+
+    let result = client
+        .execute_sql("SELECT id FROM Person", &[])
+        .await
+        .unwrap();
+
+    assert_eq!(result.items.unwrap(), vec![vec![json!(1)]]);
+    assert_eq!(server.requests_for("qryfldexe").len(), 2);
+}
+
+#[tokio::test]
+async fn dml_is_not_retried_once_sent() {
+    let server = MockIgnite::start().await;
+    server.on("qryfldexe").replies([
+        MockReply::http_status(503),
+        MockReply::rows(&[("UPDATED", "java.lang.Long")], vec![vec![json!(1)]]),
+    ]);
+    let client = client(&[server.url()], EndpointSelection::Failover);
+
+    let err = client
+        .execute_sql("UPDATE Person SET age = ? WHERE id = ?", &[&31, &2])
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(&err, IgniteRestError::Transport(err) if err.status().map(|s| s.as_u16()) == Some(503)),
+        "{err:?}"
+    );
+    assert_eq!(server.requests_for("qryfldexe").len(), 1);
+}
+
+#[tokio::test]
+async fn failing_endpoints_are_ejected() {
+    let broken = MockIgnite::start().await;
+    broken.on("version").reply(MockReply::http_status(502));
+    let healthy = MockIgnite::start().await;
+    healthy.on("version").reply(MockReply::success("2.16.0"));
+    let client = client(&[broken.url(), healthy.url()], EndpointSelection::Failover);
+
+    for _ in 0..3 {
+        assert_eq!(client.version().await.unwrap(), "2.16.0");
+    }
+
+    assert_eq!(broken.requests_for("version").len(), 1);
+    assert_eq!(healthy.requests_for("version").len(), 3);
+}
+
+#[tokio::test]
+async fn starting_nodes_probe_false_and_stay_healthy() {
+    let starting = MockIgnite::start().await;
+    starting.on("probe").reply(MockReply::http_status(503));
+    starting.on("version").reply(MockReply::success("2.16.0"));
+    let other = MockIgnite::start().await;
+    let client = IgniteRestClient::builder()
+        .endpoints([starting.url(), other.url()])
+        .retry(fast_retries())
+        .eject_after(1, Duration::from_secs(60))
+        .build()
+        .unwrap();
+
+    assert!(!client.probe().await.unwrap());
+    assert_eq!(client.version().await.unwrap(), "2.16.0");
+
+    assert_eq!(starting.requests_for("probe").len(), 1);
+    assert_eq!(starting.requests_for("version").len(), 1);
+    assert!(other.requests().is_empty());
+}
+
+#[tokio::test]
+async fn pages_are_fetched_from_the_node_that_opened_the_query() {
+    let nodes = [MockIgnite::start().await, MockIgnite::start().await];
+    for node in &nodes {
+        node.on("qryfldexe")
+            .reply(MockReply::success(MockReply::page(
+                ID_FIELDS,
+                vec![vec![json!(1)]],
+                Some(7),
+            )));
+        node.on("qryfetch")
+            .reply(MockReply::success(MockReply::page(
+                &[],
+                vec![vec![json!(2)]],
+                None,
+            )));
+    }
+    let urls: Vec<_> = nodes.iter().map(MockIgnite::url).collect();
+    let client = client(&urls, EndpointSelection::RoundRobin);
+
+    for _ in 0..2 {
+        let ids: Vec<Id> = client.query_as("SELECT id FROM Person", &[]).await.unwrap();
+        assert_eq!(ids, vec![Id { id: 1 }, Id { id: 2 }]);
+    }
+
+    for node in &nodes {
+        assert_eq!(node.requests_for("qryfldexe").len(), 1);
+        assert_eq!(node.requests_for("qryfetch").len(), 1);
+    }
+}
+
+#[tokio::test]
+async fn the_request_timeout_is_configurable() {
+    let server = MockIgnite::start().await;
+    server
+        .on("qryfldexe")
+        .reply(MockReply::rows(ID_FIELDS, vec![]).delayed(Duration::from_secs(5)));
+    let client = server
+        .client_builder()
+        .timeout(Duration::from_millis(100))
+        .retry(RetryPolicy::none())
+        .build()
+        .unwrap();
+
+    let err = client
+        .execute_sql("SELECT id FROM Person", &[])
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(&err, IgniteRestError::Transport(err) if err.is_timeout()),
+        "{err:?}"
+    );
+}
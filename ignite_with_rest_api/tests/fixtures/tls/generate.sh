@@ -0,0 +1,23 @@
+#!/bin/sh
+# Regenerates the certificates used by tests/tls.rs: a CA, a server
+# certificate for localhost and a client certificate, as PEM and PKCS#12.
+set -e
+cd "$(dirname "$0")"
+
+days=36500
+openssl req -x509 -newkey rsa:2048 -nodes -days "$days" \
+    -keyout ca.key -out ca.pem -subj "/CN=ignite-rest test CA"
+
+openssl req -newkey rsa:2048 -nodes -keyout server.key -out server.csr -subj "/CN=localhost"
+printf "subjectAltName=DNS:localhost,IP:127.0.0.1\n" > server.ext
+openssl x509 -req -in server.csr -CA ca.pem -CAkey ca.key -CAcreateserial \
+    -days "$days" -extfile server.ext -out server.pem
+
+openssl req -newkey rsa:2048 -nodes -keyout client.key -out client.csr -subj "/CN=ignite-rest client"
+printf "extendedKeyUsage=clientAuth\n" > client.ext
+openssl x509 -req -in client.csr -CA ca.pem -CAkey ca.key -CAcreateserial \
+    -days "$days" -extfile client.ext -out client.pem
+openssl pkcs12 -export -in client.pem -inkey client.key -certfile ca.pem \
+    -passout pass:changeit -out client.p12
+
+rm -f ca.key ca.srl server.csr server.ext client.csr client.ext
@@ -0,0 +1,111 @@
+use ignite_with_rest_api::{FieldMetadata, OutputFormat, csv_field, render_table};
+use serde_json::{Value, json};
+
+fn fields(columns: &[(&str, &str)]) -> Vec<FieldMetadata> {
+    columns
+        .iter()
+        .map(|(name, java_type)| FieldMetadata {
+            field_name: name.to_string(),
+            field_type_name: java_type.to_string(),
+        })
+        .collect()
+}
+
+#[test]
+fn csv_quotes_fields_as_rfc_4180_requires() {
+    assert_eq!(csv_field("plain"), "plain");
+    assert_eq!(csv_field(""), "");
+    assert_eq!(csv_field("a,b"), "\"a,b\"");
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    assert_eq!(csv_field("cr\rlf"), "\"cr\rlf\"");
+
+    let csv = OutputFormat::Csv.render(
+        &fields(&[("ID", "java.lang.Integer"), ("A,B", "java.lang.String")]),
+        &[
+            vec![json!(1), json!("a,b")],
+            vec![json!(2), json!("say \"hi\"")],
+            vec![json!(3), json!("two\nlines")],
+            vec![Value::Null, json!(true)],
+        ],
+    );
+    assert_eq!(
+        csv,
+        "ID,\"A,B\"\r\n\
+         1,\"a,b\"\r\n\
+         2,\"say \"\"hi\"\"\"\r\n\
+         3,\"two\nlines\"\r\n\
+         ,true\r\n"
+    );
+}
+
+#[test]
+fn json_lines_keep_column_order_and_nulls() {
+    let jsonl = OutputFormat::JsonLines.render(
+        &fields(&[
+            ("ZED", "java.lang.Integer"),
+            ("ALPHA", "java.lang.String"),
+            ("MID", "java.lang.Object"),
+        ]),
+        &[
+            vec![json!(1), Value::Null, json!({"b": 2, "a": 1})],
+            vec![json!(2), json!("x\"y\nz"), json!([1, null])],
+        ],
+    );
+
+    assert_eq!(
+        jsonl,
+        "{\"ZED\":1,\"ALPHA\":null,\"MID\":{\"a\":1,\"b\":2}}\n\
+         {\"ZED\":2,\"ALPHA\":\"x\\\"y\\nz\",\"MID\":[1,null]}\n"
+    );
+    for line in jsonl.lines() {
+        serde_json::from_str::<Value>(line).unwrap();
+    }
+}
+
+#[test]
+fn markdown_escapes_pipes_and_line_breaks() {
+    let markdown = OutputFormat::Markdown.render(
+        &fields(&[("A|B", "java.lang.String"), ("C", "java.lang.String")]),
+        &[
+            vec![json!("x|y"), json!("l1\r\nl2\nl3\rl4")],
+            vec![Value::Null, json!(5)],
+        ],
+    );
+
+    assert_eq!(
+        markdown,
+        "| A\\|B | C |\n\
+         | --- | --- |\n\
+         | x\\|y | l1<br>l2<br>l3<br>l4 |\n\
+         | NULL | 5 |\n"
+    );
+}
+
+#[test]
+fn table_aligns_columns_and_keeps_rows_on_one_line() {
+    let table = render_table(
+        &fields(&[("ID", "java.lang.Integer"), ("NAME", "java.lang.String")]),
+        &[
+            vec![json!(1), json!("Ann")],
+            vec![json!(22), json!("multi\nline")],
+            vec![Value::Null, json!("a\tb\r")],
+        ],
+    );
+
+    assert_eq!(
+        table,
+        "+---------+-------------+\n\
+         | ID      | NAME        |\n\
+         | Integer | String      |\n\
+         +---------+-------------+\n\
+         | 1       | Ann         |\n\
+         | 22      | multi\\nline |\n\
+         | NULL    | a\\tb\\r      |\n\
+         +---------+-------------+\n"
+    );
+    assert_eq!(
+        OutputFormat::Table.render(&fields(&[("ID", "java.lang.Integer")]), &[vec![json!(1)]]),
+        render_table(&fields(&[("ID", "java.lang.Integer")]), &[vec![json!(1)]])
+    );
+}
@@ -0,0 +1,261 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use ignite_with_rest_api::mock::{MockIgnite, MockReply};
+use ignite_with_rest_api::{
+    CacheOptions, CsvImporter, IgniteRestClient, ImportError, ImportMode, ImportProgress,
+    SqlErrorKind,
+};
+use serde::Deserialize;
+
+use common::{Emulator, scratch};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Person {
+    id: i32,
+    name: Option<String>,
+    age: Option<i32>,
+    active: Option<bool>,
+}
+
+async fn create_person(client: &IgniteRestClient) {
+    client
+        .execute_sql(
+            "CREATE TABLE Person (id INT PRIMARY KEY, name VARCHAR(50), age INT, active BOOLEAN)",
+            &[],
+        )
+        .await
+        .unwrap();
+}
+
+async fn people(client: &IgniteRestClient) -> Vec<Person> {
+    client
+        .query_as("SELECT id, name, age, active FROM Person ORDER BY id", &[])
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn fields_are_converted_and_bad_rows_rejected() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    create_person(&client).await;
+    let rejects = scratch("rejects.csv");
+    let csv = "\
+Id,Full Name,AGE,active
+1,John Doe,30,yes
+2,Will Smith,ten,0
+3,Jane Roe,,true
+1,Again,40,no
+4,,51,
+";
+
+    let progress = CsvImporter::into_table(client.clone(), "Person")
+        .unwrap()
+        .map_column("Full Name", "name")
+        .batch_size(2)
+        .rejects_file(rejects.to_path_buf())
+        .import_reader(csv.as_bytes())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        progress,
+        ImportProgress {
+            rows_written: 3,
+            rows_rejected: 2,
+            resume_line: 7,
+        }
+    );
+    let person = |id, name: Option<&str>, age, active| Person {
+        id,
+        name: name.map(Into::into),
+        age,
+        active,
+    };
+    assert_eq!(
+        people(&client).await,
+        vec![
+            person(1, Some("John Doe"), Some(30), Some(true)),
+            person(3, Some("Jane Roe"), None, Some(true)),
+            person(4, None, Some(51), None),
+        ]
+    );
+
+    let mut reader = csv::Reader::from_path(&rejects).unwrap();
+    assert_eq!(
+        reader.headers().unwrap(),
+        vec!["line", "error", "Id", "Full Name", "AGE", "active"]
+    );
+    let rejected: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rejected.len(), 2);
+    assert_eq!(&rejected[0][0], "3");
+    assert!(rejected[0][1].contains("`AGE`"), "{:?}", &rejected[0]);
+    assert_eq!(
+        rejected[0].iter().skip(2).collect::<Vec<_>>(),
+        ["2", "Will Smith", "ten", "0"]
+    );
+    assert_eq!(&rejected[1][0], "5");
+    assert!(
+        rejected[1][1].contains("Duplicate key"),
+        "{:?}",
+        &rejected[1]
+    );
+}
+
+#[tokio::test]
+async fn imports_resume_from_the_reported_line() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    create_person(&client).await;
+    client
+        .execute_sql(
+            "INSERT INTO Person (id, name) VALUES (?, ?)",
+            &[&3, &"Stale"],
+        )
+        .await
+        .unwrap();
+    let csv = "id,name\n1,One\n2,Two\n3,Three\n4,Four\n";
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&reports);
+
+    let progress = CsvImporter::into_table(client.clone(), "Person")
+        .unwrap()
+        .mode(ImportMode::Merge)
+        .batch_size(1)
+        .resume_from_line(3)
+        .on_progress(move |progress| seen.lock().unwrap().push(progress.resume_line))
+        .import_reader(csv.as_bytes())
+        .await
+        .unwrap();
+
+    assert_eq!(progress.rows_written, 3);
+    assert_eq!(*reports.lock().unwrap(), vec![4, 5, 6]);
+    let names: Vec<_> = people(&client)
+        .await
+        .into_iter()
+        .map(|person| person.name.unwrap())
+        .collect();
+    assert_eq!(names, ["Two", "Three", "Four"]);
+}
+
+#[tokio::test]
+async fn only_row_errors_reject_rows() {
+    let server = MockIgnite::start().await;
+    server
+        .on("qryfldexe")
+        .query("SELECT * FROM Person LIMIT 0")
+        .reply(MockReply::rows(
+            &[("ID", "java.lang.Integer"), ("NAME", "java.lang.String")],
+            vec![],
+        ));
+    server.on("qryfldexe").replies([
+        MockReply::error("Value conversion failed [column=NAME, from=String, to=Integer]"),
+        MockReply::rows(&[("UPDATED", "java.lang.Long")], vec![]),
+        MockReply::error("Value conversion failed [column=NAME, from=String, to=Integer]"),
+        MockReply::error("Failed to execute query: cluster is inactive"),
+    ]);
+    let client = server.client_builder().build().unwrap();
+    let rejects = scratch("row-errors.csv");
+    let csv = "id,name
+1,One
+2,Two
+3,Three
+4,Four
+";
+
+    let err = CsvImporter::into_table(client, "Person")
+        .unwrap()
+        .batch_size(2)
+        .rejects_file(rejects.to_path_buf())
+        .import_reader(csv.as_bytes())
+        .await
+        .unwrap_err();
+
+    match err {
+        ImportError::Interrupted {
+            resume_line,
+            source,
+        } => {
+            assert_eq!(resume_line, 4);
+            assert_eq!(source.sql_kind(), Some(SqlErrorKind::Other));
+        }
+        other => panic!("{other:?}"),
+    }
+    let rejected: Vec<csv::StringRecord> = csv::Reader::from_path(&rejects)
+        .unwrap()
+        .records()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(&rejected[0][0], "3");
+}
+
+#[tokio::test]
+async fn records_are_put_into_caches() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    client
+        .get_or_create_cache("Cities", &CacheOptions::new())
+        .await
+        .unwrap();
+    let cache = client.cache("Cities");
+    let csv = "code;name;population\nAMS;Amsterdam;921402\nBER;;3850809\nOSL;Oslo;717710\n";
+
+    let progress = CsvImporter::into_cache(cache.clone(), "CODE", "name")
+        .delimiter(b';')
+        .import_reader(csv.as_bytes())
+        .await
+        .unwrap();
+
+    assert_eq!((progress.rows_written, progress.rows_rejected), (2, 1));
+    assert_eq!(
+        cache.get::<_, String>(&"OSL").await.unwrap().as_deref(),
+        Some("Oslo")
+    );
+    assert_eq!(cache.get::<_, String>(&"BER").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn unknown_headers_are_reported_before_writing() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    create_person(&client).await;
+    let csv = "id,name,shoe_size\n1,John Doe,44\n";
+    let importer = || CsvImporter::into_table(client.clone(), "Person").unwrap();
+
+    let err = importer().import_reader(csv.as_bytes()).await.unwrap_err();
+    assert!(
+        matches!(&err, ImportError::UnmappedHeader(header) if header == "shoe_size"),
+        "{err:?}"
+    );
+    let err = importer()
+        .map_column("name", "full_name")
+        .import_reader(csv.as_bytes())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ImportError::UnknownColumn { .. }), "{err:?}");
+    assert!(people(&client).await.is_empty());
+
+    let progress = importer()
+        .skip_unmapped(true)
+        .import_reader(csv.as_bytes())
+        .await
+        .unwrap();
+    assert_eq!(progress.rows_written, 1);
+    assert_eq!(people(&client).await[0].name.as_deref(), Some("John Doe"));
+
+    let err = importer()
+        .skip_unmapped(true)
+        .import_reader(
+            "shoe_size,hat_size
+44,7
+"
+            .as_bytes(),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ImportError::NoMappedColumns), "{err:?}");
+    assert_eq!(people(&client).await.len(), 1);
+}
@@ -0,0 +1,224 @@
+mod common;
+
+use std::path::Path;
+
+use ignite_with_rest_api::{
+    DEFAULT_HISTORY_TABLE, IgniteRestClient, IgniteRestError, Migration, MigrationError,
+    MigrationState, Migrator, SqlErrorKind,
+};
+
+use common::Emulator;
+
+fn create(version: u32, table: &str) -> Migration {
+    Migration::new(
+        version,
+        format!("create {table}"),
+        format!("CREATE TABLE {table} (id INT PRIMARY KEY);"),
+    )
+}
+
+fn migrator_for(client: &IgniteRestClient, migrations: Vec<Migration>) -> Migrator {
+    Migrator::new(client.clone(), migrations).unwrap()
+}
+
+/// Version and state of every status entry, without the timestamps.
+async fn states(migrator: &Migrator) -> Vec<(u32, &'static str)> {
+    migrator
+        .status()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|status| {
+            let state = match status.state {
+                MigrationState::Applied { .. } => "applied",
+                MigrationState::Pending => "pending",
+                MigrationState::Modified { .. } => "modified",
+                MigrationState::BelowBaseline => "below baseline",
+                MigrationState::Baseline { .. } => "baseline",
+                MigrationState::Missing { .. } => "missing",
+            };
+            (status.version, state)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn status_does_not_create_the_history_table() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    let migrator = migrator_for(&client, vec![create(1, "A"), create(2, "B")]);
+
+    assert_eq!(states(&migrator).await, [(1, "pending"), (2, "pending")]);
+    let err = client
+        .execute_sql(&format!("SELECT * FROM {DEFAULT_HISTORY_TABLE}"), &[])
+        .await
+        .unwrap_err();
+    assert_eq!(err.sql_kind(), Some(SqlErrorKind::TableNotFound));
+
+    assert_eq!(migrator.migrate().await.unwrap(), [1, 2]);
+    assert_eq!(states(&migrator).await, [(1, "applied"), (2, "applied")]);
+}
+
+#[tokio::test]
+async fn edited_migrations_stop_every_pending_one() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    let original = create(1, "A");
+    migrator_for(&client, vec![original.clone()])
+        .migrate()
+        .await
+        .unwrap();
+
+    let mut edited = original.clone();
+    edited.script = "CREATE TABLE A (id INT PRIMARY KEY, name VARCHAR);".into();
+    let migrator = migrator_for(&client, vec![edited.clone(), create(2, "B")]);
+    match migrator.migrate().await {
+        Err(MigrationError::ChecksumMismatch {
+            version,
+            recorded,
+            actual,
+        }) => {
+            assert_eq!(version, 1);
+            assert_eq!(recorded, original.checksum());
+            assert_eq!(actual, edited.checksum());
+        }
+        other => panic!("{other:?}"),
+    }
+    assert_eq!(states(&migrator).await, [(1, "modified"), (2, "pending")]);
+
+    // Line endings alone do not count as an edit.
+    let mut crlf = original.clone();
+    crlf.script = crlf.script.replace('\n', "\r\n");
+    let migrator = migrator_for(&client, vec![crlf, create(2, "B")]);
+    assert_eq!(migrator.migrate().await.unwrap(), [2]);
+}
+
+#[tokio::test]
+async fn migrations_below_the_latest_applied_are_rejected() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    migrator_for(&client, vec![create(1, "A"), create(3, "C")])
+        .migrate()
+        .await
+        .unwrap();
+
+    let migrator = migrator_for(
+        &client,
+        vec![
+            create(1, "A"),
+            create(2, "B"),
+            create(3, "C"),
+            create(4, "D"),
+        ],
+    );
+    match migrator.migrate().await {
+        Err(MigrationError::OutOfOrder { version, latest }) => {
+            assert_eq!((version, latest), (2, 3));
+        }
+        other => panic!("{other:?}"),
+    }
+    assert_eq!(
+        states(&migrator).await,
+        [
+            (1, "applied"),
+            (2, "pending"),
+            (3, "applied"),
+            (4, "pending")
+        ]
+    );
+}
+
+#[tokio::test]
+async fn baseline_skips_older_migrations() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    let migrator = migrator_for(
+        &client,
+        vec![create(1, "A"), create(2, "B"), create(3, "C")],
+    );
+
+    migrator.baseline(2).await.unwrap();
+    assert_eq!(
+        states(&migrator).await,
+        [(1, "below baseline"), (2, "baseline"), (3, "pending")]
+    );
+    assert!(matches!(
+        migrator.baseline(3).await,
+        Err(MigrationError::AlreadyBaselined)
+    ));
+
+    assert_eq!(migrator.migrate().await.unwrap(), [3]);
+    let statuses = migrator.status().await.unwrap();
+    assert_eq!(statuses[1].description, "create B");
+    assert_eq!(
+        states(&migrator).await,
+        [(1, "below baseline"), (2, "baseline"), (3, "applied")]
+    );
+
+    // History entries without a file are still listed.
+    let trimmed = migrator_for(&client, vec![create(1, "A")]);
+    assert_eq!(
+        states(&trimmed).await,
+        [(1, "below baseline"), (2, "baseline"), (3, "missing")]
+    );
+    let statuses = trimmed.status().await.unwrap();
+    assert_eq!(statuses[1].description, "<< Baseline >>");
+    assert_eq!(statuses[2].description, "create C");
+}
+
+#[tokio::test]
+async fn failed_statements_are_reported_with_their_position() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    let migrator = migrator_for(
+        &client,
+        vec![
+            create(1, "A"),
+            Migration::new(
+                2,
+                "broken",
+                "CREATE TABLE B (id INT PRIMARY KEY);\nINSERT INTO missing VALUES (1);",
+            ),
+        ],
+    );
+
+    match migrator.migrate().await {
+        Err(MigrationError::Failed {
+            version,
+            statement,
+            source,
+        }) => {
+            assert_eq!((version, statement), (2, 2));
+            assert!(matches!(source, IgniteRestError::Sql(_)), "{source:?}");
+        }
+        other => panic!("{other:?}"),
+    }
+    // V1 and the first statement of V2 stay applied; V2 is not recorded.
+    assert_eq!(states(&migrator).await, [(1, "applied"), (2, "pending")]);
+    client.execute_sql("SELECT id FROM B", &[]).await.unwrap();
+}
+
+#[test]
+fn invalid_definitions_are_rejected_up_front() {
+    let client = IgniteRestClient::builder().build().unwrap();
+
+    assert!(matches!(
+        Migrator::new(client.clone(), vec![create(1, "A"), create(1, "B")]),
+        Err(MigrationError::DuplicateVersion(1))
+    ));
+    let migrator = Migrator::new(client.clone(), vec![]).unwrap();
+    assert!(matches!(
+        migrator.history_table("history; DROP TABLE Person"),
+        Err(MigrationError::InvalidHistoryTable(_))
+    ));
+
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    match Migration::load_dir(fixtures.join("invalid_migrations")) {
+        Err(MigrationError::InvalidFileName(name)) => assert_eq!(name, "V1_create_person.sql"),
+        other => panic!("{other:?}"),
+    }
+    assert!(matches!(
+        Migration::load_dir(fixtures.join("absent")),
+        Err(MigrationError::Io { .. })
+    ));
+}
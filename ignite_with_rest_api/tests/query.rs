@@ -0,0 +1,209 @@
+mod common;
+
+use ignite_with_rest_api::{
+    Delete, Ident, Insert, Select, SqlArg, TableRef, Update, col, count_all,
+};
+use serde::Deserialize;
+
+use common::Emulator;
+
+#[test]
+fn identifiers_follow_the_upper_case_default() {
+    assert_eq!(Ident::new("person").as_sql(), "person");
+    assert_eq!(Ident::new("PUBLIC.Person").as_sql(), "PUBLIC.Person");
+    // Keywords are quoted in the case Ignite stores them in.
+    assert_eq!(Ident::new("order").as_sql(), "\"ORDER\"");
+    assert_eq!(Ident::new("p.key").as_sql(), "p.\"KEY\"");
+    // Other names can only have been created quoted.
+    assert_eq!(Ident::new("first name").as_sql(), "\"first name\"");
+    assert_eq!(Ident::quoted("camelCase").as_sql(), "\"camelCase\"");
+    assert_eq!(Ident::quoted("say \"hi\"").as_sql(), "\"say \"\"hi\"\"\"");
+}
+
+#[test]
+fn select_clauses_render_in_order_with_their_arguments() {
+    let query = Select::from(TableRef::new("Person").alias("p"))
+        .distinct()
+        .column(col("c.name").alias("city"))
+        .column(count_all().alias("people"))
+        .column(col("p.age").max())
+        .left_join(
+            TableRef::new("City").alias("c"),
+            col("c.id").eq(col("p.city_id")),
+        )
+        .filter(col("p.age").between(18, 65))
+        .filter(col("p.name").like("J%").or(col("p.name").is_null()))
+        .filter(!col("c.id").in_list([1, 2]))
+        .group_by(col("c.name"))
+        .having(count_all().gt(1))
+        .order_by(col("people").desc())
+        .order_by(col("city"))
+        .limit(10)
+        .offset(20)
+        .build();
+
+    assert_eq!(
+        query.sql(),
+        "SELECT DISTINCT c.name AS city, COUNT(*) AS people, MAX(p.age) \
+         FROM Person p LEFT JOIN City c ON c.id = p.city_id \
+         WHERE p.age BETWEEN ? AND ? AND (p.name LIKE ? OR p.name IS NULL) \
+         AND NOT (c.id IN (?, ?)) \
+         GROUP BY c.name HAVING COUNT(*) > ? ORDER BY people DESC, city ASC \
+         LIMIT 10 OFFSET 20"
+    );
+    assert_eq!(
+        query.args(),
+        [
+            SqlArg::Int(18),
+            SqlArg::Int(65),
+            SqlArg::String("J%".into()),
+            SqlArg::Int(1),
+            SqlArg::Int(2),
+            SqlArg::Int(1),
+        ]
+    );
+    assert_eq!(
+        Select::from("Person").offset(5).build().sql(),
+        "SELECT * FROM Person OFFSET 5 ROWS"
+    );
+    assert_eq!(
+        Select::from("Person")
+            .filter(col("id").in_list(Vec::<i32>::new()))
+            .build()
+            .sql(),
+        "SELECT * FROM Person WHERE FALSE"
+    );
+}
+
+#[test]
+fn writes_render_with_bound_values() {
+    let insert = Insert::into("Person")
+        .columns(["id", "name", "key"])
+        .values(&[&1, &"John", &None::<i32>])
+        .values(&[&2, &"Will", &Some(7)])
+        .build();
+    assert_eq!(
+        insert.sql(),
+        "INSERT INTO Person (id, name, \"KEY\") VALUES (?, ?, ?), (?, ?, ?)"
+    );
+    assert_eq!(insert.args()[2], SqlArg::Null);
+    assert_eq!(insert.args()[5], SqlArg::Int(7));
+    assert_eq!(
+        Insert::merge_into("Person")
+            .columns(["id"])
+            .values(&[&3])
+            .build()
+            .sql(),
+        "MERGE INTO Person (id) VALUES (?)"
+    );
+
+    let update = Update::table("Person")
+        .set("age", 31)
+        .set("name", col("nickname"))
+        .filter(col("id").eq(2))
+        .build();
+    assert_eq!(
+        update.sql(),
+        "UPDATE Person SET age = ?, name = nickname WHERE id = ?"
+    );
+    assert_eq!(update.args(), [SqlArg::Int(31), SqlArg::Int(2)]);
+
+    let delete = Delete::from("Person").filter(col("age").lt(18)).build();
+    assert_eq!(delete.sql(), "DELETE FROM Person WHERE age < ?");
+    assert_eq!(Delete::from("Person").build().sql(), "DELETE FROM Person");
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct CityCount {
+    city: String,
+    people: i64,
+}
+
+#[tokio::test]
+async fn statements_run_through_the_client() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    for ddl in [
+        "CREATE TABLE City (id INT PRIMARY KEY, name VARCHAR)",
+        "CREATE TABLE Person (id INT PRIMARY KEY, name VARCHAR, city_id INT, \"KEY\" VARCHAR)",
+    ] {
+        client.execute_sql(ddl, &[]).await.unwrap();
+    }
+    Insert::into("City")
+        .columns(["id", "name"])
+        .values(&[&1, &"Oslo"])
+        .values(&[&2, &"Bergen"])
+        .build()
+        .execute(&client)
+        .await
+        .unwrap();
+    for (id, name, city) in [(1, "Ann", 1), (2, "Bo", 1), (3, "Cy", 2), (4, "Di", 1)] {
+        Insert::into("Person")
+            .columns(["id", "name", "city_id", "key"])
+            .values(&[&id, &name, &city, &format!("k{id}")])
+            .build()
+            .execute(&client)
+            .await
+            .unwrap();
+    }
+    Insert::merge_into("Person")
+        .columns(["id", "name", "city_id", "key"])
+        .values(&[&3, &"Cy", &1, &"k3"])
+        .build()
+        .execute(&client)
+        .await
+        .unwrap();
+    Update::table("Person")
+        .set("city_id", 2)
+        .filter(col("key").eq("k4"))
+        .build()
+        .execute(&client)
+        .await
+        .unwrap();
+    Delete::from("Person")
+        .filter(col("name").eq("Bo"))
+        .build()
+        .execute(&client)
+        .await
+        .unwrap();
+
+    // Two pages of the emulator client.
+    let counts: Vec<CityCount> = Select::from(TableRef::new("Person").alias("p"))
+        .column(col("c.name").alias("city"))
+        .column(count_all().alias("people"))
+        .join(
+            TableRef::new("City").alias("c"),
+            col("c.id").eq(col("p.city_id")),
+        )
+        .filter(col("p.id").gt(0))
+        .group_by(col("c.name"))
+        .order_by(col("city"))
+        .build()
+        .query_as(&client)
+        .await
+        .unwrap();
+    assert_eq!(
+        counts,
+        [
+            CityCount {
+                city: "Bergen".into(),
+                people: 1
+            },
+            CityCount {
+                city: "Oslo".into(),
+                people: 2
+            },
+        ]
+    );
+
+    let names: Vec<(String,)> = Select::from("Person")
+        .columns(["name"])
+        .order_by(col("id").desc())
+        .limit(2)
+        .offset(1)
+        .build()
+        .query_as(&client)
+        .await
+        .unwrap();
+    assert_eq!(names, [("Cy".to_string(),), ("Ann".to_string(),)]);
+}
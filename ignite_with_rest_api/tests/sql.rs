@@ -0,0 +1,308 @@
+use std::time::Duration;
+
+use futures::StreamExt;
+use ignite_with_rest_api::mock::{MockIgnite, MockReply};
+use ignite_with_rest_api::{IgniteRestError, IgniteValue, SqlErrorKind, ToSqlArg};
+use serde::Deserialize;
+use serde_json::json;
+
+const PERSON_FIELDS: &[(&str, &str)] = &[
+    ("ID", "java.lang.Integer"),
+    ("NAME", "java.lang.String"),
+    ("AGE", "java.lang.Integer"),
+];
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Person {
+    id: i32,
+    name: String,
+    age: i32,
+}
+
+#[tokio::test]
+async fn execute_sql_sends_query_and_bound_args() {
+    let server = MockIgnite::start().await;
+    server.on("qryfldexe").reply(MockReply::rows(
+        PERSON_FIELDS,
+        vec![vec![json!(1), json!("John Doe"), json!(30)]],
+    ));
+    let client = server
+        .client_builder()
+        .cache_name("PersonCache")
+        .build()
+        .unwrap();
+
+    let result = client
+        .execute_sql(
+            "SELECT * FROM Person WHERE age > ? AND name = ?",
+            &[&25, &"John"],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.items.unwrap().len(), 1);
+    let requests = server.requests_for("qryfldexe");
+    assert_eq!(requests.len(), 1);
+    let request = &requests[0];
+    assert_eq!(
+        request.param("qry"),
+        Some("SELECT * FROM Person WHERE age > CAST(? AS BIGINT) AND name = ?")
+    );
+    assert_eq!(request.param("cacheName"), Some("PersonCache"));
+    assert_eq!(request.param("pageSize"), Some("10"));
+    assert_eq!(request.param("arg1"), Some("25"));
+    assert_eq!(request.param("arg2"), Some("John"));
+}
+
+#[tokio::test]
+async fn rules_match_on_query_text() {
+    let server = MockIgnite::start().await;
+    server
+        .on("qryfldexe")
+        .query("SELECT COUNT(*) FROM Person")
+        .reply(MockReply::rows(
+            &[("COUNT(*)", "java.lang.Long")],
+            vec![vec![json!(2)]],
+        ));
+    server.on("qryfldexe").reply(MockReply::error(
+        "Failed to parse query. Syntax error in SQL statement",
+    ));
+    let client = server.client_builder().build().unwrap();
+
+    let count = client
+        .execute_sql("SELECT COUNT(*)\n  FROM Person", &[])
+        .await
+        .unwrap();
+    assert_eq!(count.items.unwrap(), vec![vec![json!(2)]]);
+
+    let err = client.execute_sql("SELEC oops", &[]).await.unwrap_err();
+    assert_eq!(err.sql_kind(), Some(SqlErrorKind::SyntaxError));
+}
+
+#[tokio::test]
+async fn sql_failures_are_classified() {
+    let server = MockIgnite::start().await;
+    server.on("qryfldexe").reply(MockReply::error(
+        "Failed to parse query. Table \"MISSING\" not found; SQL statement: SELECT * FROM Missing",
+    ));
+    let client = server.client_builder().build().unwrap();
+
+    let err = client
+        .execute_sql("SELECT * FROM Missing", &[])
+        .await
+        .unwrap_err();
+    assert_eq!(err.sql_kind(), Some(SqlErrorKind::TableNotFound));
+}
+
+#[tokio::test]
+async fn malformed_json_is_a_decode_error() {
+    let server = MockIgnite::start().await;
+    server.on("qryfldexe").reply(MockReply::raw(
+        r#"{"successStatus":0,"response":{"items":["#,
+    ));
+    let client = server.client_builder().build().unwrap();
+
+    let err = client.execute_sql("SELECT 1", &[]).await.unwrap_err();
+    assert!(
+        matches!(&err, IgniteRestError::Decode { command, .. } if command == "qryfldexe"),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn http_errors_are_transport_errors() {
+    let server = MockIgnite::start().await;
+    server.on("qryfldexe").reply(MockReply::http_status(503));
+    let client = server.client_builder().build().unwrap();
+
+    let err = client.execute_sql("SELECT 1", &[]).await.unwrap_err();
+    match err {
+        IgniteRestError::Transport(err) => assert_eq!(err.status().map(|s| s.as_u16()), Some(503)),
+        other => panic!("expected a transport error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn slow_responses_hit_the_http_timeout() {
+    let server = MockIgnite::start().await;
+    server
+        .on("qryfldexe")
+        .reply(MockReply::rows(PERSON_FIELDS, vec![]).delayed(Duration::from_secs(5)));
+    let http = reqwest::Client::builder()
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    let client = server.client_builder().http_client(http).build().unwrap();
+
+    let err = client
+        .execute_sql("SELECT * FROM Person", &[])
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, IgniteRestError::Transport(err) if err.is_timeout()),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn query_as_fetches_every_page() {
+    let server = MockIgnite::start().await;
+    server
+        .on("qryfldexe")
+        .reply(MockReply::success(MockReply::page(
+            PERSON_FIELDS,
+            vec![vec![json!(1), json!("John Doe"), json!(30)]],
+            Some(7),
+        )));
+    server.on("qryfetch").param("qryId", "7").replies([
+        MockReply::success(MockReply::page(
+            &[],
+            vec![vec![json!(2), json!("Will Smith"), json!(10)]],
+            Some(7),
+        )),
+        MockReply::success(MockReply::page(
+            &[],
+            vec![vec![json!(3), json!("Jane Roe"), json!(42)]],
+            None,
+        )),
+    ]);
+    let client = server.client_builder().page_size(1).build().unwrap();
+
+    let people: Vec<Person> = client.query_as("SELECT * FROM Person", &[]).await.unwrap();
+
+    assert_eq!(
+        people.iter().map(|person| person.id).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(people[2].name, "Jane Roe");
+    assert_eq!(server.requests_for("qryfetch").len(), 2);
+    assert!(server.requests_for("qrycls").is_empty());
+}
+
+#[tokio::test]
+async fn closing_a_cursor_early_sends_qrycls() {
+    let server = MockIgnite::start().await;
+    server
+        .on("qryfldexe")
+        .reply(MockReply::success(MockReply::page(
+            PERSON_FIELDS,
+            vec![vec![json!(1), json!("John Doe"), json!(30)]],
+            Some(42),
+        )));
+    server.on("qrycls").reply(MockReply::success(true));
+    let client = server.client_builder().build().unwrap();
+
+    let mut cursor = client.query("SELECT * FROM Person", &[]).await.unwrap();
+    assert!(cursor.next().await.is_some());
+    cursor.close().await.unwrap();
+
+    let closes = server.requests_for("qrycls");
+    assert_eq!(closes.len(), 1);
+    assert_eq!(closes[0].param("qryId"), Some("42"));
+}
+
+#[tokio::test]
+async fn values_follow_the_declared_java_types() {
+    let server = MockIgnite::start().await;
+    server.on("qryfldexe").reply(MockReply::raw(
+        r#"{"successStatus":0,"error":null,"sessionToken":null,"response":{
+            "fieldsMetadata":[
+                {"fieldName":"PRICE","fieldTypeName":"java.math.BigDecimal"},
+                {"fieldName":"TOTAL","fieldTypeName":"java.lang.Long"},
+                {"fieldName":"DATA","fieldTypeName":"[B"}],
+            "items":[[12345678901234567.891, 9007199254740993, "AQL/"]],
+            "last":true}}"#,
+    ));
+    let client = server.client_builder().build().unwrap();
+
+    let rows = client
+        .execute_sql("SELECT price, total, data FROM Orders", &[])
+        .await
+        .unwrap()
+        .values()
+        .unwrap();
+
+    // Without `exact-decimal`, serde_json has read the price as an `f64`.
+    let price = if cfg!(feature = "exact-decimal") {
+        "12345678901234567.891"
+    } else {
+        "12345678901234568"
+    };
+    assert_eq!(
+        rows,
+        vec![vec![
+            IgniteValue::Decimal(price.parse().unwrap()),
+            IgniteValue::Long(9_007_199_254_740_993),
+            IgniteValue::Bytes(vec![1, 2, 255]),
+        ]]
+    );
+}
+
+#[tokio::test]
+async fn decimal_text_keeps_every_digit_in_any_build() {
+    let server = MockIgnite::start().await;
+    server.on("qryfldexe").reply(MockReply::rows(
+        &[("PRICE", "java.math.BigDecimal")],
+        vec![
+            vec![json!("12345678901234567.891")],
+            vec![json!("1.2345678901234567891E+3")],
+        ],
+    ));
+    let client = server.client_builder().build().unwrap();
+
+    let rows = client
+        .execute_sql("SELECT price FROM Orders", &[])
+        .await
+        .unwrap()
+        .values()
+        .unwrap();
+
+    assert_eq!(
+        rows,
+        vec![
+            vec![IgniteValue::Decimal(
+                "12345678901234567.891".parse().unwrap()
+            )],
+            vec![IgniteValue::Decimal(
+                "1234.5678901234567891".parse().unwrap()
+            )],
+        ]
+    );
+}
+
+#[tokio::test]
+async fn long_commands_are_posted_as_forms() {
+    let server = MockIgnite::start().await;
+    server.on("qryfldexe").reply(MockReply::rows(
+        &[("UPDATED", "java.lang.Long")],
+        vec![vec![json!(200)]],
+    ));
+    let ids: Vec<i32> = (1..=200).collect();
+    let names: Vec<String> = ids.iter().map(|id| format!("Person #{id}")).collect();
+    let args: Vec<&dyn ToSqlArg> = ids
+        .iter()
+        .zip(&names)
+        .flat_map(|(id, name)| [id as &dyn ToSqlArg, name])
+        .collect();
+    let sql = format!(
+        "INSERT INTO Person (id, name) VALUES {}",
+        vec!["(?, ?)"; ids.len()].join(", ")
+    );
+
+    let mut results = Vec::new();
+    for post_threshold in [usize::MAX, 0] {
+        let client = server
+            .client_builder()
+            .post_threshold(post_threshold)
+            .build()
+            .unwrap();
+        results.push(client.execute_sql(&sql, &args).await.unwrap());
+    }
+
+    assert_eq!(results[0].items, results[1].items);
+    let requests = server.requests_for("qryfldexe");
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(requests[1].method, "POST");
+    assert_eq!(requests[0].params, requests[1].params);
+    assert_eq!(requests[1].param("arg400"), Some("Person #200"));
+}
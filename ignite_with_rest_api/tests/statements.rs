@@ -0,0 +1,50 @@
+use ignite_with_rest_api::{is_terminated, split_statements};
+
+#[test]
+fn only_a_semicolon_in_code_terminates() {
+    for sql in [
+        "SELECT 1;",
+        "SELECT 1;  \n\t",
+        "SELECT 1; -- done",
+        "SELECT 1; /* done */ ",
+        "SELECT ';';",
+        "SELECT \"a;b\" FROM t;",
+    ] {
+        assert!(is_terminated(sql), "{sql:?}");
+    }
+    for sql in [
+        "",
+        "  \n",
+        "SELECT 1",
+        "SELECT ';'",
+        "SELECT 'it''s;'",
+        "SELECT \"a;\"",
+        "SELECT 1 -- ;",
+        "SELECT 1 /* ; */",
+        "SELECT 1 /* ; ",
+        "SELECT 'open;",
+    ] {
+        assert!(!is_terminated(sql), "{sql:?}");
+    }
+}
+
+#[test]
+fn scripts_split_on_semicolons_in_code() {
+    let script = "INSERT INTO t VALUES ('a;b', 'it''s;');\n\
+                  -- one; two\n\
+                  SELECT \"x;y\" FROM t /* ; */ ;\n\
+                  \n  ;\n\
+                  UPDATE t SET a = 1  \n\
+                  -- trailing; comment\n  ";
+
+    assert_eq!(
+        split_statements(script),
+        [
+            "INSERT INTO t VALUES ('a;b', 'it''s;')",
+            "-- one; two\nSELECT \"x;y\" FROM t /* ; */",
+            "UPDATE t SET a = 1  \n-- trailing; comment",
+        ]
+    );
+    assert!(split_statements("  ; -- nothing;\n /* ; */ ;").is_empty());
+    assert_eq!(split_statements("SELECT 1"), ["SELECT 1"]);
+}
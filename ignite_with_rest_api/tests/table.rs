@@ -0,0 +1,220 @@
+mod common;
+
+use ignite_with_rest_api::{IgniteTable, SqlErrorKind};
+use serde::Deserialize;
+
+use common::Emulator;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, IgniteTable)]
+#[ignite(table = "Person", template = "partitioned", backups = 1)]
+struct Person {
+    #[ignite(primary_key)]
+    id: i32,
+    #[ignite(column = "full_name", length = 50, index)]
+    name: String,
+    age: Option<i32>,
+}
+
+#[derive(Debug, PartialEq, Deserialize, IgniteTable)]
+#[ignite(cache_name = "OrderLineCache", value_type = "com.example.OrderLine")]
+struct OrderLine {
+    #[ignite(primary_key, affinity_key)]
+    order_id: i64,
+    #[ignite(primary_key)]
+    line: i32,
+    #[ignite(index = "OrderLine_item_qty")]
+    item: String,
+    #[ignite(index = "OrderLine_item_qty")]
+    quantity: i16,
+    price: rust_decimal::Decimal,
+}
+
+/// Every column is named after a keyword.
+#[derive(Debug, Clone, PartialEq, Deserialize, IgniteTable)]
+#[ignite(table = "Setting")]
+struct Setting {
+    #[ignite(primary_key)]
+    key: String,
+    #[ignite(index)]
+    value: Option<String>,
+    #[ignite(column = "sort_order")]
+    order: i32,
+    #[ignite(column = "group")]
+    section: String,
+}
+
+fn person(id: i32, name: &str, age: Option<i32>) -> Person {
+    Person {
+        id,
+        name: name.to_string(),
+        age,
+    }
+}
+
+#[test]
+fn ddl_follows_the_attributes() {
+    assert_eq!(
+        Person::TABLE.create_table_sql(),
+        "CREATE TABLE IF NOT EXISTS Person (id INT, full_name VARCHAR(50) NOT NULL, age INT, \
+         PRIMARY KEY (id)) WITH \"TEMPLATE=partitioned,BACKUPS=1\""
+    );
+    assert_eq!(
+        Person::TABLE.create_index_sql(),
+        ["CREATE INDEX IF NOT EXISTS Person_full_name_idx ON Person (full_name)"]
+    );
+    assert_eq!(
+        OrderLine::TABLE.create_table_sql(),
+        "CREATE TABLE IF NOT EXISTS OrderLine (order_id BIGINT, line INT, \
+         item VARCHAR NOT NULL, quantity SMALLINT NOT NULL, price DECIMAL NOT NULL, \
+         PRIMARY KEY (order_id, line)) \
+         WITH \"CACHE_NAME=OrderLineCache,VALUE_TYPE=com.example.OrderLine,AFFINITY_KEY=order_id\""
+    );
+    assert_eq!(
+        OrderLine::TABLE.create_index_sql(),
+        ["CREATE INDEX IF NOT EXISTS OrderLine_item_qty ON OrderLine (item, quantity)"]
+    );
+}
+
+#[tokio::test]
+async fn rows_round_trip_through_the_generated_statements() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    Person::create_table(&client).await.unwrap();
+    // Creating it again is a no-op.
+    Person::create_table(&client).await.unwrap();
+
+    for person in [
+        person(1, "John Doe", Some(30)),
+        person(2, "Will Smith", None),
+        person(3, "Ann", Some(52)),
+    ] {
+        person.insert(&client).await.unwrap();
+    }
+    let err = person(1, "Again", None).insert(&client).await.unwrap_err();
+    assert_eq!(err.sql_kind(), Some(SqlErrorKind::DuplicateKey), "{err:?}");
+
+    // The renamed column is read back into `name`.
+    assert_eq!(
+        Person::select_by_pk(&client, &2).await.unwrap(),
+        Some(person(2, "Will Smith", None))
+    );
+    assert_eq!(Person::select_by_pk(&client, &9).await.unwrap(), None);
+
+    let mut will = person(2, "Will", Some(31));
+    assert!(will.update(&client).await.unwrap());
+    will.id = 9;
+    assert!(!will.update(&client).await.unwrap());
+
+    person(3, "Ann Lee", Some(53))
+        .upsert(&client)
+        .await
+        .unwrap();
+    person(4, "Bo", Some(7)).upsert(&client).await.unwrap();
+
+    assert!(Person::delete(&client, &1).await.unwrap());
+    assert!(!Person::delete(&client, &1).await.unwrap());
+
+    // Three rows span two pages of the emulator client.
+    let people = Person::find_where(&client, "id > ? ORDER BY id", &[&0])
+        .await
+        .unwrap();
+    assert_eq!(
+        people,
+        [
+            person(2, "Will", Some(31)),
+            person(3, "Ann Lee", Some(53)),
+            person(4, "Bo", Some(7)),
+        ]
+    );
+    let adults = Person::find_where(&client, "full_name LIKE ? AND age >= ?", &[&"%n%", &18])
+        .await
+        .unwrap();
+    assert_eq!(adults, [person(3, "Ann Lee", Some(53))]);
+}
+
+#[tokio::test]
+async fn composite_keys_are_bound_as_tuples() {
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    OrderLine::create_table(&client).await.unwrap();
+    let line = |line: i32, item: &str| OrderLine {
+        order_id: 7,
+        line,
+        item: item.to_string(),
+        quantity: 2,
+        price: rust_decimal::Decimal::new(1_250, 2),
+    };
+    line(1, "tea").insert(&client).await.unwrap();
+    line(2, "cake").insert(&client).await.unwrap();
+
+    assert_eq!(
+        OrderLine::select_by_pk(&client, &(7, 2)).await.unwrap(),
+        Some(line(2, "cake"))
+    );
+    assert_eq!(
+        OrderLine::select_by_pk(&client, &(8, 2)).await.unwrap(),
+        None
+    );
+    assert!(OrderLine::delete(&client, &(7, 1)).await.unwrap());
+    assert_eq!(
+        OrderLine::find_where(&client, "order_id = ?", &[&7_i64])
+            .await
+            .unwrap(),
+        [line(2, "cake")]
+    );
+}
+
+#[tokio::test]
+async fn keyword_columns_are_quoted() {
+    assert_eq!(
+        Setting::TABLE.create_table_sql(),
+        "CREATE TABLE IF NOT EXISTS Setting (\"KEY\" VARCHAR, \"VALUE\" VARCHAR, \
+         sort_order INT NOT NULL, \"GROUP\" VARCHAR NOT NULL, PRIMARY KEY (\"KEY\"))"
+    );
+    assert_eq!(
+        Setting::TABLE.create_index_sql(),
+        ["CREATE INDEX IF NOT EXISTS Setting_value_idx ON Setting (\"VALUE\")"]
+    );
+
+    let emulator = Emulator::start();
+    let client = emulator.client();
+    Setting::create_table(&client).await.unwrap();
+    let setting = |key: &str, value: Option<&str>, order| Setting {
+        key: key.to_string(),
+        value: value.map(Into::into),
+        order,
+        section: "ui".to_string(),
+    };
+    setting("theme", Some("dark"), 1)
+        .insert(&client)
+        .await
+        .unwrap();
+    setting("font", None, 2).upsert(&client).await.unwrap();
+    assert!(
+        setting("font", Some("mono"), 3)
+            .update(&client)
+            .await
+            .unwrap()
+    );
+
+    assert_eq!(
+        Setting::select_by_pk(&client, &"font".to_string())
+            .await
+            .unwrap(),
+        Some(setting("font", Some("mono"), 3))
+    );
+    assert_eq!(
+        Setting::find_where(&client, "\"GROUP\" = ? ORDER BY sort_order", &[&"ui"])
+            .await
+            .unwrap(),
+        [
+            setting("theme", Some("dark"), 1),
+            setting("font", Some("mono"), 3)
+        ]
+    );
+    assert!(
+        Setting::delete(&client, &"theme".to_string())
+            .await
+            .unwrap()
+    );
+}
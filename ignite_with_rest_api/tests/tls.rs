@@ -0,0 +1,135 @@
+use std::path::PathBuf;
+use std::pin::Pin;
+
+use ignite_with_rest_api::{IgniteRestClient, IgniteRestError, RetryPolicy, TlsConfig};
+use openssl::ssl::{Ssl, SslAcceptor, SslFiletype, SslMethod, SslVerifyMode};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_openssl::SslStream;
+
+/// Certificates from `tests/fixtures/tls/generate.sh`.
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/tls")
+        .join(name)
+}
+
+/// HTTPS endpoint that answers every request like `cmd=version`, optionally
+/// requiring a client certificate signed by the test CA, as Jetty does with
+/// `needClientAuth`.
+async fn start_server(need_client_auth: bool) -> String {
+    let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls_server()).unwrap();
+    acceptor
+        .set_private_key_file(fixture("server.key"), SslFiletype::PEM)
+        .unwrap();
+    acceptor
+        .set_certificate_chain_file(fixture("server.pem"))
+        .unwrap();
+    if need_client_auth {
+        acceptor.set_ca_file(fixture("ca.pem")).unwrap();
+        acceptor.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+    }
+    let acceptor = acceptor.build();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((tcp, _)) = listener.accept().await {
+            let ssl = Ssl::new(acceptor.context()).unwrap();
+            tokio::spawn(async move {
+                let mut stream = SslStream::new(ssl, tcp).unwrap();
+                if Pin::new(&mut stream).accept().await.is_err() {
+                    return;
+                }
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(read) => request.extend_from_slice(&buf[..read]),
+                    }
+                }
+                let body =
+                    r#"{"successStatus":0,"response":"2.16.0","error":null,"sessionToken":null}"#;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            });
+        }
+    });
+    format!("https://localhost:{port}/ignite")
+}
+
+fn client(url: &str, tls: TlsConfig) -> IgniteRestClient {
+    IgniteRestClient::builder()
+        .base_url(url)
+        .tls(tls)
+        .retry(RetryPolicy::none())
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn client_certificates_are_presented_from_pem_files() {
+    let url = start_server(true).await;
+    let tls = TlsConfig::new()
+        .ca_certificate(fixture("ca.pem"))
+        .client_pem(fixture("client.pem"), fixture("client.key"));
+
+    assert_eq!(client(&url, tls).version().await.unwrap(), "2.16.0");
+}
+
+#[tokio::test]
+async fn client_certificates_are_presented_from_pkcs12() {
+    let url = start_server(true).await;
+    let tls = TlsConfig::new()
+        .ca_certificate(fixture("ca.pem"))
+        .client_pkcs12(fixture("client.p12"), "changeit");
+
+    assert_eq!(client(&url, tls).version().await.unwrap(), "2.16.0");
+}
+
+#[tokio::test]
+async fn connectors_that_need_client_auth_reject_anonymous_clients() {
+    let url = start_server(true).await;
+    let tls = TlsConfig::new().ca_certificate(fixture("ca.pem"));
+
+    let err = client(&url, tls).version().await.unwrap_err();
+
+    assert!(matches!(err, IgniteRestError::Transport(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn unknown_server_certificates_are_only_accepted_when_asked() {
+    let url = start_server(false).await;
+
+    let err = client(&url, TlsConfig::new()).version().await.unwrap_err();
+    assert!(matches!(err, IgniteRestError::Transport(_)), "{err:?}");
+
+    let insecure = TlsConfig::new().insecure_skip_verify(true);
+    assert_eq!(client(&url, insecure).version().await.unwrap(), "2.16.0");
+}
+
+#[test]
+fn invalid_tls_settings_fail_the_build() {
+    let missing = IgniteRestClient::builder()
+        .base_url("https://localhost:8443/ignite")
+        .tls(TlsConfig::new().ca_certificate(fixture("missing.pem")))
+        .build()
+        .unwrap_err();
+    assert!(
+        matches!(&missing, IgniteRestError::Config(message) if message.contains("missing.pem")),
+        "{missing:?}"
+    );
+
+    let plain = IgniteRestClient::builder()
+        .base_url("http://localhost:8080/ignite")
+        .tls(TlsConfig::new().ca_certificate(fixture("ca.pem")))
+        .build()
+        .unwrap_err();
+    assert!(matches!(plain, IgniteRestError::Config(_)), "{plain:?}");
+}